@@ -0,0 +1,153 @@
+use anyhow::{bail, Result};
+use markdown::mdast::{Code, Node};
+use std::io::Write;
+
+/// The language of answer blocks whose answers are compared ignoring the case and the amount of
+/// whitespace.
+pub const CASE_INSENSITIVE_LANG: &str = "answer-ci";
+
+/// The language of the answer block of a question which also has other code blocks, for example
+/// an illustrative snippet in the question.
+pub const TAGGED_ANSWER_LANG: &str = "answer";
+
+/// The languages of code blocks with the answer of a markdown exercise.
+pub const ANSWER_LANGS: [&str; 3] = ["math", CASE_INSENSITIVE_LANG, TAGGED_ANSWER_LANG];
+
+/// The languages of answers which only have to parse. A block in one of them is only an answer
+/// block if it declares the comparator `parses` (```` ```rust parses ````). Otherwise, it is an
+/// example like a snippet in the explanation of the question.
+pub const PARSED_ANSWER_LANGS: [&str; 2] = ["rust", "circom"];
+
+fn is_answer_block(code: &Code) -> bool {
+    let Some(lang) = code.lang.as_deref() else {
+        return false;
+    };
+
+    ANSWER_LANGS.contains(&lang)
+        || (PARSED_ANSWER_LANGS.contains(&lang)
+            && code.meta.as_deref().map(str::trim) == Some("parses"))
+}
+
+/// The answer block among the children of a question: The block with the language `answer` or
+/// else the first block with one of the `ANSWER_LANGS` or a `PARSED_ANSWER_LANGS` block which
+/// declares the comparator `parses`.
+/// Fails if more than one block has the language `answer`.
+pub fn answer_block(children: &[Node]) -> Result<Option<&Code>> {
+    let mut code_blocks = children.iter().filter_map(|child| match child {
+        Node::Code(code) if is_answer_block(code) => Some(code),
+        _ => None,
+    });
+    let first = code_blocks.next();
+
+    let mut tagged = first
+        .into_iter()
+        .chain(code_blocks)
+        .filter(|code| code.lang.as_deref() == Some(TAGGED_ANSWER_LANG));
+    let Some(tagged_answer) = tagged.next() else {
+        return Ok(first);
+    };
+
+    let n_tagged = 1 + tagged.count();
+    if n_tagged > 1 {
+        bail!("A question can only have one ```{TAGGED_ANSWER_LANG} block but it has {n_tagged}. Tag only the block with the answer");
+    }
+
+    Ok(Some(tagged_answer))
+}
+
+// Rust answers can be whole items like functions or only statements and expressions.
+fn parse_rust(code: &str) -> syn::Result<()> {
+    if syn::parse_file(code).is_ok() {
+        return Ok(());
+    }
+
+    syn::parse_str::<syn::Block>(&format!("{{\n{code}\n}}")).map(|_| ())
+}
+
+/// Check that the `code` of an answer block parses in its language.
+/// The error of the parser or compiler is appended to the `output` buffer.
+pub fn answer_parses(lang: &str, code: &str, output: &mut Vec<u8>) -> Result<bool> {
+    match lang {
+        "rust" => match parse_rust(code) {
+            Ok(()) => Ok(true),
+            Err(e) => {
+                writeln!(output, "The answer isn't valid Rust code: {e}")?;
+                Ok(false)
+            }
+        },
+        #[cfg(feature = "circom")]
+        "circom" => crate::circom::check_circuit(code, output),
+        #[cfg(not(feature = "circom"))]
+        "circom" => Err(crate::exercise::CIRCOM_TYPE.disabled_err()),
+        _ => bail!("Answers in `{lang}` blocks can't be checked for valid syntax"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equivalent_rust_snippets() {
+        let mut output = Vec::new();
+        assert!(answer_parses(
+            "rust",
+            "fn square(x: u64) -> u64 {\n    x * x\n}",
+            &mut output,
+        )
+        .unwrap());
+        assert!(answer_parses("rust", "let square = |x: u64| x.pow(2);", &mut output).unwrap());
+        assert!(output.is_empty());
+
+        assert!(!answer_parses("rust", "fn square(x: u64) -> u64 { x * }", &mut output).unwrap());
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .starts_with("The answer isn't valid Rust code"));
+
+        assert!(answer_parses("math", "42", &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn tagged_answer_block() {
+        let children = |content| {
+            let ast = markdown::to_mdast(content, &markdown::ParseOptions::default()).unwrap();
+            ast.children().unwrap().clone()
+        };
+
+        let question =
+            children("# What does it print?\n\n```rust\nprintln!(\"{}\", 1 + 1);\n```\n\n```answer\n2\n```\n");
+        assert_eq!(answer_block(&question).unwrap().unwrap().value, "2");
+
+        // Without a tagged block, the first answer block is the answer.
+        let question = children("# Question\n\n```math\n1\n```\n\n```math\n2\n```\n");
+        assert_eq!(answer_block(&question).unwrap().unwrap().value, "1");
+
+        let question = children("# Question\n\n```answer\n1\n```\n\n```answer\n2\n```\n");
+        assert!(answer_block(&question)
+            .unwrap_err()
+            .to_string()
+            .contains("only have one ```answer block but it has 2"));
+
+        assert!(answer_block(&children("# Question\n")).unwrap().is_none());
+    }
+
+    #[test]
+    fn code_example_before_answer() {
+        let question = markdown::to_mdast(
+            "# What does it print?\n\n```rust\nprintln!(\"{}\", 2 * 3);\n```\n\n```math\n6\n```\n",
+            &markdown::ParseOptions::default(),
+        )
+        .unwrap();
+        let children = question.children().unwrap();
+        assert_eq!(answer_block(children).unwrap().unwrap().value, "6");
+
+        let question = markdown::to_mdast(
+            "# Write a function\n\n```circom\ntemplate A() {}\n```\n\n```rust parses\nfn f() {}\n```\n",
+            &markdown::ParseOptions::default(),
+        )
+        .unwrap();
+        let answer = answer_block(question.children().unwrap()).unwrap().unwrap();
+        assert_eq!(answer.lang.as_deref(), Some("rust"));
+        assert_eq!(answer.value, "fn f() {}");
+    }
+}
@@ -1,15 +1,16 @@
 use anyhow::{bail, Context, Result};
-use crossterm::style::Stylize;
 use serde::Deserialize;
 use std::{
     fs::{self, File},
     io::{Read, StdoutLock, Write},
     path::{Path, PathBuf},
     process::{Command, Stdio},
+    sync::Mutex,
 };
 
 use crate::{
     clear_terminal,
+    color::Stylize,
     embedded::EMBEDDED_FILES,
     exercise::{Exercise, RunnableExercise, OUTPUT_CAPACITY},
     info_file::ExerciseInfo,
@@ -17,6 +18,11 @@ use crate::{
 };
 
 const STATE_FILE_NAME: &str = ".zklings-state.txt";
+// The previous state file. Used if the state file is missing or corrupt.
+const STATE_BACKUP_FILE_NAME: &str = ".zklings-state.txt.bak";
+// The new state is written to this file first and then renamed to the state file.
+const STATE_TMP_FILE_NAME: &str = ".zklings-state.txt.tmp";
+const STATE_FILE_HEADER: &[u8] = b"DON'T EDIT THIS FILE!";
 const BAD_INDEX_ERR: &str = "The current exercise index is higher than the number of exercises";
 
 #[must_use]
@@ -34,6 +40,14 @@ pub enum StateFileStatus {
     NotRead,
 }
 
+// The result of loading one state file.
+#[derive(PartialEq, Eq, Debug)]
+enum StateLoad {
+    Loaded,
+    Missing,
+    Corrupt,
+}
+
 // Parses parts of the output of `cargo metadata`.
 #[derive(Deserialize)]
 struct CargoMetadata {
@@ -70,44 +84,70 @@ pub struct AppState {
     official_exercises: bool,
     // Cargo's target directory.
     target_dir: PathBuf,
+    // Maximum number of exercises whose hint can be shown in an assessment.
+    hint_budget: Option<u32>,
 }
 
 impl AppState {
-    // Update the app state from the state file.
-    fn update_from_file(&mut self) -> StateFileStatus {
+    // Update the app state from the state file at `path`. The state is only changed if the file
+    // is valid.
+    fn load_state_file(&mut self, path: &str) -> StateLoad {
         self.file_buf.clear();
-        self.n_done = 0;
 
-        if File::open(STATE_FILE_NAME)
+        if File::open(path)
             .and_then(|mut file| file.read_to_end(&mut self.file_buf))
             .is_err()
         {
-            return StateFileStatus::NotRead;
+            return StateLoad::Missing;
         }
 
+        self.parse_state()
+    }
+
+    // Update the app state from the content of a state file in `Self::file_buf`.
+    fn parse_state(&mut self) -> StateLoad {
         // See `Self::write` for more information about the file format.
-        let mut lines = self.file_buf.split(|c| *c == b'\n').skip(2);
+        let mut lines = self.file_buf.split(|c| *c == b'\n');
+
+        if lines.next() != Some(STATE_FILE_HEADER) || lines.next() != Some(&b""[..]) {
+            return StateLoad::Corrupt;
+        }
 
         let Some(current_exercise_name) = lines.next() else {
-            return StateFileStatus::NotRead;
+            return StateLoad::Corrupt;
         };
 
         if current_exercise_name.is_empty() || lines.next().is_none() {
-            return StateFileStatus::NotRead;
+            return StateLoad::Corrupt;
         }
 
         let mut done_exercises = hashbrown::HashSet::with_capacity(self.exercises.len());
 
-        for done_exerise_name in lines {
+        for done_exerise_name in lines.by_ref() {
             if done_exerise_name.is_empty() {
                 break;
             }
             done_exercises.insert(done_exerise_name);
         }
 
+        // State files written before skipping was supported end after the done exercises.
+        let skipped_exercises = lines
+            .by_ref()
+            .take_while(|name| !name.is_empty())
+            .collect::<hashbrown::HashSet<_>>();
+
+        // State files written before hints were tracked end after the skipped exercises.
+        let hinted_exercises = lines
+            .filter(|name| !name.is_empty())
+            .collect::<hashbrown::HashSet<_>>();
+
+        self.n_done = 0;
         for (ind, exercise) in self.exercises.iter_mut().enumerate() {
-            if done_exercises.contains(exercise.name.as_bytes()) {
-                exercise.done = true;
+            exercise.done = done_exercises.contains(exercise.name.as_bytes());
+            exercise.skipped =
+                !exercise.done && skipped_exercises.contains(exercise.name.as_bytes());
+            exercise.hint_shown = hinted_exercises.contains(exercise.name.as_bytes());
+            if exercise.done {
                 self.n_done += 1;
             }
 
@@ -116,12 +156,47 @@ impl AppState {
             }
         }
 
-        StateFileStatus::Read
+        StateLoad::Loaded
+    }
+
+    // Update the app state from the state file.
+    // Falls back to the backup of the previous state if the state file is missing or corrupt,
+    // for example because the process was killed while writing it.
+    fn update_from_file(&mut self) -> StateFileStatus {
+        let state_load = self.load_state_file(STATE_FILE_NAME);
+        if state_load == StateLoad::Loaded {
+            return StateFileStatus::Read;
+        }
+
+        match (state_load, self.load_state_file(STATE_BACKUP_FILE_NAME)) {
+            (_, StateLoad::Loaded) => {
+                eprintln!(
+                    "{}",
+                    format!(
+                        "The state file {STATE_FILE_NAME} is missing or corrupt. Restored the progress from the backup {STATE_BACKUP_FILE_NAME}"
+                    )
+                    .yellow(),
+                );
+                StateFileStatus::Read
+            }
+            (StateLoad::Corrupt, _) => {
+                eprintln!(
+                    "{}",
+                    format!(
+                        "The state file {STATE_FILE_NAME} is corrupt and there is no valid backup. Starting without progress"
+                    )
+                    .yellow(),
+                );
+                StateFileStatus::NotRead
+            }
+            _ => StateFileStatus::NotRead,
+        }
     }
 
     pub fn new(
         exercise_infos: Vec<ExerciseInfo>,
         final_message: String,
+        hint_budget: Option<u32>,
     ) -> Result<(Self, StateFileStatus)> {
         let target_dir = parse_target_dir()?;
 
@@ -138,6 +213,15 @@ impl AppState {
                 let hint = exercise_info.hint.trim().to_owned();
 
                 let ext = exercise_info.ext.leak();
+                let ptau = exercise_info.ptau.map(|ptau| &*ptau.leak());
+                let field_modulus = exercise_info
+                    .field_modulus
+                    .map(|field_modulus| &*field_modulus.leak());
+                let prime = exercise_info.prime.map(|prime| &*prime.leak());
+                let prompt = exercise_info.prompt.map(|prompt| &*prompt.leak());
+                let rust_verifier = exercise_info
+                    .rust_verifier
+                    .map(|rust_verifier| &*rust_verifier.leak());
 
                 Exercise {
                     dir,
@@ -146,9 +230,34 @@ impl AppState {
                     path,
                     test: exercise_info.test,
                     strict_clippy: exercise_info.strict_clippy,
+                    no_warnings: exercise_info.no_warnings,
+                    report_clippy_warnings: exercise_info.report_clippy_warnings,
+                    max_memory: exercise_info.max_memory,
+                    max_cpu_time: exercise_info.max_cpu_time,
+                    external_proof: exercise_info.external_proof,
+                    prove: exercise_info.prove,
+                    check_vkey: exercise_info.check_vkey,
+                    ptau,
+                    max_verify_time: exercise_info.max_verify_time,
+                    max_constraints: exercise_info.max_constraints,
+                    rust_verifier,
+                    input_spec: exercise_info.input_spec,
+                    backends: exercise_info.backends,
+                    proving_system: exercise_info.proving_system,
+                    prime,
+                    min_security_bits: exercise_info.min_security_bits,
+                    field_modulus,
+                    prompt,
+                    required_patterns: exercise_info.required_patterns,
+                    forbidden_patterns: exercise_info.forbidden_patterns,
+                    input_files: exercise_info.input_files,
+                    normalize: exercise_info.normalize,
                     hint,
                     // Updated in `Self::update_from_file`.
                     done: false,
+                    skipped: false,
+                    hint_shown: false,
+                    build_cache: Mutex::new(None),
                 }
             })
             .collect::<Vec<_>>();
@@ -161,6 +270,7 @@ impl AppState {
             file_buf: Vec::with_capacity(2048),
             official_exercises: !Path::new("info.toml").exists(),
             target_dir,
+            hint_budget,
         };
 
         let state_file_status = slf.update_from_file();
@@ -200,12 +310,16 @@ impl AppState {
     // - The third line is the name of the current exercise. It must end with `\n` even if there
     // are no done exercises.
     // - The fourth line is an empty line.
-    // - All remaining lines are the names of done exercises.
-    fn write(&mut self) -> Result<()> {
+    // - The following lines are the names of done exercises.
+    // - If any exercise is skipped or its hint was shown, an empty line follows and the next lines
+    // are the names of skipped exercises.
+    // - If the hint of any exercise was shown, an empty line follows and all remaining lines are
+    // the names of these exercises.
+    fn write_state_buf(&mut self) {
         self.file_buf.clear();
 
-        self.file_buf
-            .extend_from_slice(b"DON'T EDIT THIS FILE!\n\n");
+        self.file_buf.extend_from_slice(STATE_FILE_HEADER);
+        self.file_buf.extend_from_slice(b"\n\n");
         self.file_buf
             .extend_from_slice(self.current_exercise().name.as_bytes());
         self.file_buf.push(b'\n');
@@ -217,7 +331,44 @@ impl AppState {
             }
         }
 
-        fs::write(STATE_FILE_NAME, &self.file_buf)
+        let any_hint_shown = self.exercises.iter().any(|exercise| exercise.hint_shown);
+        if any_hint_shown || self.exercises.iter().any(|exercise| exercise.skipped) {
+            self.file_buf.push(b'\n');
+
+            for exercise in &self.exercises {
+                if exercise.skipped {
+                    self.file_buf.push(b'\n');
+                    self.file_buf.extend_from_slice(exercise.name.as_bytes());
+                }
+            }
+        }
+
+        if any_hint_shown {
+            self.file_buf.push(b'\n');
+
+            for exercise in &self.exercises {
+                if exercise.hint_shown {
+                    self.file_buf.push(b'\n');
+                    self.file_buf.extend_from_slice(exercise.name.as_bytes());
+                }
+            }
+        }
+    }
+
+    fn write(&mut self) -> Result<()> {
+        self.write_state_buf();
+
+        // Write atomically by renaming to never leave a partially written state file behind.
+        fs::write(STATE_TMP_FILE_NAME, &self.file_buf)
+            .with_context(|| format!("Failed to write the state file {STATE_TMP_FILE_NAME}"))?;
+
+        if Path::new(STATE_FILE_NAME).exists() {
+            fs::copy(STATE_FILE_NAME, STATE_BACKUP_FILE_NAME).with_context(|| {
+                format!("Failed to back up the state file to {STATE_BACKUP_FILE_NAME}")
+            })?;
+        }
+
+        fs::rename(STATE_TMP_FILE_NAME, STATE_FILE_NAME)
             .with_context(|| format!("Failed to write the state file {STATE_FILE_NAME}"))?;
 
         Ok(())
@@ -237,18 +388,98 @@ impl AppState {
         self.write()
     }
 
-    pub fn set_current_exercise_by_name(&mut self, name: &str) -> Result<()> {
+    pub fn exercise_ind_by_name(&self, name: &str) -> Result<usize> {
         // O(N) is fine since this method is used only once until the program exits.
         // Building a hashmap would have more overhead.
-        self.current_exercise_ind = self
-            .exercises
+        self.exercises
             .iter()
             .position(|exercise| exercise.name == name)
-            .with_context(|| format!("No exercise found for '{name}'!"))?;
+            .with_context(|| format!("No exercise found for '{name}'!"))
+    }
+
+    pub fn set_current_exercise_by_name(&mut self, name: &str) -> Result<()> {
+        self.current_exercise_ind = self.exercise_ind_by_name(name)?;
 
         self.write()
     }
 
+    #[inline]
+    pub fn hint_budget(&self) -> Option<u32> {
+        self.hint_budget
+    }
+
+    /// The number of exercises whose hint was shown.
+    pub fn hints_used(&self) -> u32 {
+        self.exercises
+            .iter()
+            .filter(|exercise| exercise.hint_shown)
+            .count() as u32
+    }
+
+    // Mark the hint of the exercise as shown if the hint budget allows it.
+    // Returns `false` if the budget is exhausted.
+    fn take_hint(&mut self, exercise_ind: usize) -> bool {
+        let hints_used = self.hints_used();
+        let exercise = &mut self.exercises[exercise_ind];
+        if exercise.hint_shown {
+            return true;
+        }
+
+        if self.hint_budget.is_some_and(|budget| hints_used >= budget) {
+            return false;
+        }
+
+        exercise.hint_shown = true;
+        true
+    }
+
+    /// Mark the hint of the exercise as shown and return it.
+    /// Returns `None` in an assessment if the hint budget is exhausted.
+    /// Showing the same hint again doesn't count against the budget.
+    pub fn reveal_hint(&mut self, exercise_ind: usize) -> Result<Option<&str>> {
+        if exercise_ind >= self.exercises.len() {
+            bail!(BAD_INDEX_ERR);
+        }
+
+        let was_shown = self.exercises[exercise_ind].hint_shown;
+        if !self.take_hint(exercise_ind) {
+            return Ok(None);
+        }
+        if !was_shown {
+            self.write()?;
+        }
+
+        Ok(Some(&self.exercises[exercise_ind].hint))
+    }
+
+    // A hint which wasn't requested must not count against the hint budget of an assessment.
+    fn shows_hint_on_failure(&self, exercise_ind: usize) -> bool {
+        let exercise = &self.exercises[exercise_ind];
+        !exercise.hint.trim().is_empty() && (self.hint_budget.is_none() || exercise.hint_shown)
+    }
+
+    /// The hint to show after the exercise failed with `--hint-on-failure`.
+    /// `None` if the hint is empty or if it wasn't shown before in an assessment.
+    pub fn hint_on_failure(&mut self, exercise_ind: usize) -> Result<Option<&str>> {
+        if exercise_ind >= self.exercises.len() {
+            bail!(BAD_INDEX_ERR);
+        }
+
+        if !self.shows_hint_on_failure(exercise_ind) {
+            return Ok(None);
+        }
+
+        self.reveal_hint(exercise_ind)
+    }
+
+    /// The polite refusal to show a hint after the hint budget is exhausted.
+    pub fn hint_budget_exhausted_msg(&self) -> String {
+        format!(
+            "You have used all {} hints available in this assessment. You can do it without more hints!",
+            self.hint_budget.unwrap_or_default(),
+        )
+    }
+
     pub fn set_pending(&mut self, exercise_ind: usize) -> Result<()> {
         let exercise = self
             .exercises
@@ -293,6 +524,32 @@ impl AppState {
         Ok(())
     }
 
+    /// The exercise file has changes which resetting it would discard.
+    /// Official exercises are compared with the original file in the binary.
+    /// Third-party exercises are compared with the last commit in Git.
+    /// Without Git or outside of a repository, the exercise is treated as modified to ask before
+    /// resetting it.
+    pub fn is_exercise_modified(&self, exercise_ind: usize) -> Result<bool> {
+        let exercise = self.exercises.get(exercise_ind).context(BAD_INDEX_ERR)?;
+        if self.official_exercises {
+            return Ok(EMBEDDED_FILES.exercise_differs(exercise_ind, exercise.path));
+        }
+
+        let output = Command::new("git")
+            .arg("status")
+            .arg("--porcelain")
+            .arg("--")
+            .arg(exercise.path)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output();
+
+        match output {
+            Ok(output) if output.status.success() => Ok(!output.stdout.is_empty()),
+            _ => Ok(true),
+        }
+    }
+
     pub fn reset_current_exercise(&mut self) -> Result<&'static str> {
         self.set_pending(self.current_exercise_ind)?;
         let exercise = self.current_exercise();
@@ -336,6 +593,34 @@ impl AppState {
         }
     }
 
+    // Mark the current pending exercise as skipped and move on to the next pending exercise.
+    // Returns `false` if there is no other pending exercise to move on to.
+    fn skip_current(&mut self) -> bool {
+        let Some(ind) = self.next_pending_exercise_ind() else {
+            return false;
+        };
+
+        let exercise = &mut self.exercises[self.current_exercise_ind];
+        if !exercise.done {
+            exercise.skipped = true;
+        }
+        self.current_exercise_ind = ind;
+
+        true
+    }
+
+    /// Move on to the next pending exercise without marking the current one as done.
+    /// The current exercise is marked as skipped until it is done.
+    pub fn skip_current_exercise(&mut self) -> Result<ExercisesProgress> {
+        if !self.skip_current() {
+            return Ok(ExercisesProgress::CurrentPending);
+        }
+
+        self.write()?;
+
+        Ok(ExercisesProgress::NewPending)
+    }
+
     /// Official exercises: Dump the solution file form the binary and return its path.
     /// Third-party exercises: Check if a solution file exists and return its path in that case.
     pub fn current_solution_path(&self) -> Result<Option<String>> {
@@ -377,6 +662,7 @@ impl AppState {
         let exercise = &mut self.exercises[self.current_exercise_ind];
         if !exercise.done {
             exercise.done = true;
+            exercise.skipped = false;
             self.n_done += 1;
         }
 
@@ -393,7 +679,7 @@ impl AppState {
             write!(writer, "Running {exercise} ... ")?;
             writer.flush()?;
 
-            let success = exercise.run_exercise(&mut output, &self.target_dir)?;
+            let success = exercise.run_exercise(&mut output, &self.target_dir, None)?;
             if !success {
                 writeln!(writer, "{}\n", "FAILED".red())?;
 
@@ -465,29 +751,25 @@ mod tests {
     use super::*;
 
     fn dummy_exercise() -> Exercise {
-        Exercise {
-            dir: None,
-            name: "0",
-            ext: "rs",
-            path: "exercises/0.rs",
-            test: false,
-            strict_clippy: false,
-            hint: String::new(),
-            done: false,
-        }
+        Exercise::new("0", "rs", "exercises/0.rs")
     }
 
-    #[test]
-    fn next_pending_exercise() {
-        let mut app_state = AppState {
+    fn dummy_app_state(n_exercises: usize) -> AppState {
+        AppState {
             current_exercise_ind: 0,
-            exercises: vec![dummy_exercise(), dummy_exercise(), dummy_exercise()],
+            exercises: (0..n_exercises).map(|_| dummy_exercise()).collect(),
             n_done: 0,
             final_message: String::new(),
             file_buf: Vec::new(),
             official_exercises: true,
             target_dir: PathBuf::new(),
-        };
+            hint_budget: None,
+        }
+    }
+
+    #[test]
+    fn next_pending_exercise() {
+        let mut app_state = dummy_app_state(3);
 
         let mut assert = |done: [bool; 3], expected: [Option<usize>; 3]| {
             for (exercise, done) in app_state.exercises.iter_mut().zip(done) {
@@ -512,4 +794,131 @@ mod tests {
         assert([false, true, false], [Some(2), Some(2), Some(0)]);
         assert([false, false, true], [Some(1), Some(0), Some(0)]);
     }
+
+    #[test]
+    fn modified_outside_of_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("0.rs");
+        fs::write(&path, "fn main() {}\n").unwrap();
+
+        let mut app_state = dummy_app_state(1);
+        app_state.official_exercises = false;
+        app_state.exercises[0].path = path.to_str().unwrap().to_owned().leak();
+        assert!(app_state.is_exercise_modified(0).unwrap());
+    }
+
+    #[test]
+    fn skip_then_return() {
+        let mut app_state = dummy_app_state(3);
+
+        assert!(app_state.skip_current());
+        assert_eq!(app_state.current_exercise_ind, 1);
+        assert!(app_state.exercises[0].skipped);
+
+        // Finishing the next exercise leads to the next pending one, not back to the skipped one.
+        app_state.exercises[1].done = true;
+        assert_eq!(app_state.next_pending_exercise_ind(), Some(2));
+
+        // Skipping the last pending exercise wraps around to the skipped one.
+        app_state.current_exercise_ind = 2;
+        assert!(app_state.skip_current());
+        assert_eq!(app_state.current_exercise_ind, 0);
+        assert!(app_state.exercises[2].skipped);
+
+        // Nothing to move on to if the skipped exercises are the only pending ones.
+        app_state.exercises[2].done = true;
+        app_state.exercises[2].skipped = false;
+        assert!(!app_state.skip_current());
+        assert_eq!(app_state.current_exercise_ind, 0);
+        assert!(app_state.exercises[0].skipped);
+    }
+
+    #[test]
+    fn truncated_state_file() {
+        let mut app_state = dummy_app_state(2);
+        app_state.exercises[1].done = true;
+        app_state.n_done = 1;
+
+        for truncated in [
+            &b""[..],
+            b"DON'T EDIT",
+            b"DON'T EDIT THIS FILE!\n",
+            b"DON'T EDIT THIS FILE!\n\n",
+            b"DON'T EDIT THIS FILE!\n\n0",
+            b"\0\0\0\0\0\0\0\0",
+        ] {
+            app_state.file_buf = truncated.to_vec();
+            assert_eq!(app_state.parse_state(), StateLoad::Corrupt, "{truncated:?}");
+            // The state isn't changed by a corrupt file.
+            assert!(app_state.exercises[1].done);
+            assert_eq!(app_state.n_done, 1);
+        }
+
+        app_state.file_buf = b"DON'T EDIT THIS FILE!\n\n0\n".to_vec();
+        assert_eq!(app_state.parse_state(), StateLoad::Loaded);
+        assert_eq!(app_state.n_done, 0);
+        assert!(!app_state.exercises[1].done);
+    }
+
+    #[test]
+    fn hint_budget() {
+        let mut app_state = dummy_app_state(3);
+        for (ind, exercise) in app_state.exercises.iter_mut().enumerate() {
+            exercise.name = ["0", "1", "2"][ind];
+        }
+        app_state.hint_budget = Some(1);
+
+        assert!(app_state.take_hint(1));
+        // Showing the same hint again is free.
+        assert!(app_state.take_hint(1));
+        assert!(!app_state.take_hint(0));
+        assert!(!app_state.exercises[0].hint_shown);
+        assert_eq!(app_state.hints_used(), 1);
+
+        // The hint usage is kept in the state file.
+        app_state.exercises[2].skipped = true;
+        app_state.write_state_buf();
+        let file_buf = app_state.file_buf.clone();
+        let mut restored = dummy_app_state(3);
+        for (ind, exercise) in restored.exercises.iter_mut().enumerate() {
+            exercise.name = ["0", "1", "2"][ind];
+        }
+        restored.hint_budget = Some(1);
+        restored.file_buf = file_buf;
+        assert_eq!(restored.parse_state(), StateLoad::Loaded);
+        assert!(restored.exercises[1].hint_shown);
+        assert!(restored.exercises[2].skipped);
+        assert!(!restored.take_hint(2));
+
+        // Without skipped exercises.
+        app_state.exercises[2].skipped = false;
+        app_state.write_state_buf();
+        restored.file_buf = app_state.file_buf.clone();
+        assert_eq!(restored.parse_state(), StateLoad::Loaded);
+        assert!(restored.exercises[1].hint_shown);
+        assert!(!restored.exercises[2].skipped);
+
+        // Without a budget, hints are unlimited.
+        app_state.hint_budget = None;
+        assert!(app_state.take_hint(0));
+        assert!(app_state.take_hint(2));
+        assert_eq!(app_state.hints_used(), 3);
+    }
+
+    #[test]
+    fn hint_on_failure() {
+        let mut app_state = dummy_app_state(3);
+        app_state.exercises[0].hint = String::from("Look at the constraint");
+        app_state.exercises[1].hint = String::from("  ");
+        app_state.exercises[2].hint = String::from("Use `<==`");
+
+        assert!(app_state.shows_hint_on_failure(0));
+        assert!(!app_state.shows_hint_on_failure(1));
+
+        // Only hints which were already requested are shown in an assessment.
+        app_state.hint_budget = Some(1);
+        app_state.exercises[2].hint_shown = true;
+        assert!(!app_state.shows_hint_on_failure(0));
+        assert!(app_state.shows_hint_on_failure(2));
+    }
 }
@@ -24,6 +24,24 @@ pub fn bins_start_end_ind(cargo_toml: &str) -> Result<(usize, usize)> {
     Ok((start_ind, end_ind))
 }
 
+// Path of the Rust verifier `name` of a Circom exercise in the directory `dir` inside the
+// directory `exercises/` or `solutions/`.
+fn rust_verifier_path(root_dir: &str, dir: Option<&str>, name: &str) -> String {
+    match dir {
+        Some(dir) => format!("{root_dir}/{dir}/{name}.rs"),
+        None => format!("{root_dir}/{name}.rs"),
+    }
+}
+
+fn append_bin(buf: &mut Vec<u8>, name: &str, exercise_path_prefix: &[u8], path: &str) {
+    buf.extend_from_slice(b"  { name = \"");
+    buf.extend_from_slice(name.as_bytes());
+    buf.extend_from_slice(b"\", path = \"");
+    buf.extend_from_slice(exercise_path_prefix);
+    buf.extend_from_slice(path.as_bytes());
+    buf.extend_from_slice(b"\" },\n");
+}
+
 /// Generate and append the content of the `bin` list in `Cargo.toml`.
 /// The `exercise_path_prefix` is the prefix of the `path` field of every list entry.
 pub fn append_bins(
@@ -47,6 +65,22 @@ pub fn append_bins(
         buf.extend_from_slice(exercise_info.ext.as_bytes());
         buf.extend_from_slice(b"\" },\n");
 
+        let dir = exercise_info.dir.as_deref();
+        if let Some(rust_verifier) = &exercise_info.rust_verifier {
+            let path = rust_verifier_path("exercises", dir, rust_verifier);
+            append_bin(buf, rust_verifier, exercise_path_prefix, &path);
+
+            let sol_path = rust_verifier_path("solutions", dir, rust_verifier);
+            if Path::new(&sol_path).exists() {
+                append_bin(
+                    buf,
+                    &format!("{rust_verifier}_sol"),
+                    exercise_path_prefix,
+                    &sol_path,
+                );
+            }
+        }
+
         let sol_path = exercise_info.sol_path();
         if !Path::new(&sol_path).exists() {
             continue;
@@ -110,22 +144,14 @@ mod tests {
     fn test_bins() {
         let exercise_infos = [
             ExerciseInfo {
-                name: String::from("1"),
-                ext: String::from("rs"),
-                dir: None,
                 test: true,
                 strict_clippy: true,
-                hint: String::new(),
-                skip_check_unsolved: false,
+                ..ExerciseInfo::new("1", "rs")
             },
             ExerciseInfo {
-                name: String::from("2"),
-                ext: String::from("rs"),
                 dir: Some(String::from("d")),
-                test: false,
-                strict_clippy: false,
-                hint: String::new(),
-                skip_check_unsolved: false,
+                rust_verifier: Some(String::from("2_verifier")),
+                ..ExerciseInfo::new("2", "rs")
             },
         ];
 
@@ -136,6 +162,7 @@ mod tests {
             br#"
   { name = "1", path = "exercises/1.rs" },
   { name = "2", path = "exercises/d/2.rs" },
+  { name = "2_verifier", path = "exercises/d/2_verifier.rs" },
 "#,
         );
 
@@ -145,6 +172,7 @@ mod tests {
 bin = [
   { name = "1", path = "../exercises/1.rs" },
   { name = "2", path = "../exercises/d/2.rs" },
+  { name = "2_verifier", path = "../exercises/d/2_verifier.rs" },
 ]
 123"#,
         );
@@ -0,0 +1,160 @@
+use anyhow::{bail, Result};
+use markdown::mdast::Node;
+use std::{
+    io::{self, Write},
+    ptr,
+};
+
+use crate::{answer_syntax::answer_block, color::Stylize, comparator::Comparator};
+
+// The letter of the choice at the index: a, b, c, …
+fn choice_letter(ind: usize) -> char {
+    (b'a' + (ind % 26) as u8) as char
+}
+
+/// The choices of a multiple-choice question: The items of a list after the question heading
+/// and before the answer block. Empty for a free-text question.
+pub fn extract_choices(ast: &Node) -> Result<Vec<String>> {
+    let Node::Root(root) = ast else {
+        return Ok(Vec::new());
+    };
+
+    let answer_block = answer_block(&root.children)?;
+    let mut after_heading = false;
+    let mut choices: Option<Vec<String>> = None;
+    for child in &root.children {
+        match child {
+            Node::Heading(heading) if heading.depth == 1 => after_heading = true,
+            Node::List(list) if after_heading => {
+                if choices.is_some() {
+                    bail!("A multiple-choice question can only have one list of choices");
+                }
+                choices = Some(
+                    list.children
+                        .iter()
+                        .map(|item| item.to_string().trim().to_string())
+                        .collect(),
+                );
+            }
+            Node::Code(code) if answer_block.is_some_and(|block| ptr::eq(block, code)) => break,
+            _ => (),
+        }
+    }
+
+    let choices = choices.unwrap_or_default();
+    if choices.len() > 26 {
+        bail!("A multiple-choice question can't have more than 26 choices");
+    }
+
+    Ok(choices)
+}
+
+/// The index of the choice whose letter is the answer, e.g. `b`, `B` or `b)`.
+pub fn parse_choice(answer: &str, n_choices: usize) -> Option<usize> {
+    let answer = answer.trim();
+    let answer = answer
+        .strip_suffix([')', '.'])
+        .unwrap_or(answer)
+        .to_ascii_lowercase();
+
+    let mut chars = answer.chars();
+    let (Some(letter), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+
+    (0..n_choices).find(|&ind| choice_letter(ind) == letter)
+}
+
+pub fn write_choices(choices: &[String], output: &mut Vec<u8>) -> io::Result<()> {
+    for (ind, choice) in choices.iter().enumerate() {
+        writeln!(output, "  {}) {choice}", choice_letter(ind))?;
+    }
+
+    Ok(())
+}
+
+/// Check the letter answered to a multiple-choice question against the letter in the
+/// solution's answer block. The choices are written to the output.
+pub fn check_choice(
+    choices: &[String],
+    comparator: Comparator,
+    answer: &str,
+    expected: &str,
+    output: &mut Vec<u8>,
+) -> Result<bool> {
+    // A free-text comparator with a list of choices is ambiguous.
+    if comparator != Comparator::Exact {
+        bail!("A multiple-choice question can't declare an answer comparator. Its answer is the letter of a choice");
+    }
+    let Some(expected) = parse_choice(expected, choices.len()) else {
+        bail!(
+            "The list after the question makes it a multiple-choice question but the solution's answer `{}` isn't the letter of one of its {} choices",
+            expected.trim(),
+            choices.len(),
+        );
+    };
+
+    write_choices(choices, output)?;
+
+    let Some(answer) = parse_choice(answer, choices.len()) else {
+        writeln!(
+            output,
+            "{}",
+            format!(
+                "Answer with the letter of one of the choices (a to {})",
+                choice_letter(choices.len() - 1),
+            )
+            .red(),
+        )?;
+        return Ok(false);
+    };
+
+    Ok(answer == expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use markdown::{to_mdast, ParseOptions};
+
+    use super::*;
+
+    const QUESTION: &str = "# Which proving system has constant-size proofs?
+
+- Groth16
+- A *Merkle* tree
+- SHA-256
+
+```math
+a
+```
+";
+
+    #[test]
+    fn multiple_choice() {
+        let ast = to_mdast(QUESTION, &ParseOptions::gfm()).unwrap();
+        let choices = extract_choices(&ast).unwrap();
+        assert_eq!(choices, ["Groth16", "A Merkle tree", "SHA-256"]);
+
+        assert_eq!(parse_choice(" B) ", 3), Some(1));
+        assert_eq!(parse_choice("d", 3), None);
+        assert_eq!(parse_choice("Groth16", 3), None);
+
+        let mut output = Vec::new();
+        assert!(check_choice(&choices, Comparator::Exact, "A", "a", &mut output).unwrap());
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("  c) SHA-256\n"));
+        assert!(!check_choice(&choices, Comparator::Exact, "c", "a", &mut Vec::new()).unwrap());
+        assert!(
+            check_choice(&choices, Comparator::Exact, "a", "Groth16", &mut Vec::new()).is_err()
+        );
+        assert!(check_choice(&choices, Comparator::Set, "a", "a", &mut Vec::new()).is_err());
+
+        let free_text = to_mdast("# Question\n\n```math\n42\n```\n", &ParseOptions::gfm()).unwrap();
+        assert!(extract_choices(&free_text).unwrap().is_empty());
+
+        let two_lists = QUESTION.replace("\n```math", "\n---\n\n- Plonk\n\n```math");
+        let ast = to_mdast(&two_lists, &ParseOptions::gfm()).unwrap();
+        assert!(extract_choices(&ast).is_err());
+    }
+}
@@ -0,0 +1,956 @@
+use anyhow::{bail, Context, Result};
+use std::{
+    env,
+    ffi::OsStr,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use self::{backends::ProofSetup, constraint_delta::ConstraintCategories, vkey::ExpectedVkey};
+use crate::{
+    cmd::{check_tool_version, CircomCmd, PlannedCmd, SnarkjsCmd, ToolVersion},
+    color::Stylize,
+    criteria::Measurements,
+    exercise::{RunnableExercise, OUTPUT_CAPACITY},
+    info_file::Backend,
+    input_spec,
+};
+
+mod arity;
+mod backends;
+pub mod build_cache;
+mod constraint_delta;
+mod expected_public;
+mod security;
+mod signals;
+pub mod structure;
+mod tool_output;
+mod vkey;
+
+// Circom 1 is the old JavaScript compiler which doesn't support the syntax of the exercises.
+const MIN_CIRCOM_VERSION: ToolVersion = ToolVersion(2, 0, 0);
+const CIRCOM_INSTALL_HINT: &str =
+    "Install it via https://docs.circom.io/getting-started/installation";
+
+/// Check that a supported version of `circom` is installed.
+/// The error explains how to install it.
+pub fn check_circom_version() -> Result<()> {
+    check_tool_version("circom", MIN_CIRCOM_VERSION, CIRCOM_INSTALL_HINT)
+}
+
+// Find a file belonging to the circuit `name` in `circuit_dir`.
+// `NAME.FILE_NAME` is preferred over `FILE_NAME` to allow multiple circuits in one directory.
+fn circuit_file(circuit_dir: &Path, name: &str, file_name: &str) -> Option<String> {
+    let specific_file_name = format!("{name}.{file_name}");
+    if circuit_dir.join(&specific_file_name).is_file() {
+        return Some(specific_file_name);
+    }
+
+    circuit_dir
+        .join(file_name)
+        .is_file()
+        .then(|| file_name.to_string())
+}
+
+// The files used to verify an external proof of the compiled circuit `name`.
+struct ExternalProofFiles {
+    r1cs: String,
+    zkey: String,
+    vkey: String,
+    ptau: String,
+    proof: String,
+    public: String,
+}
+
+impl ExternalProofFiles {
+    fn new(name: &str, ptau: &Path, proof: String, public: String) -> Self {
+        Self {
+            r1cs: format!("{name}.r1cs"),
+            zkey: format!("{name}.zkey"),
+            vkey: format!("{name}.vkey.json"),
+            ptau: ptau.to_string_lossy().into_owned(),
+            proof,
+            public,
+        }
+    }
+
+    // The `snarkjs` arguments and descriptions of the steps in this order:
+    // Groth16 setup, export of the verification key and verification of the proof.
+    fn steps(&self) -> [([&str; 5], &'static str); 3] {
+        [
+            (
+                ["groth16", "setup", &self.r1cs, &self.ptau, &self.zkey],
+                "snarkjs groth16 setup …",
+            ),
+            (
+                ["zkey", "export", "verificationkey", &self.zkey, &self.vkey],
+                "snarkjs zkey export verificationkey …",
+            ),
+            (
+                ["groth16", "verify", &self.vkey, &self.public, &self.proof],
+                "snarkjs groth16 verify …",
+            ),
+        ]
+    }
+}
+
+// Resolve the Powers of Tau file of an exercise which verifies an external proof or compares
+// backends.
+fn ptau_path<E>(exercise: &E) -> Result<PathBuf>
+where
+    E: RunnableExercise + ?Sized,
+{
+    let Some(ptau) = exercise.ptau() else {
+        bail!(
+            "The exercise `{}` runs a setup but has no `ptau` file in `info.toml`",
+            exercise.name(),
+        );
+    };
+
+    fs::canonicalize(ptau)
+        .with_context(|| format!("Failed to find the Powers of Tau file `{ptau}`"))
+}
+
+// Generate the verification key of the compiled circuit `name` and use it to verify a proof
+// which was generated elsewhere (`proof.json` and `public.json` next to the circuit).
+// The Groth16 setup without contributions is deterministic. Therefore, the proof only verifies
+// if the user's circuit is the same as the one used to generate the proof.
+// The duration of the verification is reported and measured for the time budget.
+fn verify_external_proof(
+    name: &str,
+    circuit_dir: &Path,
+    ptau: &Path,
+    expected_vkey: Option<&ExpectedVkey>,
+    output: &mut Vec<u8>,
+    measurements: &mut Measurements,
+) -> Result<bool> {
+    let (Some(proof), Some(public)) = (
+        circuit_file(circuit_dir, name, "proof.json"),
+        circuit_file(circuit_dir, name, "public.json"),
+    ) else {
+        writeln!(
+            output,
+            "{}",
+            "The exercise expects the files `proof.json` and `public.json` next to the circuit"
+                .bold()
+                .red(),
+        )?;
+        return Ok(false);
+    };
+
+    let files = ExternalProofFiles::new(name, ptau, proof, public);
+    let [setup, export, verify] = files.steps();
+
+    writeln!(
+        output,
+        "{}",
+        "Generating the verification key...".underlined()
+    )?;
+
+    let setup_success = SnarkjsCmd {
+        args: &setup.0,
+        description: setup.1,
+        output,
+        circuit_dir,
+    }
+    .run()?;
+    if !setup_success {
+        return Ok(false);
+    }
+
+    let export_success = SnarkjsCmd {
+        args: &export.0,
+        description: export.1,
+        output,
+        circuit_dir,
+    }
+    .run()?;
+    if !export_success {
+        return Ok(false);
+    }
+
+    let vkey_path = circuit_dir.join(&files.vkey);
+    if let Some(expected_vkey) = expected_vkey {
+        if !vkey::check_vkey_file(&vkey_path, expected_vkey, output)? {
+            return Ok(false);
+        }
+    }
+
+    if !vkey::check_proof_file(
+        &vkey_path,
+        &circuit_dir.join(&files.proof),
+        &circuit_dir.join(&files.public),
+        output,
+    )? {
+        return Ok(false);
+    }
+
+    writeln!(output, "{}", "Verifying the provided proof...".underlined())?;
+
+    let verify_output_start = output.len();
+    let verify_start = Instant::now();
+    let verify_exit_success = SnarkjsCmd {
+        args: &verify.0,
+        description: verify.1,
+        output,
+        circuit_dir,
+    }
+    .run()?;
+    let verify_time = verify_start.elapsed();
+    // Old versions of snarkjs exit successfully even if the proof is invalid.
+    let parsed =
+        tool_output::parse_verify_output(&String::from_utf8_lossy(&output[verify_output_start..]));
+    let verify_success =
+        tool_output::combine_with_exit_status(verify_exit_success, parsed, verify.1, output)?;
+    writeln!(output, "Verification took {} ms", verify_time.as_millis())?;
+    measurements.verify_time = Some(verify_time);
+    if !verify_success {
+        writeln!(
+            output,
+            "{}",
+            "The verification key of your circuit doesn't match the provided proof.
+Your circuit must have the same constraints and public signals as the circuit used to generate the proof."
+                .bold()
+                .red(),
+        )?;
+    }
+
+    Ok(verify_success)
+}
+
+// The environment variables with the paths of the files used to verify the external proof.
+// They are passed to the Rust verifier which runs after the proof was verified.
+fn rust_verifier_env<E>(exercise: &E, circuit_dir: &Path) -> Result<[(&'static str, PathBuf); 3]>
+where
+    E: RunnableExercise + ?Sized,
+{
+    if !exercise.external_proof() {
+        bail!(
+            "The exercise `{}` has a Rust verifier but doesn't verify an external proof (`external_proof`)",
+            exercise.name(),
+        );
+    }
+
+    let name = exercise.name();
+    let proof =
+        circuit_file(circuit_dir, name, "proof.json").unwrap_or_else(|| String::from("proof.json"));
+    let public = circuit_file(circuit_dir, name, "public.json")
+        .unwrap_or_else(|| String::from("public.json"));
+
+    Ok([
+        (
+            "ZKLINGS_VKEY",
+            circuit_dir.join(format!("{name}.vkey.json")),
+        ),
+        ("ZKLINGS_PROOF", circuit_dir.join(proof)),
+        ("ZKLINGS_PUBLIC", circuit_dir.join(public)),
+    ])
+}
+
+// The bin name and source path of the Rust verifier of an exercise.
+fn rust_verifier<E>(exercise: &E) -> Option<(&str, String)>
+where
+    E: RunnableExercise + ?Sized,
+{
+    let bin_name = exercise.rust_verifier()?;
+    let source_path = exercise.rust_verifier_path()?;
+
+    Some((bin_name, source_path.to_string_lossy().into_owned()))
+}
+
+// The directory of the circuit and the circuit's file name (`name` if it can't be determined).
+// A path without a directory is relative to the current directory.
+fn circuit_location<'a>(path: &'a Path, name: &'a str) -> (&'a Path, &'a str) {
+    let circuit_dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let circuit_file = path.file_name().and_then(OsStr::to_str).unwrap_or(name);
+
+    (circuit_dir, circuit_file)
+}
+
+fn compile_cmd<'a>(
+    circuit_dir: &'a Path,
+    circuit_name: &'a str,
+    prime: Option<&'a str>,
+    output: &'a mut Vec<u8>,
+) -> CircomCmd<'a> {
+    CircomCmd {
+        args: &["--r1cs", "--wasm", "--sym"],
+        circuit_name,
+        prime,
+        description: "Compiling Circom circuit",
+        output,
+        circuit_dir,
+    }
+}
+
+/// The commands that `run_circom` and `run_rust_verifier` execute in this order, without
+/// executing them.
+pub fn plan_circom<E>(exercise: &E, target_dir: &Path) -> Result<Vec<PlannedCmd>>
+where
+    E: RunnableExercise + ?Sized,
+{
+    let path = exercise.path();
+    let (circuit_dir, circuit_file) = circuit_location(Path::new(&path), exercise.name());
+    // Only used when running the commands.
+    let mut output = Vec::new();
+
+    let circom_cmd = compile_cmd(circuit_dir, circuit_file, exercise.prime(), &mut output);
+    let mut plan = vec![PlannedCmd {
+        description: circom_cmd.description.to_string(),
+        cmd: circom_cmd.command(),
+    }];
+
+    if exercise.external_proof() {
+        let ptau = ptau_path(exercise)
+            // Planning shouldn't fail because the file doesn't exist yet.
+            .unwrap_or_else(|_| PathBuf::from(exercise.ptau().unwrap_or_default()));
+        let name = exercise.name();
+        let proof = circuit_file(circuit_dir, name, "proof.json")
+            .unwrap_or_else(|| String::from("proof.json"));
+        let public = circuit_file(circuit_dir, name, "public.json")
+            .unwrap_or_else(|| String::from("public.json"));
+        let files = ExternalProofFiles::new(name, &ptau, proof, public);
+
+        for (args, description) in files.steps() {
+            let snarkjs_cmd = SnarkjsCmd {
+                args: &args,
+                description,
+                output: &mut output,
+                circuit_dir,
+            };
+            plan.push(PlannedCmd {
+                description: description.to_string(),
+                cmd: snarkjs_cmd.command(),
+            });
+        }
+    }
+
+    // The input generated from the input spec is written to a scratch directory when running.
+    // Its file name stands in for it.
+    let generated_input = (!exercise.input_spec().is_empty())
+        .then(|| PathBuf::from(format!("{}.input.json", exercise.name())));
+    let generated_input = generated_input.as_deref();
+
+    if !exercise.external_proof() && !exercise.backends().is_empty() {
+        let ptau = ptau_path(exercise)
+            .unwrap_or_else(|_| PathBuf::from(exercise.ptau().unwrap_or_default()));
+        plan.extend(backends::plan_backends(
+            exercise.name(),
+            circuit_dir,
+            generated_input,
+            &ptau,
+            exercise.backends(),
+        ));
+    }
+
+    if exercise.prove() && !exercise.external_proof() && exercise.backends().is_empty() {
+        let ptau = exercise
+            .ptau()
+            .map(|ptau| ptau_path(exercise).unwrap_or_else(|_| PathBuf::from(ptau)));
+        let setup = ProofSetup::new(
+            circuit_dir,
+            exercise.name(),
+            exercise.proving_system(),
+            ptau,
+        );
+        plan.extend(backends::plan_proof(
+            exercise.name(),
+            circuit_dir,
+            generated_input,
+            setup.as_ref(),
+        ));
+    }
+
+    if let Some((bin_name, _)) = rust_verifier(exercise) {
+        let env = rust_verifier_env(exercise, circuit_dir)?;
+        plan.extend(exercise.plan_rust(bin_name, target_dir, &env));
+    }
+
+    Ok(plan)
+}
+
+// Compile the circuit at `path` with the constraints as JSON into `out_dir` and categorize them.
+fn categorize_constraints(
+    path: &str,
+    name: &str,
+    prime: Option<&str>,
+    out_dir: &Path,
+    output: &mut Vec<u8>,
+) -> Result<ConstraintCategories> {
+    fs::create_dir_all(out_dir)
+        .with_context(|| format!("Failed to create the directory {}", out_dir.display()))?;
+    // `circom` resolves the output directory relative to the circuit directory.
+    let out_dir = fs::canonicalize(out_dir)
+        .with_context(|| format!("Failed to canonicalize {}", out_dir.display()))?;
+    let out_dir_str = out_dir.to_string_lossy();
+
+    let (circuit_dir, circuit_file) = circuit_location(Path::new(path), name);
+    let mut cmd = CircomCmd {
+        args: &["--r1cs", "--json", "-o", &out_dir_str],
+        circuit_name: circuit_file,
+        prime,
+        description: "Exporting the constraints",
+        output,
+        circuit_dir,
+    };
+    if !cmd.run()? {
+        bail!("Failed to export the constraints of {path}");
+    }
+
+    ConstraintCategories::read(&out_dir, name)
+}
+
+/// Compare the categorized constraints of an exercise exceeding its constraint budget with those
+/// of its solution and append guesses about the difference to the `output` buffer.
+/// Does nothing if the solution doesn't exist.
+pub fn explain_constraints<E>(exercise: &E, target_dir: &Path, output: &mut Vec<u8>) -> Result<()>
+where
+    E: RunnableExercise + ?Sized,
+{
+    let sol_path = exercise.sol_path();
+    if !Path::new(&sol_path).is_file() {
+        return Ok(());
+    }
+
+    let name = exercise.name();
+    let out_dir = target_dir.join("constraints").join(name);
+    // The output of the commands is only relevant if they fail.
+    let mut cmd_output = Vec::new();
+    let prime = exercise.prime();
+    let categories = categorize_constraints(
+        &exercise.path(),
+        name,
+        prime,
+        &out_dir.join("exercise"),
+        &mut cmd_output,
+    )
+    .and_then(|learner| {
+        let solution = categorize_constraints(
+            &sol_path,
+            name,
+            prime,
+            &out_dir.join("solution"),
+            &mut cmd_output,
+        )?;
+        Ok((learner, solution))
+    });
+
+    let (learner, solution) = match categories {
+        Ok(categories) => categories,
+        Err(e) => {
+            output.extend_from_slice(&cmd_output);
+            writeln!(
+                output,
+                "{}",
+                format!("{e:#}. Skipping the comparison with the solution's constraints").yellow(),
+            )?;
+            return Ok(());
+        }
+    };
+
+    let explanations = constraint_delta::explain_delta(learner, solution);
+    if explanations.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(
+        output,
+        "\n{}",
+        "Guesses from comparing the kinds of constraints with the solution (heuristic, may be wrong):"
+            .underlined(),
+    )?;
+    for explanation in explanations {
+        writeln!(output, "  {explanation}")?;
+    }
+
+    Ok(())
+}
+
+/// Compile the circuit of a Circom exercise and render its components from the symbol table.
+pub fn circuit_structure<E>(exercise: &E, format: structure::StructureFormat) -> Result<String>
+where
+    E: RunnableExercise + ?Sized,
+{
+    if !exercise.is_circom() {
+        bail!("The exercise {} isn't a Circom exercise", exercise.name());
+    }
+
+    let path = exercise.path();
+    let (circuit_dir, circuit_file) = circuit_location(Path::new(&path), exercise.name());
+
+    let mut output = Vec::with_capacity(OUTPUT_CAPACITY);
+    if !compile_cmd(circuit_dir, circuit_file, exercise.prime(), &mut output).run()? {
+        bail!(
+            "Failed to compile the circuit {path}:\n{}",
+            String::from_utf8_lossy(&output),
+        );
+    }
+
+    let sym_path = circuit_dir.join(format!("{}.sym", exercise.name()));
+    let sym = fs::read_to_string(&sym_path)
+        .with_context(|| format!("Failed to read the file {}", sym_path.display()))?;
+
+    Ok(structure::render(
+        &structure::parse_components(&sym),
+        format,
+    ))
+}
+
+static N_SCRATCH_DIRS: AtomicUsize = AtomicUsize::new(0);
+
+// A temporary directory for the files of one run, like a markdown answer to compile or an input
+// generated from the input spec. It is removed when dropped.
+// Exercises are checked concurrently by `verify --parallel`. Therefore, every run gets its own
+// directory.
+struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    fn create(kind: &str) -> Result<Self> {
+        let n = N_SCRATCH_DIRS.fetch_add(1, Ordering::Relaxed);
+        let dir = env::temp_dir().join(format!("zklings-{kind}-{}-{n}", process::id()));
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create the directory {}", dir.display()))?;
+
+        Ok(Self(dir))
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        // Not cleaning up the temporary directory is harmless.
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Compile the circuit of a markdown answer without generating any files to check its syntax.
+/// The answer has to be a complete circuit with a main component. It is compiled with `bn128`.
+pub fn check_circuit(circuit: &str, output: &mut Vec<u8>) -> Result<bool> {
+    let answer_dir = ScratchDir::create("answer")?;
+    let dir = &answer_dir.0;
+    let circuit_path = dir.join("answer.circom");
+    fs::write(&circuit_path, circuit)
+        .with_context(|| format!("Failed to write the file {}", circuit_path.display()))?;
+
+    CircomCmd {
+        args: &[],
+        circuit_name: "answer.circom",
+        prime: None,
+        description: "Compiling the answer",
+        output,
+        circuit_dir: dir,
+    }
+    .run()
+}
+
+// Generate `NAME.input.json` in a scratch directory if the exercise declares its input signals.
+// It is checked against the signals of the compiled circuit like a handwritten one.
+// The directory is removed when the returned value is dropped.
+fn write_input_file<E>(exercise: &E, output: &mut Vec<u8>) -> Result<Option<(ScratchDir, PathBuf)>>
+where
+    E: RunnableExercise + ?Sized,
+{
+    let input_spec = exercise.input_spec();
+    if input_spec.is_empty() {
+        return Ok(None);
+    }
+
+    let input = input_spec::generate_input(input_spec, exercise.field_modulus())?;
+    let input_dir = ScratchDir::create("input")?;
+    let input_path = input_dir.0.join(format!("{}.input.json", exercise.name()));
+    let mut content = serde_json::to_vec_pretty(&input)?;
+    content.push(b'\n');
+    fs::write(&input_path, content)
+        .with_context(|| format!("Failed to write the file {}", input_path.display()))?;
+    writeln!(
+        output,
+        "Generated {} from the input spec",
+        input_path.display()
+    )?;
+
+    Ok(Some((input_dir, input_path)))
+}
+
+/// Compile the circuit at `path` of a Circom exercise, which is the exercise file or its solution,
+/// and append the output to the `output` buffer. The input and proof files are expected next to
+/// the circuit. The number of constraints and the verification time are stored in `measurements`.
+pub fn run_circom<E>(
+    exercise: &E,
+    path: &str,
+    output: &mut Vec<u8>,
+    measurements: &mut Measurements,
+) -> Result<bool>
+where
+    E: RunnableExercise + ?Sized,
+{
+    writeln!(output, "{}", "Compiling Circom circuit...".underlined())?;
+
+    let (circuit_dir, circuit_file) = circuit_location(Path::new(path), exercise.name());
+
+    if !circuit_dir.is_dir() {
+        writeln!(
+            output,
+            "{}",
+            format!(
+                "The directory `{}` of the circuit doesn't exist. Check the `dir` of the exercise `{}` in `info.toml`",
+                circuit_dir.display(),
+                exercise.name(),
+            )
+            .bold()
+            .red(),
+        )?;
+        return Ok(false);
+    }
+
+    // A missing circuit file is reported by `circom`.
+    let circuit = fs::read_to_string(path).unwrap_or_default();
+    let prime = exercise.prime();
+    if let Some(prime) = prime {
+        if !security::check_prime(prime, output)? {
+            return Ok(false);
+        }
+    }
+    if let Some(min_bits) = exercise.min_security_bits() {
+        let prime = prime.unwrap_or(security::DEFAULT_PRIME);
+        if !security::check_security_level(prime, min_bits, output)? {
+            return Ok(false);
+        }
+    }
+
+    if let Some(mismatch) = arity::check_main_arity(&circuit) {
+        writeln!(output, "{}", mismatch.red())?;
+        return Ok(false);
+    }
+
+    let compile_output_start = output.len();
+    if build_cache::is_fresh(circuit_dir, Path::new(path), exercise.name()) {
+        writeln!(output, "Circuit unchanged, using cached build")?;
+        measurements.stages.compiled = Some(true);
+    } else {
+        if let Err(e) = check_circom_version() {
+            writeln!(output, "{}", format!("{e:#}").bold().red())?;
+            measurements.stages.compiled = Some(false);
+            return Ok(false);
+        }
+
+        let compile_success = compile_cmd(circuit_dir, circuit_file, prime, output).run()?;
+        measurements.stages.compiled = Some(compile_success);
+
+        if !compile_success {
+            return Ok(false);
+        }
+    }
+
+    let generated_input = write_input_file(exercise, output)?;
+    let generated_input = generated_input.as_ref().map(|(_, path)| path.as_path());
+
+    // The signals and constraints are read from the compiled files. If their format isn't
+    // supported, the number of constraints is taken from the output of `circom` instead.
+    let mut n_public = None;
+    match signals::r1cs_header(circuit_dir, exercise.name()) {
+        Ok(header) => {
+            measurements.constraints = Some(u64::from(header.n_constraints));
+            n_public = Some(header.n_public());
+
+            let signals = signals::main_signals(circuit_dir, exercise.name(), header)?;
+            signals::write_signals(&signals, output)?;
+            if !signals::check_input_file(
+                circuit_dir,
+                exercise.name(),
+                generated_input,
+                &signals,
+                output,
+            )? {
+                return Ok(false);
+            }
+        }
+        Err(e) => {
+            writeln!(
+                output,
+                "{}",
+                format!("{e:#}. Skipping the checks of the circuit's signals").yellow(),
+            )?;
+            measurements.constraints = tool_output::parse_constraints(&String::from_utf8_lossy(
+                &output[compile_output_start..],
+            ));
+        }
+    }
+
+    if let Some(constraints) = measurements.constraints {
+        writeln!(output, "Constraints: {constraints}")?;
+    }
+
+    // Exercises with `prove = false` are done once the circuit compiles.
+    if !exercise.prove() {
+        return Ok(true);
+    }
+
+    if exercise.external_proof() {
+        if exercise.proving_system() != Backend::Groth16 {
+            bail!(
+                "The exercise `{}` has an external proof which can only be verified with Groth16, not with {:?}",
+                exercise.name(),
+                exercise.proving_system(),
+            );
+        }
+
+        let ptau = ptau_path(exercise)?;
+        let expected_vkey = exercise.check_vkey().then(|| ExpectedVkey {
+            protocol: "groth16",
+            curve: prime.unwrap_or(security::DEFAULT_PRIME),
+            n_public,
+        });
+
+        return verify_external_proof(
+            exercise.name(),
+            circuit_dir,
+            &ptau,
+            expected_vkey.as_ref(),
+            output,
+            measurements,
+        );
+    }
+
+    if !exercise.backends().is_empty() {
+        let ptau = ptau_path(exercise)?;
+
+        return backends::compare_backends(
+            exercise.name(),
+            circuit_dir,
+            generated_input,
+            &ptau,
+            exercise.backends(),
+            output,
+        );
+    }
+
+    let ptau = match exercise.proving_system() {
+        // The universal setup always needs the Powers of Tau.
+        Backend::Plonk => Some(ptau_path(exercise)?),
+        Backend::Groth16 => exercise.ptau().map(|_| ptau_path(exercise)).transpose()?,
+    };
+    let setup = ProofSetup::new(
+        circuit_dir,
+        exercise.name(),
+        exercise.proving_system(),
+        ptau,
+    );
+
+    backends::prove(
+        exercise.name(),
+        circuit_dir,
+        generated_input,
+        setup.as_ref(),
+        output,
+        measurements,
+    )
+}
+
+/// Build, check and run the Rust verifier of a Circom exercise whose proof was already verified
+/// by `run_circom`. Its output is appended to the `output` buffer.
+pub fn run_rust_verifier<E>(
+    exercise: &E,
+    output: &mut Vec<u8>,
+    target_dir: &Path,
+    measurements: &mut Measurements,
+) -> Result<bool>
+where
+    E: RunnableExercise + ?Sized,
+{
+    let Some((bin_name, source_path)) = rust_verifier(exercise) else {
+        return Ok(true);
+    };
+
+    let path = exercise.path();
+    let (circuit_dir, _) = circuit_location(Path::new(&path), exercise.name());
+    let env = rust_verifier_env(exercise, circuit_dir)?;
+
+    writeln!(output, "{}", "Running the Rust verifier...".underlined())?;
+
+    // `run_with_env` clears its output buffer.
+    let mut verifier_output = Vec::with_capacity(OUTPUT_CAPACITY);
+    let success = exercise.run_with_env(
+        bin_name,
+        &source_path,
+        &mut verifier_output,
+        target_dir,
+        &env,
+        None,
+        measurements,
+    )?;
+    output.append(&mut verifier_output);
+
+    Ok(success)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::info_file::ExerciseInfo;
+
+    #[test]
+    fn answer_dirs_are_unique() {
+        let first = ScratchDir::create("answer").unwrap();
+        let second = ScratchDir::create("answer").unwrap();
+        assert_ne!(first.0, second.0);
+
+        let first_path = first.0.clone();
+        let second_circuit = second.0.join("answer.circom");
+        fs::write(&second_circuit, "template B() {}").unwrap();
+        drop(first);
+        assert!(!first_path.exists());
+        assert_eq!(
+            fs::read_to_string(&second_circuit).unwrap(),
+            "template B() {}",
+        );
+    }
+
+    #[test]
+    fn concurrent_answers_in_pool() {
+        let answers = ["template A() {}", "template B() {}"];
+        let worker_dirs = [PathBuf::from("worker1"), PathBuf::from("worker2")];
+        // Each worker keeps its answer directory until both answers were written.
+        let barrier = std::sync::Barrier::new(worker_dirs.len());
+
+        let circuits = crate::pool::run_pool(&answers, &worker_dirs, |answer, _| {
+            let answer_dir = ScratchDir::create("answer").unwrap();
+            let circuit_path = answer_dir.0.join("answer.circom");
+            fs::write(&circuit_path, answer).unwrap();
+            barrier.wait();
+            fs::read_to_string(&circuit_path).unwrap()
+        });
+
+        assert_eq!(circuits, answers);
+    }
+
+    fn multiplier(rust_verifier: Option<&str>) -> ExerciseInfo {
+        ExerciseInfo {
+            dir: Some(String::from("circom")),
+            external_proof: true,
+            ptau: Some(String::from("pot12.ptau")),
+            rust_verifier: rust_verifier.map(String::from),
+            ..ExerciseInfo::new("multiplier", "circom")
+        }
+    }
+
+    #[test]
+    fn generated_input_in_scratch_dir() {
+        let mut exercise = multiplier(None);
+        let mut output = Vec::new();
+        assert!(write_input_file(&exercise, &mut output).unwrap().is_none());
+
+        exercise.input_spec = toml_edit::de::from_str::<ExerciseInfo>(
+            r#"
+name = "multiplier"
+ext = "circom"
+hint = ""
+input_spec = [{ signal = "a", value = 3 }]
+"#,
+        )
+        .unwrap()
+        .input_spec;
+        let (input_dir, input_path) = write_input_file(&exercise, &mut output).unwrap().unwrap();
+        assert!(!input_path.starts_with("exercises"));
+        assert_eq!(
+            fs::read_to_string(&input_path).unwrap(),
+            "{\n  \"a\": \"3\"\n}\n",
+        );
+
+        drop(input_dir);
+        assert!(!input_path.exists());
+    }
+
+    #[test]
+    fn circuit_dir_of_exercise_path() {
+        let mut exercise = multiplier(None);
+        let path = exercise.path();
+        assert_eq!(
+            circuit_location(Path::new(&path), exercise.name()),
+            (Path::new("exercises/circom"), "multiplier.circom"),
+        );
+
+        // Directly in `exercises/` without a subdirectory.
+        exercise.dir = None;
+        let path = exercise.path();
+        assert_eq!(
+            circuit_location(Path::new(&path), exercise.name()),
+            (Path::new("exercises"), "multiplier.circom"),
+        );
+
+        assert_eq!(
+            circuit_location(Path::new("multiplier.circom"), "multiplier"),
+            (Path::new("."), "multiplier.circom"),
+        );
+    }
+
+    #[test]
+    fn groth16_plan() {
+        let exercise = multiplier(None);
+
+        let plan = plan_circom(&exercise, Path::new("target")).unwrap();
+        let cmds = plan
+            .iter()
+            .map(|planned_cmd| planned_cmd.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            cmds,
+            [
+                "circom multiplier.circom --r1cs --wasm --sym",
+                "snarkjs groth16 setup multiplier.r1cs pot12.ptau multiplier.zkey",
+                "snarkjs zkey export verificationkey multiplier.zkey multiplier.vkey.json",
+                "snarkjs groth16 verify multiplier.vkey.json public.json proof.json",
+            ],
+        );
+
+        for planned_cmd in &plan {
+            assert_eq!(
+                planned_cmd.cmd.get_current_dir(),
+                Some(Path::new("exercises/circom")),
+            );
+        }
+    }
+
+    #[test]
+    fn rust_verifier_phases() {
+        let exercise = multiplier(Some("multiplier_verifier"));
+        assert_eq!(
+            exercise.rust_verifier_path(),
+            Some(PathBuf::from("exercises/circom/multiplier_verifier.rs")),
+        );
+
+        let plan = plan_circom(&exercise, Path::new("target")).unwrap();
+        let cmds = plan
+            .iter()
+            .map(|planned_cmd| planned_cmd.to_string())
+            .collect::<Vec<_>>();
+
+        // The Rust verifier is built and run after the proof of the circuit was verified.
+        assert_eq!(cmds.len(), 7);
+        assert!(cmds[0].starts_with("circom multiplier.circom"));
+        assert!(cmds[3].starts_with("snarkjs groth16 verify"));
+        assert!(cmds[4].starts_with("cargo build"));
+        assert!(cmds[4].contains("--bin multiplier_verifier"));
+        assert!(cmds[5].starts_with("cargo clippy"));
+        assert_eq!(
+            Path::new(plan[6].cmd.get_program()),
+            Path::new("target/debug/multiplier_verifier"),
+        );
+        for env in [
+            "ZKLINGS_VKEY=exercises/circom/multiplier.vkey.json",
+            "ZKLINGS_PROOF=exercises/circom/proof.json",
+            "ZKLINGS_PUBLIC=exercises/circom/public.json",
+        ] {
+            assert!(cmds[6].contains(env));
+        }
+
+        let mut exercise = exercise;
+        exercise.external_proof = false;
+        assert!(plan_circom(&exercise, Path::new("target")).is_err());
+    }
+}
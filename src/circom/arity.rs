@@ -0,0 +1,197 @@
+// Check the instantiation of the main component against the parameters of its template before
+// compiling because `circom` reports wrong argument counts cryptically.
+
+// Remove `//` and `/* */` comments.
+fn strip_comments(source: &str) -> String {
+    let mut stripped = String::with_capacity(source.len());
+    let mut rest = source;
+
+    while let Some(ind) = rest.find('/') {
+        stripped.push_str(&rest[..ind]);
+        let after = &rest[ind..];
+
+        if after.starts_with("//") {
+            rest = after.find('\n').map_or("", |newline| &after[newline..]);
+        } else if after.starts_with("/*") {
+            rest = after[2..].find("*/").map_or("", |end| &after[end + 4..]);
+            stripped.push(' ');
+        } else {
+            stripped.push('/');
+            rest = &after[1..];
+        }
+    }
+    stripped.push_str(rest);
+
+    stripped
+}
+
+// The number of comma-separated arguments at the top level of the list.
+fn count_args(args: &str) -> usize {
+    if args.trim().is_empty() {
+        return 0;
+    }
+
+    let mut depth = 0_usize;
+    let mut count = 1;
+    for c in args.chars() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => count += 1,
+            _ => (),
+        }
+    }
+
+    count
+}
+
+// The content between the parenthesis at the start of `s` and its matching closing parenthesis.
+fn parenthesized(s: &str) -> Option<&str> {
+    let s = s.trim_start().strip_prefix('(')?;
+
+    let mut depth = 0_usize;
+    for (ind, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(&s[..ind]),
+            ')' => depth -= 1,
+            _ => (),
+        }
+    }
+
+    None
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '$'
+}
+
+// Split the identifier at the start of `s` from the rest.
+fn split_ident(s: &str) -> (&str, &str) {
+    let s = s.trim_start();
+    let end = s.find(|c| !is_ident_char(c)).unwrap_or(s.len());
+    s.split_at(end)
+}
+
+// The number of parameters of the template `name` if it is declared in the source.
+fn template_arity(source: &str, name: &str) -> Option<usize> {
+    let mut rest = source;
+
+    while let Some(ind) = rest.find("template") {
+        let before = rest[..ind].chars().next_back();
+        let mut after = &rest[ind + 8..];
+        rest = after;
+        if before.is_some_and(is_ident_char) || !after.starts_with(char::is_whitespace) {
+            continue;
+        }
+
+        // Templates can be declared as `template parallel NAME(…)` or `template custom NAME(…)`.
+        let (mut ident, mut tail) = split_ident(after);
+        if matches!(ident, "parallel" | "custom") {
+            after = tail;
+            (ident, tail) = split_ident(after);
+        }
+
+        if ident == name {
+            return parenthesized(tail).map(count_args);
+        }
+    }
+
+    None
+}
+
+// The template name and the number of arguments of the main component.
+fn main_instantiation(source: &str) -> Option<(&str, usize)> {
+    let mut rest = source;
+
+    loop {
+        let ind = rest.find("component")?;
+        let before = rest[..ind].chars().next_back();
+        let after = &rest[ind + 9..];
+        rest = after;
+        if before.is_some_and(is_ident_char) {
+            continue;
+        }
+
+        let (ident, tail) = split_ident(after);
+        if ident != "main" {
+            continue;
+        }
+
+        // component main {public [a, b]} = NAME(…);
+        let eq_ind = tail.find('=')?;
+        let (template, tail) = split_ident(&tail[eq_ind + 1..]);
+        // `parallel` can precede the template name.
+        let (template, tail) = if template == "parallel" {
+            split_ident(tail)
+        } else {
+            (template, tail)
+        };
+
+        return parenthesized(tail).map(|args| (template, count_args(args)));
+    }
+}
+
+/// Check the number of arguments of the main component against the parameters of its template.
+/// Returns the explanation of a mismatch. Templates from included files aren't checked.
+pub fn check_main_arity(source: &str) -> Option<String> {
+    let source = strip_comments(source);
+    let (template, n_args) = main_instantiation(&source)?;
+    let n_params = template_arity(&source, template)?;
+
+    if n_args == n_params {
+        return None;
+    }
+
+    let parameters = if n_params == 1 {
+        "parameter"
+    } else {
+        "parameters"
+    };
+    Some(format!(
+        "The main component instantiates the template {template} with the wrong number of arguments: template {template} expects {n_params} {parameters} but {n_args} given",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mismatched_main_arity() {
+        let circuit = "pragma circom 2.1.6;
+
+// template Decoy(a) {}
+template Sum(n, offset) {
+    signal input in[n];
+    signal output out;
+    var acc = offset;
+    for (var i = 0; i < n; i++) {
+        acc += in[i];
+    }
+    out <== acc;
+}
+
+component main {public [in]} = Sum(f(2, 3));
+";
+
+        assert_eq!(
+            check_main_arity(circuit).unwrap(),
+            "The main component instantiates the template Sum with the wrong number of arguments: template Sum expects 2 parameters but 1 given",
+        );
+        assert_eq!(
+            check_main_arity(&circuit.replace("Sum(f(2, 3))", "Sum(f(2, 3), 1)")),
+            None,
+        );
+        assert!(check_main_arity(
+            "template parallel Id() {}\n/* main */\ncomponent main = parallel Id(1, 2);"
+        )
+        .unwrap()
+        .ends_with("template Id expects 0 parameters but 2 given"));
+        // Templates of included files are unknown.
+        assert_eq!(
+            check_main_arity("include \"lib.circom\";\ncomponent main = Lib(1);"),
+            None,
+        );
+    }
+}
@@ -0,0 +1,717 @@
+use anyhow::{Context, Result};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use super::{circuit_file, expected_public, tool_output};
+use crate::{
+    cmd::{PlannedCmd, SnarkjsCmd},
+    color::Stylize,
+    criteria::Measurements,
+    info_file::Backend,
+};
+
+/// The files of proving a compiled circuit with one backend.
+/// The files of different backends have different names to keep them for comparison.
+pub struct BackendFiles {
+    r1cs: String,
+    ptau: String,
+    wtns: String,
+    zkey: String,
+    vkey: String,
+    proof: String,
+    public: String,
+}
+
+impl BackendFiles {
+    fn new(name: &str, backend: &dyn ProvingBackend, ptau: &Path) -> Self {
+        let protocol = backend.protocol();
+
+        Self {
+            r1cs: format!("{name}.r1cs"),
+            ptau: ptau.to_string_lossy().into_owned(),
+            wtns: format!("{name}.wtns"),
+            zkey: format!("{name}.{protocol}.zkey"),
+            vkey: format!("{name}.{protocol}.vkey.json"),
+            proof: format!("{name}.{protocol}.proof.json"),
+            public: format!("{name}.{protocol}.public.json"),
+        }
+    }
+}
+
+/// A key which was generated and checked in by the author of the exercise instead of running a
+/// setup. Either `NAME.circuit_final.zkey` or `circuit_final.zkey` next to the circuit.
+const CHECKED_IN_ZKEY: &str = "circuit_final.zkey";
+
+/// How the proving key of the proof of `run_circom` is obtained.
+pub enum ProofSetup {
+    /// Run the circuit-specific Groth16 setup with the Powers of Tau file.
+    Groth16Ptau(PathBuf),
+    /// Reuse the checked-in Groth16 key with this file name.
+    Groth16Zkey(String),
+    /// Run the universal PLONK setup with the Powers of Tau file. There is no circuit-specific
+    /// ceremony and therefore no checked-in key.
+    Plonk(PathBuf),
+}
+
+impl ProofSetup {
+    /// Groth16: The checked-in key or else the setup with the Powers of Tau file if there is one.
+    /// PLONK: The setup with the Powers of Tau file if there is one.
+    pub fn new(
+        circuit_dir: &Path,
+        name: &str,
+        proving_system: Backend,
+        ptau: Option<PathBuf>,
+    ) -> Option<Self> {
+        match proving_system {
+            Backend::Groth16 => circuit_file(circuit_dir, name, CHECKED_IN_ZKEY)
+                .map(Self::Groth16Zkey)
+                .or_else(|| ptau.map(Self::Groth16Ptau)),
+            Backend::Plonk => ptau.map(Self::Plonk),
+        }
+    }
+
+    fn backend(&self) -> &'static dyn ProvingBackend {
+        match self {
+            Self::Groth16Ptau(_) | Self::Groth16Zkey(_) => &Groth16,
+            Self::Plonk(_) => &Plonk,
+        }
+    }
+
+    fn files(&self, name: &str) -> BackendFiles {
+        match self {
+            Self::Groth16Ptau(ptau) | Self::Plonk(ptau) => {
+                BackendFiles::new(name, self.backend(), ptau)
+            }
+            Self::Groth16Zkey(zkey) => BackendFiles {
+                zkey: zkey.clone(),
+                ..BackendFiles::new(name, &Groth16, Path::new(""))
+            },
+        }
+    }
+
+    // The steps after generating the witness without the setup if the key is reused.
+    fn steps<'a>(&self, files: &'a BackendFiles) -> Vec<Vec<&'a str>> {
+        let [setup, export, prove, verify] = self.backend().steps(files);
+        match self {
+            Self::Groth16Ptau(_) | Self::Plonk(_) => vec![setup, export, prove, verify],
+            Self::Groth16Zkey(_) => vec![export, prove, verify],
+        }
+    }
+}
+
+/// A proving system of `snarkjs`.
+/// The steps are the same for all backends: Setup, export of the verification key, proving and
+/// verifying.
+pub trait ProvingBackend {
+    /// The `snarkjs` subcommand of the proving system like `groth16`.
+    fn protocol(&self) -> &'static str;
+
+    fn setup_args<'a>(&self, files: &'a BackendFiles) -> Vec<&'a str> {
+        vec![
+            self.protocol(),
+            "setup",
+            &files.r1cs,
+            &files.ptau,
+            &files.zkey,
+        ]
+    }
+
+    fn export_args<'a>(&self, files: &'a BackendFiles) -> Vec<&'a str> {
+        vec![
+            "zkey",
+            "export",
+            "verificationkey",
+            &files.zkey,
+            &files.vkey,
+        ]
+    }
+
+    fn prove_args<'a>(&self, files: &'a BackendFiles) -> Vec<&'a str> {
+        vec![
+            self.protocol(),
+            "prove",
+            &files.zkey,
+            &files.wtns,
+            &files.proof,
+            &files.public,
+        ]
+    }
+
+    fn verify_args<'a>(&self, files: &'a BackendFiles) -> Vec<&'a str> {
+        vec![
+            self.protocol(),
+            "verify",
+            &files.vkey,
+            &files.public,
+            &files.proof,
+        ]
+    }
+
+    // The arguments of all steps in this order.
+    fn steps<'a>(&self, files: &'a BackendFiles) -> [Vec<&'a str>; 4] {
+        [
+            self.setup_args(files),
+            self.export_args(files),
+            self.prove_args(files),
+            self.verify_args(files),
+        ]
+    }
+}
+
+struct Groth16;
+
+impl ProvingBackend for Groth16 {
+    fn protocol(&self) -> &'static str {
+        "groth16"
+    }
+}
+
+struct Plonk;
+
+impl ProvingBackend for Plonk {
+    fn protocol(&self) -> &'static str {
+        "plonk"
+    }
+}
+
+impl Backend {
+    pub fn proving_backend(self) -> &'static dyn ProvingBackend {
+        match self {
+            Self::Groth16 => &Groth16,
+            Self::Plonk => &Plonk,
+        }
+    }
+}
+
+fn description(args: &[&str]) -> String {
+    // The subcommand without the file arguments.
+    format!("snarkjs {} {} …", args[0], args[1])
+}
+
+fn witness_args<'a>(wasm: &'a str, input: &'a str, wtns: &'a str) -> [&'a str; 5] {
+    ["wtns", "calculate", wasm, input, wtns]
+}
+
+/// The measurements of proving the circuit with one backend.
+pub struct BackendResult {
+    pub protocol: &'static str,
+    pub setup_time: Duration,
+    pub prove_time: Duration,
+    pub verify_time: Duration,
+    /// Size of the proof file in bytes.
+    pub proof_size: u64,
+}
+
+/// Write the measurements of all backends as a table.
+pub fn write_comparison(results: &[BackendResult], output: &mut Vec<u8>) -> io::Result<()> {
+    writeln!(
+        output,
+        "{}",
+        format!(
+            "{:<10}{:>12}{:>12}{:>12}{:>14}",
+            "Backend", "Setup", "Prove", "Verify", "Proof size",
+        )
+        .bold(),
+    )?;
+
+    for result in results {
+        writeln!(
+            output,
+            "{:<10}{:>12}{:>12}{:>12}{:>14}",
+            result.protocol,
+            format!("{} ms", result.setup_time.as_millis()),
+            format!("{} ms", result.prove_time.as_millis()),
+            format!("{} ms", result.verify_time.as_millis()),
+            format!("{} B", result.proof_size),
+        )?;
+    }
+
+    Ok(())
+}
+
+// Explain which file provides the input signals of the witness generation.
+fn missing_input_msg(name: &str) -> String {
+    format!("The exercise needs an input file with the input signals of the circuit to compute the witness: `{name}.input.json` or `input.json` next to the circuit, or an `input_spec` in `info.toml`")
+}
+
+// The input file of the witness generation: The file generated from the input spec or the input
+// file next to the circuit.
+fn input_file(circuit_dir: &Path, name: &str, generated_input: Option<&Path>) -> Option<String> {
+    match generated_input {
+        Some(path) => Some(path.to_string_lossy().into_owned()),
+        None => circuit_file(circuit_dir, name, "input.json"),
+    }
+}
+
+/// The commands of `prove` in this order, without executing them.
+pub fn plan_proof(
+    name: &str,
+    circuit_dir: &Path,
+    generated_input: Option<&Path>,
+    setup: Option<&ProofSetup>,
+) -> Vec<PlannedCmd> {
+    let Some(input) = input_file(circuit_dir, name, generated_input) else {
+        return Vec::new();
+    };
+
+    let mut output = Vec::new();
+    let mut plan = Vec::with_capacity(5);
+    let mut push = |args: &[&str]| {
+        let description = description(args);
+        let cmd = SnarkjsCmd {
+            args,
+            description: &description,
+            output: &mut output,
+            circuit_dir,
+        }
+        .command();
+        plan.push(PlannedCmd { description, cmd });
+    };
+
+    let wasm = format!("{name}_js/{name}.wasm");
+    push(&witness_args(&wasm, &input, &format!("{name}.wtns")));
+
+    if let Some(setup) = setup {
+        let files = setup.files(name);
+        for args in setup.steps(&files) {
+            push(&args);
+        }
+    }
+
+    plan
+}
+
+/// The commands of `compare_backends` in this order, without executing them.
+pub fn plan_backends(
+    name: &str,
+    circuit_dir: &Path,
+    generated_input: Option<&Path>,
+    ptau: &Path,
+    backends: &[Backend],
+) -> Vec<PlannedCmd> {
+    // Only used when running the commands.
+    let mut output = Vec::new();
+    let mut plan = Vec::with_capacity(1 + 4 * backends.len());
+    let mut push = |args: &[&str]| {
+        let description = description(args);
+        let cmd = SnarkjsCmd {
+            args,
+            description: &description,
+            output: &mut output,
+            circuit_dir,
+        }
+        .command();
+        plan.push(PlannedCmd { description, cmd });
+    };
+
+    let wasm = format!("{name}_js/{name}.wasm");
+    let input = input_file(circuit_dir, name, generated_input)
+        .unwrap_or_else(|| String::from("input.json"));
+    push(&witness_args(&wasm, &input, &format!("{name}.wtns")));
+
+    for backend in backends {
+        let backend = backend.proving_backend();
+        let files = BackendFiles::new(name, backend, ptau);
+        for args in backend.steps(&files) {
+            push(&args);
+        }
+    }
+
+    plan
+}
+
+// Run a `snarkjs` command and measure its duration.
+fn run_timed(args: &[&str], circuit_dir: &Path, output: &mut Vec<u8>) -> Result<(bool, Duration)> {
+    let start = Instant::now();
+    let success = SnarkjsCmd {
+        args,
+        description: &description(args),
+        output,
+        circuit_dir,
+    }
+    .run()?;
+
+    Ok((success, start.elapsed()))
+}
+
+/// Compute the witness of the compiled circuit `name` from its input file (`generated_input` if
+/// the input was generated from the input spec), prove it with the
+/// proving system of the setup and verify the proof. Fails if the witness can't be computed because the input doesn't satisfy
+/// the constraints, if the proof doesn't verify or if its public signals aren't the expected ones.
+/// Also fails without an input file or a setup. Exercises which shouldn't be proved have
+/// `prove = false` in `info.toml` and don't call this function.
+pub fn prove(
+    name: &str,
+    circuit_dir: &Path,
+    generated_input: Option<&Path>,
+    setup: Option<&ProofSetup>,
+    output: &mut Vec<u8>,
+    measurements: &mut Measurements,
+) -> Result<bool> {
+    let Some(input) = input_file(circuit_dir, name, generated_input) else {
+        writeln!(output, "{}", missing_input_msg(name).bold().red())?;
+        return Ok(false);
+    };
+
+    writeln!(output, "{}", "Generating the witness...".underlined())?;
+    let wasm = format!("{name}_js/{name}.wasm");
+    let wtns = format!("{name}.wtns");
+    let (witness_success, _) = run_timed(&witness_args(&wasm, &input, &wtns), circuit_dir, output)?;
+    if !witness_success {
+        writeln!(
+            output,
+            "{}",
+            format!("Failed to compute the witness. The input `{input}` doesn't satisfy the constraints of the circuit")
+                .bold()
+                .red(),
+        )?;
+        return Ok(false);
+    }
+
+    let Some(setup) = setup else {
+        writeln!(
+            output,
+            "{}",
+            format!("The proof can't be generated because there is neither a `ptau` file in `info.toml` nor a `{CHECKED_IN_ZKEY}` next to the circuit")
+                .bold()
+                .red(),
+        )?;
+        return Ok(false);
+    };
+
+    writeln!(
+        output,
+        "{}",
+        format!("Generating the {} proof...", setup.backend().protocol()).underlined(),
+    )?;
+    let files = setup.files(name);
+    let steps = setup.steps(&files);
+    let Some((verify, steps)) = steps.split_last() else {
+        unreachable!("The steps end with the verification");
+    };
+    for args in steps {
+        if !run_timed(args, circuit_dir, output)?.0 {
+            return Ok(false);
+        }
+    }
+
+    writeln!(output, "{}", "Verifying proof...".underlined())?;
+    let verify_output_start = output.len();
+    let (verify_exit_success, verify_time) = run_timed(verify, circuit_dir, output)?;
+    measurements.verify_time = Some(verify_time);
+    let parsed =
+        tool_output::parse_verify_output(&String::from_utf8_lossy(&output[verify_output_start..]));
+    if !tool_output::combine_with_exit_status(
+        verify_exit_success,
+        parsed,
+        &description(verify),
+        output,
+    )? {
+        writeln!(
+            output,
+            "{}",
+            "The proof doesn't verify with the verification key of the circuit"
+                .bold()
+                .red(),
+        )?;
+        return Ok(false);
+    }
+
+    expected_public::check_expected_public(circuit_dir, name, &files.public, output)
+}
+
+/// Prove the compiled circuit `name` with every backend and compare the setup time, proving
+/// time, verification time and proof size in a table.
+/// Passes if the proofs of all backends verify.
+pub fn compare_backends(
+    name: &str,
+    circuit_dir: &Path,
+    generated_input: Option<&Path>,
+    ptau: &Path,
+    backends: &[Backend],
+    output: &mut Vec<u8>,
+) -> Result<bool> {
+    let Some(input) = input_file(circuit_dir, name, generated_input) else {
+        writeln!(output, "{}", missing_input_msg(name).bold().red())?;
+        return Ok(false);
+    };
+
+    writeln!(output, "{}", "Generating the witness...".underlined())?;
+    let wasm = format!("{name}_js/{name}.wasm");
+    let wtns = format!("{name}.wtns");
+    let (witness_success, _) = run_timed(&witness_args(&wasm, &input, &wtns), circuit_dir, output)?;
+    if !witness_success {
+        return Ok(false);
+    }
+
+    let mut results = Vec::with_capacity(backends.len());
+    for backend in backends {
+        let backend = backend.proving_backend();
+        let protocol = backend.protocol();
+        let files = BackendFiles::new(name, backend, ptau);
+        let [setup, export, prove, verify] = backend.steps(&files);
+
+        writeln!(
+            output,
+            "{}",
+            format!("Proving with {protocol}...").underlined()
+        )?;
+
+        let (setup_success, setup_time) = run_timed(&setup, circuit_dir, output)?;
+        if !setup_success || !run_timed(&export, circuit_dir, output)?.0 {
+            return Ok(false);
+        }
+
+        let (prove_success, prove_time) = run_timed(&prove, circuit_dir, output)?;
+        if !prove_success {
+            return Ok(false);
+        }
+
+        let verify_output_start = output.len();
+        let (verify_exit_success, verify_time) = run_timed(&verify, circuit_dir, output)?;
+        let parsed = tool_output::parse_verify_output(&String::from_utf8_lossy(
+            &output[verify_output_start..],
+        ));
+        if !tool_output::combine_with_exit_status(
+            verify_exit_success,
+            parsed,
+            &description(&verify),
+            output,
+        )? {
+            writeln!(
+                output,
+                "{}",
+                format!("The {protocol} proof doesn't verify").bold().red(),
+            )?;
+            return Ok(false);
+        }
+
+        let proof_path = circuit_dir.join(&files.proof);
+        let proof_size = fs::metadata(&proof_path)
+            .with_context(|| format!("Failed to read the size of {}", proof_path.display()))?
+            .len();
+
+        results.push(BackendResult {
+            protocol,
+            setup_time,
+            prove_time,
+            verify_time,
+            proof_size,
+        });
+    }
+
+    writeln!(output, "{}", "Comparison of the backends:".underlined())?;
+    write_comparison(&results, output)?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backend_steps() {
+        let files = BackendFiles::new(
+            "mul",
+            Backend::Plonk.proving_backend(),
+            Path::new("pot8.ptau"),
+        );
+        let [setup, export, prove, verify] = Backend::Plonk.proving_backend().steps(&files);
+        assert_eq!(
+            setup,
+            ["plonk", "setup", "mul.r1cs", "pot8.ptau", "mul.plonk.zkey"]
+        );
+        assert_eq!(
+            export,
+            [
+                "zkey",
+                "export",
+                "verificationkey",
+                "mul.plonk.zkey",
+                "mul.plonk.vkey.json"
+            ],
+        );
+        assert_eq!(
+            prove,
+            [
+                "plonk",
+                "prove",
+                "mul.plonk.zkey",
+                "mul.wtns",
+                "mul.plonk.proof.json",
+                "mul.plonk.public.json"
+            ],
+        );
+        assert_eq!(
+            verify,
+            [
+                "plonk",
+                "verify",
+                "mul.plonk.vkey.json",
+                "mul.plonk.public.json",
+                "mul.plonk.proof.json"
+            ],
+        );
+
+        let plan = plan_backends(
+            "mul",
+            Path::new(""),
+            None,
+            Path::new("pot8.ptau"),
+            &[Backend::Groth16, Backend::Plonk],
+        );
+        let descriptions = plan
+            .iter()
+            .map(|planned| planned.description.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            descriptions,
+            [
+                "snarkjs wtns calculate …",
+                "snarkjs groth16 setup …",
+                "snarkjs zkey export …",
+                "snarkjs groth16 prove …",
+                "snarkjs groth16 verify …",
+                "snarkjs plonk setup …",
+                "snarkjs zkey export …",
+                "snarkjs plonk prove …",
+                "snarkjs plonk verify …",
+            ],
+        );
+    }
+
+    #[test]
+    fn generated_input_outside_circuit_dir() {
+        let input = Path::new("/tmp/zklings-input/mul.input.json");
+        let plan = plan_proof("mul", Path::new("missing"), Some(input), None);
+        assert_eq!(plan.len(), 1);
+        let args = plan[0].cmd.get_args().collect::<Vec<_>>();
+        assert!(args.contains(&input.as_os_str()));
+
+        assert!(plan_proof("mul", Path::new("missing"), None, None).is_empty());
+    }
+
+    #[test]
+    fn checked_in_zkey_skips_setup() {
+        let setup = ProofSetup::Groth16Zkey(String::from("mul.circuit_final.zkey"));
+        let files = setup.files("mul");
+        let steps = setup.steps(&files);
+
+        assert_eq!(steps.len(), 3);
+        assert_eq!(
+            steps[0],
+            [
+                "zkey",
+                "export",
+                "verificationkey",
+                "mul.circuit_final.zkey",
+                "mul.groth16.vkey.json"
+            ],
+        );
+        assert_eq!(
+            steps[1][..3],
+            ["groth16", "prove", "mul.circuit_final.zkey"]
+        );
+        assert_eq!(steps[2][..2], ["groth16", "verify"]);
+
+        let setup = ProofSetup::Groth16Ptau(PathBuf::from("pot8.ptau"));
+        let files = setup.files("mul");
+        assert_eq!(
+            setup.steps(&files)[0],
+            [
+                "groth16",
+                "setup",
+                "mul.r1cs",
+                "pot8.ptau",
+                "mul.groth16.zkey"
+            ],
+        );
+    }
+
+    #[test]
+    fn plonk_setup_ignores_checked_in_zkey() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        fs::write(dir.join(CHECKED_IN_ZKEY), "").unwrap();
+        let ptau = || Some(PathBuf::from("pot8.ptau"));
+
+        assert!(matches!(
+            ProofSetup::new(dir, "mul", Backend::Groth16, ptau()),
+            Some(ProofSetup::Groth16Zkey(_)),
+        ));
+        let setup = ProofSetup::new(dir, "mul", Backend::Plonk, ptau()).unwrap();
+        assert!(matches!(setup, ProofSetup::Plonk(_)));
+        assert!(ProofSetup::new(dir, "mul", Backend::Plonk, None).is_none());
+
+        let files = setup.files("mul");
+        let steps = setup.steps(&files);
+        assert_eq!(steps.len(), 4);
+        assert_eq!(
+            steps[0],
+            ["plonk", "setup", "mul.r1cs", "pot8.ptau", "mul.plonk.zkey"]
+        );
+        assert_eq!(steps[3][..2], ["plonk", "verify"]);
+    }
+
+    #[test]
+    fn comparison_table() {
+        let results = [
+            BackendResult {
+                protocol: "groth16",
+                setup_time: Duration::from_millis(1200),
+                prove_time: Duration::from_millis(340),
+                verify_time: Duration::from_millis(250),
+                proof_size: 805,
+            },
+            BackendResult {
+                protocol: "plonk",
+                setup_time: Duration::from_millis(2500),
+                prove_time: Duration::from_millis(910),
+                verify_time: Duration::from_millis(270),
+                proof_size: 2251,
+            },
+        ];
+
+        let mut output = Vec::new();
+        write_comparison(&results, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains("Backend"));
+        assert!(lines[0].contains("Proof size"));
+        assert_eq!(
+            lines[1],
+            "groth16        1200 ms      340 ms      250 ms         805 B",
+        );
+        assert_eq!(
+            lines[2],
+            "plonk          2500 ms      910 ms      270 ms        2251 B",
+        );
+    }
+
+    #[test]
+    fn missing_input_fails() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+
+        let mut output = Vec::new();
+        let passed = prove(
+            "mul",
+            dir,
+            None,
+            None,
+            &mut output,
+            &mut Measurements::default(),
+        )
+        .unwrap();
+        assert!(!passed);
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("The exercise needs an input file"));
+    }
+}
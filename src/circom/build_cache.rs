@@ -0,0 +1,129 @@
+// Skip compiling a circuit whose compiled files are newer than its sources.
+// The sources are the circuit file and the files that it includes transitively. Includes of
+// libraries which are resolved by `circom` with `-l` can't be found and are ignored.
+
+use hashbrown::HashSet;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::SystemTime,
+};
+
+static FORCE_COMPILE: AtomicBool = AtomicBool::new(false);
+
+/// Always compile circuits, for example to recover from a corrupted build. Set by the global
+/// argument `--force-compile`.
+pub fn force_compile() {
+    FORCE_COMPILE.store(true, Ordering::Relaxed);
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+// The paths in the include statements of a circuit like `include "utils.circom";`.
+fn includes(circuit: &str) -> impl Iterator<Item = &str> {
+    circuit.lines().filter_map(|line| {
+        let rest = line.trim_start().strip_prefix("include")?;
+        let rest = rest.trim_start().strip_prefix('"')?;
+        rest.split_once('"').map(|(include, _)| include)
+    })
+}
+
+// The circuit file and all included files which exist relative to the including file.
+fn circuit_sources(circuit_path: &Path) -> Vec<PathBuf> {
+    let mut sources = Vec::new();
+    let mut visited = HashSet::new();
+    let mut pending = vec![circuit_path.to_path_buf()];
+
+    while let Some(path) = pending.pop() {
+        let Ok(circuit) = fs::read_to_string(&path) else {
+            continue;
+        };
+        if !visited.insert(path.clone()) {
+            continue;
+        }
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        pending.extend(includes(&circuit).map(|include| dir.join(include)));
+        sources.push(path);
+    }
+
+    sources
+}
+
+/// The compiled files of the circuit `name` in `circuit_dir` exist and are newer than all of
+/// its sources.
+pub fn is_fresh(circuit_dir: &Path, circuit_path: &Path, name: &str) -> bool {
+    if FORCE_COMPILE.load(Ordering::Relaxed) {
+        return false;
+    }
+
+    // A missing circuit file has to be reported by `circom`.
+    if modified(circuit_path).is_none() {
+        return false;
+    }
+
+    let compiled = [
+        format!("{name}.r1cs"),
+        format!("{name}_js/{name}.wasm"),
+        format!("{name}.sym"),
+    ];
+    let Some(oldest_compiled) = compiled
+        .iter()
+        .map(|file| modified(&circuit_dir.join(file)))
+        .collect::<Option<Vec<_>>>()
+        .and_then(|times| times.into_iter().min())
+    else {
+        return false;
+    };
+
+    circuit_sources(circuit_path)
+        .iter()
+        .all(|source| modified(source).is_some_and(|time| time < oldest_compiled))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, time::Duration};
+
+    use super::*;
+
+    #[test]
+    fn changed_include_invalidates_build() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        fs::create_dir_all(dir.join("mul_js")).unwrap();
+        let circuit = dir.join("mul.circom");
+        fs::write(
+            &circuit,
+            "pragma circom 2.0.0;\ninclude \"utils.circom\";\ninclude \"circomlib/poseidon.circom\";\n",
+        )
+        .unwrap();
+        fs::write(dir.join("utils.circom"), "template T() {}\n").unwrap();
+
+        let set_modified = |file: &str, secs: u64| {
+            File::options()
+                .write(true)
+                .open(dir.join(file))
+                .unwrap()
+                .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+                .unwrap();
+        };
+        set_modified("mul.circom", 100);
+        set_modified("utils.circom", 100);
+        assert!(!is_fresh(dir, &circuit, "mul"));
+
+        for file in ["mul.r1cs", "mul_js/mul.wasm", "mul.sym"] {
+            fs::write(dir.join(file), "").unwrap();
+            set_modified(file, 200);
+        }
+        assert!(is_fresh(dir, &circuit, "mul"));
+
+        set_modified("utils.circom", 300);
+        assert!(!is_fresh(dir, &circuit, "mul"));
+    }
+}
@@ -0,0 +1,170 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::{collections::HashMap, fs, path::Path};
+
+// The signal with the index 0 is the constant 1.
+const CONSTANT_SIGNAL: &str = "0";
+
+// A linear combination mapping signal indices to coefficients.
+type LinearCombination = HashMap<String, String>;
+
+// The file `NAME_constraints.json` written by `circom --json`.
+// Every constraint `[A, B, C]` means `A * B - C = 0`.
+#[derive(Deserialize)]
+struct ConstraintsFile {
+    constraints: Vec<[LinearCombination; 3]>,
+}
+
+/// The number of constraints of a circuit per category.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct ConstraintCategories {
+    /// `A` or `B` is constant.
+    pub linear: u64,
+    /// `b * (b - 1) = 0` which forces `b` to be a bit, usually as part of a range check.
+    pub boolean: u64,
+    /// Every other constraint multiplying two signals.
+    pub nonlinear: u64,
+}
+
+fn non_constant_signals(lc: &LinearCombination) -> impl Iterator<Item = &str> {
+    lc.keys()
+        .map(String::as_str)
+        .filter(|signal| *signal != CONSTANT_SIGNAL)
+}
+
+fn is_boolean([a, b, c]: &[LinearCombination; 3]) -> bool {
+    let mut a_signals = non_constant_signals(a);
+    let mut b_signals = non_constant_signals(b);
+
+    match (
+        a_signals.next(),
+        a_signals.next(),
+        b_signals.next(),
+        b_signals.next(),
+    ) {
+        (Some(a_signal), None, Some(b_signal), None) => {
+            a_signal == b_signal && non_constant_signals(c).next().is_none()
+        }
+        _ => false,
+    }
+}
+
+impl ConstraintCategories {
+    fn parse(constraints_json: &str) -> Result<Self> {
+        let file = serde_json::from_str::<ConstraintsFile>(constraints_json)?;
+        let mut categories = Self::default();
+
+        for constraint in &file.constraints {
+            let [a, b, _] = constraint;
+            if non_constant_signals(a).next().is_none() || non_constant_signals(b).next().is_none()
+            {
+                categories.linear += 1;
+            } else if is_boolean(constraint) {
+                categories.boolean += 1;
+            } else {
+                categories.nonlinear += 1;
+            }
+        }
+
+        Ok(categories)
+    }
+
+    /// Categorize the constraints in the file `NAME_constraints.json` in `dir`.
+    pub fn read(dir: &Path, name: &str) -> Result<Self> {
+        let path = dir.join(format!("{name}_constraints.json"));
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read the file {}", path.display()))?;
+
+        Self::parse(&content)
+            .with_context(|| format!("Failed to parse the file {}", path.display()))
+    }
+}
+
+fn describe(delta: i128, category: &str, guess: &str) -> Option<String> {
+    let (amount, comparison) = match delta {
+        0 => return None,
+        1.. => (delta, "fewer"),
+        _ => (-delta, "more"),
+    };
+
+    Some(format!(
+        "The solution uses {amount} {comparison} {category} constraints, {guess}"
+    ))
+}
+
+/// Explain the differences between the constraints of the learner's circuit and those of the
+/// solution. The explanations are guesses based on the categories of the constraints.
+pub fn explain_delta(learner: ConstraintCategories, solution: ConstraintCategories) -> Vec<String> {
+    let delta = |learner: u64, solution: u64| i128::from(learner) - i128::from(solution);
+
+    [
+        describe(
+            delta(learner.boolean, solution.boolean),
+            "bit",
+            "possibly by avoiding a range check or using fewer bits for it",
+        ),
+        describe(
+            delta(learner.nonlinear, solution.nonlinear),
+            "nonlinear",
+            "possibly by reusing products or replacing multiplications with additions",
+        ),
+        describe(
+            delta(learner.linear, solution.linear),
+            "linear",
+            "possibly by assigning expressions directly instead of through intermediate signals",
+        ),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `in * in = sq` and a bit check `bit * (bit - 1) = 0` twice.
+    const LEARNER: &str = r#"{
+"constraints": [
+[{"2":"1"},{"2":"1"},{"1":"1"}],
+[{"3":"1"},{"3":"1","0":"21888242871839275222246405745257275088548364400416034343698204186575808495616"},{}],
+[{"4":"1"},{"4":"1","0":"21888242871839275222246405745257275088548364400416034343698204186575808495616"},{}],
+[{},{},{"1":"1","5":"21888242871839275222246405745257275088548364400416034343698204186575808495616"}]
+]
+}"#;
+    const SOLUTION: &str = r#"{
+"constraints": [
+[{"2":"1"},{"2":"1"},{"1":"1"}]
+]
+}"#;
+
+    #[test]
+    fn categorized_delta() {
+        let learner = ConstraintCategories::parse(LEARNER).unwrap();
+        let solution = ConstraintCategories::parse(SOLUTION).unwrap();
+        assert_eq!(
+            learner,
+            ConstraintCategories {
+                linear: 1,
+                boolean: 2,
+                nonlinear: 1,
+            },
+        );
+        assert_eq!(
+            solution,
+            ConstraintCategories {
+                linear: 0,
+                boolean: 0,
+                nonlinear: 1,
+            },
+        );
+
+        let explanations = explain_delta(learner, solution);
+        assert_eq!(explanations.len(), 2);
+        assert!(explanations[0].starts_with("The solution uses 2 fewer bit constraints"));
+        assert!(explanations[1].starts_with("The solution uses 1 fewer linear constraints"));
+
+        assert!(explain_delta(solution, solution).is_empty());
+        assert!(explain_delta(solution, learner)[0].contains("2 more bit constraints"));
+    }
+}
@@ -0,0 +1,128 @@
+// Compare the public signals of a verified proof with the ones expected by the author of the
+// exercise. A proof of a satisfiable circuit which computes the wrong outputs still verifies.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::{fs, io::Write, path::Path};
+
+use super::circuit_file;
+use crate::color::Stylize;
+
+/// The public signals which the circuit has to compute. Either `NAME.expected.json` or
+/// `expected.json` next to the circuit. The format is the one of `public.json` of `snarkjs`.
+const EXPECTED_FILE: &str = "expected.json";
+
+// The signals are decimal strings in `public.json`. Authors can also write small numbers.
+fn parse_signals(json: &[u8]) -> Result<Vec<String>, String> {
+    let signals = match serde_json::from_slice::<Value>(json) {
+        Ok(Value::Array(signals)) => signals,
+        Ok(_) => return Err(String::from("It isn't an array of public signals")),
+        Err(e) => return Err(format!("It isn't valid JSON: {e}")),
+    };
+
+    signals
+        .into_iter()
+        .map(|signal| match signal {
+            Value::String(signal) => Ok(signal.trim().to_string()),
+            Value::Number(signal) if signal.is_u64() => Ok(signal.to_string()),
+            signal => Err(format!(
+                "The signal `{signal}` is neither a decimal string nor a non-negative integer"
+            )),
+        })
+        .collect()
+}
+
+// The lines of the differences between the expected and the computed signals.
+fn signal_diff(expected: &[String], computed: &[String]) -> Vec<String> {
+    let mut diff = Vec::new();
+
+    if expected.len() != computed.len() {
+        diff.push(format!(
+            "{} public signals are expected but the circuit has {}",
+            expected.len(),
+            computed.len(),
+        ));
+    }
+
+    for (ind, (expected, computed)) in expected.iter().zip(computed).enumerate() {
+        if expected != computed {
+            diff.push(format!("- [{ind}] {expected}"));
+            diff.push(format!("+ [{ind}] {computed}"));
+        }
+    }
+
+    diff
+}
+
+/// Check the public signals in the file `public` of the proof against the expected ones.
+/// Passes if there is no file with the expected signals.
+pub fn check_expected_public(
+    circuit_dir: &Path,
+    name: &str,
+    public: &str,
+    output: &mut Vec<u8>,
+) -> Result<bool> {
+    let Some(expected_file) = circuit_file(circuit_dir, name, EXPECTED_FILE) else {
+        return Ok(true);
+    };
+
+    let read = |file: &str| {
+        let path = circuit_dir.join(file);
+        fs::read(&path).with_context(|| format!("Failed to read the file {}", path.display()))
+    };
+    let expected = parse_signals(&read(&expected_file)?);
+    let computed = parse_signals(&read(public)?);
+
+    let diff = match (expected, computed) {
+        (Ok(expected), Ok(computed)) => signal_diff(&expected, &computed),
+        (Err(e), _) => vec![format!("`{expected_file}`: {e}")],
+        (_, Err(e)) => vec![format!("`{public}`: {e}")],
+    };
+    if diff.is_empty() {
+        return Ok(true);
+    }
+
+    writeln!(
+        output,
+        "{}",
+        format!(
+            "The proof verifies but its public signals don't match `{expected_file}` (- expected, + computed):"
+        )
+        .bold()
+        .red(),
+    )?;
+    for line in diff {
+        if line.starts_with('+') {
+            writeln!(output, "  {}", line.red())?;
+        } else {
+            writeln!(output, "  {line}")?;
+        }
+    }
+
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrong_output() {
+        let expected = parse_signals(br#"["33", 2]"#).unwrap();
+        let computed = parse_signals(br#"["32", "2"]"#).unwrap();
+        assert_eq!(signal_diff(&expected, &computed), ["- [0] 33", "+ [0] 32"]);
+        assert!(signal_diff(&expected, &expected).is_empty());
+
+        assert_eq!(
+            signal_diff(&expected, &computed[..1]),
+            [
+                "2 public signals are expected but the circuit has 1",
+                "- [0] 33",
+                "+ [0] 32",
+            ],
+        );
+
+        assert!(parse_signals(b"{}").is_err());
+        assert!(parse_signals(b"[-1]").is_err());
+    }
+}
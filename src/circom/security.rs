@@ -0,0 +1,123 @@
+use std::io::{self, Write};
+
+use crate::color::Stylize;
+
+/// The prime of `circom` if none is chosen.
+pub const DEFAULT_PRIME: &str = "bn128";
+
+// The primes of `circom --prime` which `snarkjs` supports with the curve and the approximate
+// security level of the curve in bits. The pairing-friendly curves lost bits to the (ex)TNFS
+// attacks on their pairings. The other primes of `circom` like `pallas` have no pairing which
+// Groth16 and PLONK in `snarkjs` require.
+const SECURITY_LEVELS: [(&str, &str, u32); 2] =
+    [("bn128", "BN254", 100), ("bls12381", "BLS12-381", 117)];
+
+/// Check that `snarkjs` supports the `prime` of `circom`.
+/// Explains the failure in the `output` buffer.
+pub fn check_prime(prime: &str, output: &mut Vec<u8>) -> io::Result<bool> {
+    if SECURITY_LEVELS.iter().any(|(name, _, _)| *name == prime) {
+        return Ok(true);
+    }
+
+    let supported = SECURITY_LEVELS
+        .iter()
+        .map(|(name, _, _)| *name)
+        .collect::<Vec<_>>();
+    writeln!(
+        output,
+        "{}",
+        format!(
+            "The prime `{prime}` of the exercise isn't supported by `snarkjs`. Supported primes: {}",
+            supported.join(", "),
+        )
+        .red(),
+    )?;
+
+    Ok(false)
+}
+
+/// Check that the curve of the `prime` provides at least `min_bits` of security.
+/// Explains the failure in the `output` buffer.
+pub fn check_security_level(prime: &str, min_bits: u32, output: &mut Vec<u8>) -> io::Result<bool> {
+    let Some(&(_, curve, bits)) = SECURITY_LEVELS.iter().find(|(name, _, _)| *name == prime) else {
+        writeln!(
+            output,
+            "{}",
+            format!(
+                "The prime `{prime}` isn't supported by `snarkjs`. The exercise requires at least {min_bits} bits of security"
+            )
+            .red(),
+        )?;
+        return Ok(false);
+    };
+
+    if bits >= min_bits {
+        writeln!(
+            output,
+            "The curve {curve} provides about {bits} bits of security (at least {min_bits} required)",
+        )?;
+        return Ok(true);
+    }
+
+    let alternatives = SECURITY_LEVELS
+        .iter()
+        .filter(|(_, _, bits)| *bits >= min_bits)
+        .map(|(name, _, bits)| format!("{name} ({bits} bits)"))
+        .collect::<Vec<_>>();
+
+    writeln!(
+        output,
+        "{}",
+        format!(
+            "The curve {curve} (prime `{prime}`) only provides about {bits} bits of security but the exercise requires at least {min_bits}.
+An attacker would need about 2^{bits} operations to break it.",
+        )
+        .red(),
+    )?;
+    if alternatives.is_empty() {
+        writeln!(output, "No supported curve reaches this level")?;
+    } else {
+        writeln!(
+            output,
+            "Set `prime` of the exercise in `info.toml` to a sufficient prime: {}",
+            alternatives.join(", "),
+        )?;
+    }
+
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn under_parameterized_curve() {
+        let mut output = Vec::new();
+        assert!(!check_security_level("bn128", 110, &mut output).unwrap());
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("only provides about 100 bits of security"));
+        assert!(output.contains("requires at least 110"));
+        assert!(output.contains("bls12381 (117 bits)"));
+        assert!(!output.contains("bn128 (100 bits)"));
+
+        let mut output = Vec::new();
+        assert!(check_security_level("bls12381", 110, &mut output).unwrap());
+        assert!(!check_security_level("bls12381", 118, &mut output).unwrap());
+        // Secure curves without a pairing aren't suggested.
+        assert!(!check_security_level("pallas", 110, &mut output).unwrap());
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("No supported curve reaches this level"));
+        assert!(output.contains("The prime `pallas` isn't supported by `snarkjs`"));
+    }
+
+    #[test]
+    fn unsupported_prime() {
+        assert!(check_prime("bls12381", &mut Vec::new()).unwrap());
+
+        let mut output = Vec::new();
+        assert!(!check_prime("vesta", &mut output).unwrap());
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Supported primes: bn128, bls12381"));
+    }
+}
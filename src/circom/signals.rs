@@ -0,0 +1,370 @@
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+use std::{
+    fmt::{self, Display, Formatter},
+    fs,
+    io::Write,
+    path::Path,
+};
+
+use super::circuit_file;
+use crate::color::Stylize;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Visibility {
+    PublicOutput,
+    PublicInput,
+    SecretInput,
+}
+
+impl Visibility {
+    #[inline]
+    fn is_input(self) -> bool {
+        self != Self::PublicOutput
+    }
+}
+
+impl Display for Visibility {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::PublicOutput => f.write_str("public output"),
+            Self::PublicInput => f.write_str("public input"),
+            Self::SecretInput => f.write_str("secret input"),
+        }
+    }
+}
+
+/// A signal of the main component.
+/// The elements of an array signal are merged into one signal with the array's name.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Signal {
+    pub name: String,
+    pub visibility: Visibility,
+}
+
+/// The numbers of signals and constraints from the header section of an `.r1cs` file.
+#[derive(Clone, Copy)]
+pub struct R1csHeader {
+    n_pub_out: u32,
+    n_pub_in: u32,
+    n_prv_in: u32,
+    pub n_constraints: u32,
+}
+
+impl R1csHeader {
+    /// The number of public signals (outputs and public inputs).
+    pub fn n_public(self) -> u32 {
+        self.n_pub_out + self.n_pub_in
+    }
+
+    // The wires are ordered: The constant 1, public outputs, public inputs, private inputs and
+    // then all remaining (internal) signals.
+    fn visibility(self, wire: u32) -> Option<Visibility> {
+        let pub_in_start = 1 + self.n_pub_out;
+        let prv_in_start = pub_in_start + self.n_pub_in;
+
+        if wire == 0 {
+            None
+        } else if wire < pub_in_start {
+            Some(Visibility::PublicOutput)
+        } else if wire < prv_in_start {
+            Some(Visibility::PublicInput)
+        } else if wire < prv_in_start + self.n_prv_in {
+            Some(Visibility::SecretInput)
+        } else {
+            None
+        }
+    }
+}
+
+fn read_u32(bytes: &[u8], pos: usize) -> Option<u32> {
+    let bytes = bytes.get(pos..pos + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn read_u64(bytes: &[u8], pos: usize) -> Option<u64> {
+    let bytes = bytes.get(pos..pos + 8)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+// See https://github.com/iden3/r1csfile/blob/master/doc/r1cs_bin_format.md
+fn parse_r1cs_header(bytes: &[u8]) -> Result<R1csHeader> {
+    const HEADER_SECTION: u32 = 1;
+
+    if bytes.get(..4) != Some(b"r1cs") {
+        bail!("Not an `.r1cs` file");
+    }
+
+    let n_sections = read_u32(bytes, 8).context("Truncated `.r1cs` file")?;
+
+    // Magic, version and number of sections.
+    let mut pos = 12;
+    for _ in 0..n_sections {
+        let section_type = read_u32(bytes, pos).context("Truncated `.r1cs` file")?;
+        let section_size = read_u64(bytes, pos + 4).context("Truncated `.r1cs` file")?;
+        pos += 12;
+
+        if section_type == HEADER_SECTION {
+            // The field element size is followed by the prime and the number of wires.
+            let field_size = read_u32(bytes, pos).context("Truncated `.r1cs` header")? as usize;
+            let counts = pos + 4 + field_size + 4;
+            let header = R1csHeader {
+                n_pub_out: read_u32(bytes, counts).context("Truncated `.r1cs` header")?,
+                n_pub_in: read_u32(bytes, counts + 4).context("Truncated `.r1cs` header")?,
+                n_prv_in: read_u32(bytes, counts + 8).context("Truncated `.r1cs` header")?,
+                // After the number of labels (`u64`).
+                n_constraints: read_u32(bytes, counts + 20).context("Truncated `.r1cs` header")?,
+            };
+
+            return Ok(header);
+        }
+
+        pos += usize::try_from(section_size).context("Invalid `.r1cs` section size")?;
+    }
+
+    bail!("The `.r1cs` file has no header section")
+}
+
+// Every line of a `.sym` file has the format `LABEL,WITNESS,COMPONENT,NAME`.
+// The witness index is the wire index or -1 if the signal was optimized away.
+fn parse_sym(sym: &str, header: R1csHeader) -> Vec<Signal> {
+    let mut signals: Vec<Signal> = Vec::new();
+
+    for line in sym.lines() {
+        let mut fields = line.splitn(4, ',');
+        let (Some(_), Some(wire), Some(_), Some(name)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+
+        let Some(visibility) = wire.parse().ok().and_then(|wire| header.visibility(wire)) else {
+            continue;
+        };
+
+        // Only signals of the main component can be public or inputs.
+        let Some(name) = name.strip_prefix("main.") else {
+            continue;
+        };
+        let name = name.split_once('[').map_or(name, |(name, _)| name);
+
+        if !signals.iter().any(|signal| signal.name == name) {
+            signals.push(Signal {
+                name: name.to_string(),
+                visibility,
+            });
+        }
+    }
+
+    signals
+}
+
+/// Read the header of the `.r1cs` file of the compiled circuit `name`.
+pub fn r1cs_header(circuit_dir: &Path, name: &str) -> Result<R1csHeader> {
+    let r1cs_path = circuit_dir.join(format!("{name}.r1cs"));
+    let r1cs = fs::read(&r1cs_path)
+        .with_context(|| format!("Failed to read the file {}", r1cs_path.display()))?;
+
+    parse_r1cs_header(&r1cs)
+        .with_context(|| format!("Failed to parse the file {}", r1cs_path.display()))
+}
+
+/// Read the inputs and outputs of the main component of the compiled circuit `name`.
+pub fn main_signals(circuit_dir: &Path, name: &str, header: R1csHeader) -> Result<Vec<Signal>> {
+    let sym_path = circuit_dir.join(format!("{name}.sym"));
+    let sym = fs::read_to_string(&sym_path)
+        .with_context(|| format!("Failed to read the file {}", sym_path.display()))?;
+
+    Ok(parse_sym(&sym, header))
+}
+
+pub fn write_signals(signals: &[Signal], output: &mut Vec<u8>) -> Result<()> {
+    writeln!(output, "{}", "Signals of the main component:".underlined())?;
+
+    for signal in signals {
+        writeln!(
+            output,
+            "  {} {}",
+            signal.visibility,
+            signal.name.as_str().bold()
+        )?;
+    }
+
+    Ok(())
+}
+
+fn check_inputs(
+    signals: &[Signal],
+    input: &Map<String, Value>,
+    output: &mut Vec<u8>,
+) -> Result<bool> {
+    let mut success = true;
+
+    for signal in signals {
+        if signal.visibility.is_input() && !input.contains_key(&signal.name) {
+            success = false;
+            writeln!(
+                output,
+                "{}",
+                format!(
+                    "Missing {} `{}` in `input.json`",
+                    signal.visibility, signal.name
+                )
+                .red(),
+            )?;
+        }
+    }
+
+    for key in input.keys() {
+        match signals.iter().find(|signal| &signal.name == key) {
+            Some(signal) if signal.visibility.is_input() => (),
+            Some(signal) => {
+                success = false;
+                writeln!(
+                    output,
+                    "{}",
+                    format!(
+                        "`{key}` in `input.json` is a {} and can't be provided",
+                        signal.visibility
+                    )
+                    .red(),
+                )?;
+            }
+            None => {
+                success = false;
+                writeln!(
+                    output,
+                    "{}",
+                    format!("`{key}` in `input.json` isn't an input of the main component").red(),
+                )?;
+            }
+        }
+    }
+
+    Ok(success)
+}
+
+/// Check that the input file (`generated_input` or the `input.json` next to the circuit if it
+/// exists) provides exactly its inputs.
+pub fn check_input_file(
+    circuit_dir: &Path,
+    name: &str,
+    generated_input: Option<&Path>,
+    signals: &[Signal],
+    output: &mut Vec<u8>,
+) -> Result<bool> {
+    let input_path = match generated_input {
+        Some(path) => path.to_path_buf(),
+        None => match circuit_file(circuit_dir, name, "input.json") {
+            Some(input_file) => circuit_dir.join(input_file),
+            None => return Ok(true),
+        },
+    };
+    let input = fs::read(&input_path)
+        .with_context(|| format!("Failed to read the file {}", input_path.display()))?;
+    let Ok(input) = serde_json::from_slice::<Map<String, Value>>(&input) else {
+        writeln!(
+            output,
+            "{}",
+            format!("`{}` isn't a JSON object", input_path.display()).red(),
+        )?;
+        return Ok(false);
+    };
+
+    check_inputs(signals, &input, output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: R1csHeader = R1csHeader {
+        n_pub_out: 1,
+        n_pub_in: 1,
+        n_prv_in: 2,
+        n_constraints: 3,
+    };
+
+    // `component main {public [b]} = Example();` with `signal input a[2];`, `signal input b;`
+    // and `signal output c;`.
+    const SYM: &str = "1,1,0,main.c
+2,2,0,main.b
+3,3,0,main.a[0]
+4,4,0,main.a[1]
+5,-1,0,main.tmp
+6,5,1,main.mul.in
+7,6,0,main.tmp2";
+
+    #[test]
+    fn r1cs_header() {
+        let mut r1cs = b"r1cs".to_vec();
+        // Version and number of sections.
+        r1cs.extend_from_slice(&1_u32.to_le_bytes());
+        r1cs.extend_from_slice(&2_u32.to_le_bytes());
+        // A constraints section before the header section.
+        r1cs.extend_from_slice(&2_u32.to_le_bytes());
+        r1cs.extend_from_slice(&3_u64.to_le_bytes());
+        r1cs.extend_from_slice(&[0; 3]);
+        // Header section with a 1 byte field.
+        r1cs.extend_from_slice(&1_u32.to_le_bytes());
+        r1cs.extend_from_slice(&33_u64.to_le_bytes());
+        r1cs.extend_from_slice(&1_u32.to_le_bytes());
+        r1cs.push(7);
+        for n in [7_u32, 1, 1, 3] {
+            r1cs.extend_from_slice(&n.to_le_bytes());
+        }
+        // Number of labels and constraints.
+        r1cs.extend_from_slice(&9_u64.to_le_bytes());
+        r1cs.extend_from_slice(&4_u32.to_le_bytes());
+
+        let header = parse_r1cs_header(&r1cs).unwrap();
+        assert_eq!(header.n_pub_out, 1);
+        assert_eq!(header.n_pub_in, 1);
+        assert_eq!(header.n_prv_in, 3);
+        assert_eq!(header.n_constraints, 4);
+
+        assert!(parse_r1cs_header(b"r1cs").is_err());
+        assert!(parse_r1cs_header(b"wasm\0\0\0\0\0\0\0\0").is_err());
+    }
+
+    #[test]
+    fn visibility() {
+        let signals = parse_sym(SYM, HEADER);
+        assert_eq!(
+            signals,
+            [
+                Signal {
+                    name: String::from("c"),
+                    visibility: Visibility::PublicOutput,
+                },
+                Signal {
+                    name: String::from("b"),
+                    visibility: Visibility::PublicInput,
+                },
+                Signal {
+                    name: String::from("a"),
+                    visibility: Visibility::SecretInput,
+                },
+            ],
+        );
+
+        let mut output = Vec::new();
+        write_signals(&signals, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("public output "));
+        assert!(output.contains("public input "));
+        assert!(output.contains("secret input "));
+
+        let input = serde_json::json!({ "b": "3", "c": "4" });
+        let mut output = Vec::new();
+        assert!(!check_inputs(&signals, input.as_object().unwrap(), &mut output).unwrap());
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Missing secret input `a`"));
+        assert!(output.contains("`c` in `input.json` is a public output"));
+
+        let input = serde_json::json!({ "a": ["1", "2"], "b": "3" });
+        let mut output = Vec::new();
+        assert!(check_inputs(&signals, input.as_object().unwrap(), &mut output).unwrap());
+        assert!(output.is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+use clap::ValueEnum;
+use std::fmt::Write;
+
+/// The format of the circuit structure printed by `zklings structure`.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum StructureFormat {
+    /// A textual tree of the components
+    Tree,
+    /// A graph in the DOT language of Graphviz
+    Dot,
+}
+
+/// A component instance with the names of its signals and subcomponents.
+/// The elements of an array signal are merged into one signal with the array's name.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Component {
+    pub name: String,
+    pub signals: Vec<String>,
+    pub children: Vec<Component>,
+}
+
+impl Component {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            signals: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn child(&mut self, name: &str) -> &mut Self {
+        let ind = match self.children.iter().position(|child| child.name == name) {
+            Some(ind) => ind,
+            None => {
+                self.children.push(Self::new(name));
+                self.children.len() - 1
+            }
+        };
+
+        &mut self.children[ind]
+    }
+}
+
+/// Build the component hierarchy from the signal names in a `.sym` file.
+/// Every line has the format `LABEL,WITNESS,COMPONENT,NAME` with a name like `main.mul.in`.
+/// This is only an approximation of the circuit: Components without signals and the
+/// constraints between signals don't appear in the symbol table.
+pub fn parse_components(sym: &str) -> Component {
+    let mut main = Component::new("main");
+
+    for line in sym.lines() {
+        let Some(name) = line.splitn(4, ',').nth(3) else {
+            continue;
+        };
+        let Some(name) = name.trim().strip_prefix("main.") else {
+            continue;
+        };
+
+        let mut segments = name.split('.').collect::<Vec<_>>();
+        let Some(signal) = segments.pop() else {
+            continue;
+        };
+        let signal = signal.split_once('[').map_or(signal, |(signal, _)| signal);
+
+        let mut component = &mut main;
+        for segment in segments {
+            component = component.child(segment);
+        }
+
+        if !component.signals.iter().any(|known| known == signal) {
+            component.signals.push(signal.to_string());
+        }
+    }
+
+    main
+}
+
+const APPROXIMATION_NOTE: &str =
+    "Approximate structure from the symbol table. Constraints between signals aren't shown.";
+
+fn write_tree_children(component: &Component, prefix: &str, tree: &mut String) {
+    for (ind, child) in component.children.iter().enumerate() {
+        let last = ind + 1 == component.children.len();
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+
+        let _ = writeln!(
+            tree,
+            "{prefix}{branch}{} ({})",
+            child.name,
+            child.signals.join(", "),
+        );
+        write_tree_children(child, &format!("{prefix}{indent}"), tree);
+    }
+}
+
+fn write_dot_children(component: &Component, id: &str, dot: &mut String) {
+    for child in &component.children {
+        let child_id = format!("{id}.{}", child.name);
+        let _ = writeln!(
+            dot,
+            "  \"{child_id}\" [label=\"{}\\n{}\"];",
+            child.name,
+            child.signals.join(", "),
+        );
+        let _ = writeln!(dot, "  \"{id}\" -> \"{child_id}\";");
+        write_dot_children(child, &child_id, dot);
+    }
+}
+
+/// Render the component hierarchy in the given format.
+pub fn render(main: &Component, format: StructureFormat) -> String {
+    let mut rendered = String::with_capacity(1024);
+
+    // Writing to a `String` can't fail.
+    match format {
+        StructureFormat::Tree => {
+            let _ = writeln!(rendered, "{APPROXIMATION_NOTE}\n");
+            let _ = writeln!(rendered, "{} ({})", main.name, main.signals.join(", "));
+            write_tree_children(main, "", &mut rendered);
+        }
+        StructureFormat::Dot => {
+            let _ = writeln!(rendered, "// {APPROXIMATION_NOTE}");
+            rendered.push_str("digraph circuit {\n");
+            let _ = writeln!(
+                rendered,
+                "  \"{0}\" [label=\"{0}\\n{1}\"];",
+                main.name,
+                main.signals.join(", "),
+            );
+            write_dot_children(main, &main.name, &mut rendered);
+            rendered.push_str("}\n");
+        }
+    }
+
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A template `Square` with `signal output sq[2];` whose main component instantiates two
+    // `Multiplier()` components `mul[0]` and `mul[1]`.
+    const SYM: &str = "1,1,0,main.sq[0]
+2,2,0,main.sq[1]
+3,3,0,main.x
+4,4,1,main.mul[0].a
+5,5,1,main.mul[0].b
+6,6,1,main.mul[0].c
+7,-1,2,main.mul[1].a
+8,7,2,main.mul[1].c";
+
+    #[test]
+    fn two_component_tree() {
+        let main = parse_components(SYM);
+        assert_eq!(main.signals, ["sq", "x"]);
+        assert_eq!(main.children.len(), 2);
+        assert_eq!(main.children[0].name, "mul[0]");
+        assert_eq!(main.children[0].signals, ["a", "b", "c"]);
+        assert_eq!(main.children[1].name, "mul[1]");
+        assert_eq!(main.children[1].signals, ["a", "c"]);
+
+        assert_eq!(
+            render(&main, StructureFormat::Tree),
+            format!(
+                "{APPROXIMATION_NOTE}
+
+main (sq, x)
+├── mul[0] (a, b, c)
+└── mul[1] (a, c)
+",
+            ),
+        );
+
+        let dot = render(&main, StructureFormat::Dot);
+        assert!(dot.starts_with("// Approximate"));
+        assert!(dot.contains("  \"main\" -> \"main.mul[0]\";\n"));
+        assert!(dot.contains("  \"main.mul[1]\" [label=\"mul[1]\\na, c\"];\n"));
+    }
+}
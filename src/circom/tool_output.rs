@@ -0,0 +1,133 @@
+// Parsers for the output of `circom` and `snarkjs`.
+// Their wording changes between versions. Every parser returns `None` for unknown output to let
+// the caller fall back to the exit status of the command.
+
+use std::io::{self, Write};
+
+use crate::{color::Stylize, verify::strip_ansi};
+
+/// The result of `snarkjs groth16 verify` according to its output.
+pub fn parse_verify_output(output: &str) -> Option<bool> {
+    for line in strip_ansi(output).lines() {
+        let line = line.trim();
+        // snarkjs >= 0.3 logs with a level and a prefix like `[INFO]  snarkJS: OK!`.
+        let message = line
+            .split_once("snarkJS:")
+            .map_or(line, |(_, message)| message.trim());
+
+        match message {
+            // snarkjs >= 0.3
+            "OK!" => return Some(true),
+            "Invalid proof" => return Some(false),
+            // snarkjs < 0.3 printed the result without a prefix.
+            "OK" => return Some(true),
+            "INVALID" => return Some(false),
+            _ => (),
+        }
+    }
+
+    None
+}
+
+/// The total number of constraints in the output of `circom --r1cs`.
+pub fn parse_constraints(output: &str) -> Option<u64> {
+    let mut non_linear = None;
+    let mut linear = None;
+
+    for line in strip_ansi(output).lines() {
+        let line = line.trim();
+        let parse = |prefix: &str| line.strip_prefix(prefix)?.trim().parse::<u64>().ok();
+
+        if let Some(n) = parse("non-linear constraints:") {
+            // circom 2
+            non_linear = Some(n);
+        } else if let Some(n) = parse("linear constraints:") {
+            linear = Some(n);
+        } else if let Some(n) = parse("Constraints:") {
+            // circom 0.5
+            return Some(n);
+        }
+    }
+
+    Some(non_linear? + linear.unwrap_or(0))
+}
+
+/// The result of a command according to its output: the exit status `success` and the result
+/// parsed from its output. If the output can't be parsed, a warning is appended to `output` and
+/// only the exit status is used.
+pub fn combine_with_exit_status(
+    success: bool,
+    parsed: Option<bool>,
+    cmd_description: &str,
+    output: &mut Vec<u8>,
+) -> io::Result<bool> {
+    match parsed {
+        Some(parsed) => Ok(success && parsed),
+        None => {
+            writeln!(
+                output,
+                "{}",
+                format!(
+                    "Couldn't interpret the output of `{cmd_description}`. Maybe the installed version isn't supported. Only checking its exit status"
+                )
+                .yellow(),
+            )?;
+
+            Ok(success)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snarkjs_versions() {
+        // snarkjs 0.7
+        assert_eq!(
+            parse_verify_output("\x1b[32m[INFO]  \x1b[39msnarkJS: OK!\n"),
+            Some(true),
+        );
+        assert_eq!(
+            parse_verify_output("[ERROR] snarkJS: Invalid proof\n"),
+            Some(false),
+        );
+
+        // snarkjs 0.1
+        assert_eq!(parse_verify_output("OK\n"), Some(true));
+        assert_eq!(parse_verify_output("INVALID\n"), Some(false));
+
+        assert_eq!(parse_verify_output("Proof verified successfully\n"), None);
+        assert_eq!(parse_verify_output(""), None);
+    }
+
+    #[test]
+    fn circom_versions() {
+        // circom 2.1
+        let output = "template instances: 1
+non-linear constraints: 2
+linear constraints: 1
+public inputs: 0
+Everything went okay";
+        assert_eq!(parse_constraints(output), Some(3));
+
+        // circom 0.5
+        assert_eq!(parse_constraints("Constraints: 4\n"), Some(4));
+
+        assert_eq!(parse_constraints("Everything went okay"), None);
+    }
+
+    #[test]
+    fn unknown_output() {
+        let mut output = Vec::new();
+        assert!(combine_with_exit_status(true, Some(true), "snarkjs …", &mut output).unwrap());
+        assert!(!combine_with_exit_status(true, Some(false), "snarkjs …", &mut output).unwrap());
+        assert!(!combine_with_exit_status(false, Some(true), "snarkjs …", &mut output).unwrap());
+        assert!(output.is_empty());
+
+        assert!(combine_with_exit_status(true, None, "snarkjs …", &mut output).unwrap());
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Couldn't interpret the output of `snarkjs …`"));
+    }
+}
@@ -0,0 +1,248 @@
+// Check the fields of a verification key exported by `snarkjs zkey export verificationkey`.
+// A key which doesn't match the circuit's interface or the provided proof would make
+// `snarkjs groth16 verify` fail without explaining why.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::{fs, io::Write, path::Path};
+
+use crate::color::Stylize;
+
+/// The fields that the verification key of a circuit is expected to have.
+pub struct ExpectedVkey<'a> {
+    pub protocol: &'a str,
+    /// The name of the curve in `snarkjs` which is the same as the prime in `circom`.
+    pub curve: &'a str,
+    /// The number of public signals (outputs and public inputs) if known from the compiled circuit.
+    pub n_public: Option<u32>,
+}
+
+fn string_field<'a>(vkey: &'a Value, field: &str) -> Option<&'a str> {
+    vkey.get(field).and_then(Value::as_str)
+}
+
+// The explanations of the fields which don't match.
+fn vkey_mismatches(vkey: &Value, expected: &ExpectedVkey) -> Vec<String> {
+    let mut mismatches = Vec::new();
+
+    for (field, expected_value) in [("protocol", expected.protocol), ("curve", expected.curve)] {
+        match string_field(vkey, field) {
+            Some(value) if value.eq_ignore_ascii_case(expected_value) => (),
+            Some(value) => mismatches.push(format!(
+                "`{field}` is \"{value}\" but \"{expected_value}\" was expected",
+            )),
+            None => mismatches.push(format!("The field `{field}` is missing")),
+        }
+    }
+
+    let n_public = vkey.get("nPublic").and_then(Value::as_u64);
+    if let (Some(n_public), Some(expected_n_public)) = (n_public, expected.n_public) {
+        if n_public != u64::from(expected_n_public) {
+            mismatches.push(format!(
+                "`nPublic` is {n_public} but the circuit declares {expected_n_public} public signals",
+            ));
+        }
+    }
+
+    // One IC point for the constant 1 and one for every public signal.
+    let n_ic = vkey.get("IC").and_then(Value::as_array).map(Vec::len);
+    let expected_n_ic = expected
+        .n_public
+        .map(u64::from)
+        .or(n_public)
+        .map(|n_public| n_public + 1);
+    match (n_ic, expected_n_ic) {
+        (Some(n_ic), Some(expected_n_ic)) if n_ic as u64 != expected_n_ic => {
+            mismatches.push(format!(
+                "`IC` has {n_ic} points but {expected_n_ic} are expected for {} public signals",
+                expected_n_ic - 1,
+            ));
+        }
+        (None, _) if expected.protocol == "groth16" => {
+            mismatches.push(String::from("The field `IC` is missing"));
+        }
+        _ => (),
+    }
+
+    mismatches
+}
+
+// The explanations of the differences between the verification key of the user's circuit and
+// the provided proof with its public signals.
+fn proof_mismatches(vkey: &Value, proof: &Value, public: &Value) -> Vec<String> {
+    let mut mismatches = Vec::new();
+
+    for field in ["protocol", "curve"] {
+        if let (Some(vkey_value), Some(proof_value)) =
+            (string_field(vkey, field), string_field(proof, field))
+        {
+            if !vkey_value.eq_ignore_ascii_case(proof_value) {
+                mismatches.push(format!(
+                    "The `{field}` of the proof is \"{proof_value}\" but the one of your circuit's key is \"{vkey_value}\"",
+                ));
+            }
+        }
+    }
+
+    match (
+        public.as_array().map(Vec::len),
+        vkey.get("nPublic").and_then(Value::as_u64),
+    ) {
+        (Some(n_signals), Some(n_public)) if n_signals as u64 != n_public => {
+            mismatches.push(format!(
+                "The proof has {n_signals} public signals but your circuit has {n_public}",
+            ));
+        }
+        (None, _) => mismatches.push(String::from("The public signals aren't an array")),
+        _ => (),
+    }
+
+    mismatches
+}
+
+fn read_json(path: &Path) -> Result<Result<Value, String>> {
+    let json =
+        fs::read(path).with_context(|| format!("Failed to read the file {}", path.display()))?;
+
+    Ok(serde_json::from_slice(&json)
+        .map_err(|e| format!("The file {} isn't valid JSON: {e}", path.display())))
+}
+
+/// Check that the provided proof and public signals fit the verification key at `vkey_path` and
+/// write the differences to `output`. A proof for another proving system, curve or number of
+/// public signals can't verify.
+pub fn check_proof_file(
+    vkey_path: &Path,
+    proof_path: &Path,
+    public_path: &Path,
+    output: &mut Vec<u8>,
+) -> Result<bool> {
+    let mismatches = match (
+        read_json(vkey_path)?,
+        read_json(proof_path)?,
+        read_json(public_path)?,
+    ) {
+        (Ok(vkey), Ok(proof), Ok(public)) => proof_mismatches(&vkey, &proof, &public),
+        (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => vec![e],
+    };
+
+    if mismatches.is_empty() {
+        return Ok(true);
+    }
+
+    writeln!(
+        output,
+        "{}",
+        "The verification key of your circuit doesn't match the provided proof:"
+            .bold()
+            .red(),
+    )?;
+    for mismatch in mismatches {
+        writeln!(output, "  {}", mismatch.red())?;
+    }
+
+    Ok(false)
+}
+
+/// Check the verification key at `path` and write the mismatching fields to `output`.
+pub fn check_vkey_file(path: &Path, expected: &ExpectedVkey, output: &mut Vec<u8>) -> Result<bool> {
+    let vkey = fs::read(path)
+        .with_context(|| format!("Failed to read the verification key {}", path.display()))?;
+    let mismatches = match serde_json::from_slice::<Value>(&vkey) {
+        Ok(vkey) => vkey_mismatches(&vkey, expected),
+        Err(e) => vec![format!("It isn't valid JSON: {e}")],
+    };
+
+    if mismatches.is_empty() {
+        return Ok(true);
+    }
+
+    writeln!(
+        output,
+        "{}",
+        format!(
+            "The verification key {} doesn't match the circuit:",
+            path.display(),
+        )
+        .bold()
+        .red(),
+    )?;
+    for mismatch in mismatches {
+        writeln!(output, "  {}", mismatch.red())?;
+    }
+
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPECTED: ExpectedVkey = ExpectedVkey {
+        protocol: "groth16",
+        curve: "bn128",
+        n_public: Some(2),
+    };
+
+    #[test]
+    fn ic_count_mismatch() {
+        let vkey = serde_json::json!({
+            "protocol": "groth16",
+            "curve": "bn128",
+            "nPublic": 1,
+            "IC": [["1", "2", "1"], ["3", "4", "1"]],
+        });
+
+        assert_eq!(
+            vkey_mismatches(&vkey, &EXPECTED),
+            [
+                "`nPublic` is 1 but the circuit declares 2 public signals",
+                "`IC` has 2 points but 3 are expected for 2 public signals",
+            ],
+        );
+
+        let mut matching = vkey.clone();
+        matching["nPublic"] = 2.into();
+        matching["IC"]
+            .as_array_mut()
+            .unwrap()
+            .push(vkey["IC"][0].clone());
+        assert!(vkey_mismatches(&matching, &EXPECTED).is_empty());
+
+        matching["curve"] = "bls12381".into();
+        assert_eq!(
+            vkey_mismatches(&matching, &EXPECTED),
+            ["`curve` is \"bls12381\" but \"bn128\" was expected"],
+        );
+    }
+
+    #[test]
+    fn vkey_and_external_proof() {
+        // The fixture's proof is made up. Only its shape is compared with the key.
+        let fixture = Path::new("tests/fixture/external_proof");
+        let proof = fixture.join("exercises/proof.json");
+        let public = fixture.join("exercises/public.json");
+        let vkey = fixture.join("vkey.json");
+
+        let mut output = Vec::new();
+        assert!(check_proof_file(&vkey, &proof, &public, &mut output).unwrap());
+        assert!(output.is_empty());
+
+        let vkey = serde_json::from_slice::<Value>(&fs::read(vkey).unwrap()).unwrap();
+        let proof = serde_json::from_slice::<Value>(&fs::read(proof).unwrap()).unwrap();
+        let mut mismatching = vkey.clone();
+        mismatching["nPublic"] = 2.into();
+        mismatching["curve"] = "bls12381".into();
+        assert_eq!(
+            proof_mismatches(&mismatching, &proof, &serde_json::json!(["33"])),
+            [
+                "The `curve` of the proof is \"bn128\" but the one of your circuit's key is \"bls12381\"",
+                "The proof has 1 public signals but your circuit has 2",
+            ],
+        );
+        assert_eq!(
+            proof_mismatches(&vkey, &proof, &serde_json::json!({})),
+            ["The public signals aren't an array"],
+        );
+    }
+}
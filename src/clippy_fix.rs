@@ -0,0 +1,211 @@
+use anyhow::{Context, Result};
+use std::{
+    io::{self, BufRead, Write},
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use crate::{
+    cmd::CargoCmd,
+    color::Stylize,
+    exercise::{Exercise, OUTPUT_CAPACITY},
+    in_official_repo, DEBUG_PROFILE,
+};
+
+/// The state of the exercise file in Git.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum FileState {
+    /// Tracked without uncommitted changes.
+    Clean,
+    /// Tracked with uncommitted changes.
+    Modified,
+    /// Not in a Git repository, untracked or ignored.
+    NotVersioned,
+}
+
+impl FileState {
+    fn of(path: &str) -> Self {
+        let output = Command::new("git")
+            .args(["status", "--porcelain", "--ignored", "--", path])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output();
+
+        let Ok(output) = output else {
+            // Git isn't installed.
+            return Self::NotVersioned;
+        };
+
+        if !output.status.success() {
+            // Not a Git repository.
+            return Self::NotVersioned;
+        }
+
+        Self::parse_status(&output.stdout)
+    }
+
+    // Parse the output of `git status --porcelain --ignored -- PATH`.
+    fn parse_status(status: &[u8]) -> Self {
+        match status {
+            [] => Self::Clean,
+            [b'?', b'?', ..] | [b'!', b'!', ..] => Self::NotVersioned,
+            _ => Self::Modified,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug)]
+enum FixDecision {
+    Apply,
+    Declined,
+    /// The fixes could overwrite work which can't be restored with Git.
+    Unsafe(FileState),
+}
+
+// Decide if the fixes are applied. The learner is only asked if the exercise file can be restored
+// with Git after applying the fixes. Only an explicit "y" or "yes" is consent.
+fn decide(
+    file_state: FileState,
+    ask: impl FnOnce() -> io::Result<String>,
+) -> io::Result<FixDecision> {
+    if file_state != FileState::Clean {
+        return Ok(FixDecision::Unsafe(file_state));
+    }
+
+    let answer = ask()?;
+    let decision = match answer.trim().to_lowercase().as_str() {
+        "y" | "yes" => FixDecision::Apply,
+        _ => FixDecision::Declined,
+    };
+
+    Ok(decision)
+}
+
+fn ask_consent() -> io::Result<String> {
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(b"Apply the fixes to the exercise file? [y/N] ")?;
+    stdout.flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+
+    Ok(answer)
+}
+
+/// Offer to apply the machine-applicable suggestions of Clippy to an exercise after Clippy
+/// failed. The fixes are only applied with the learner's consent and only if the exercise file
+/// is committed in Git to be able to restore it.
+pub fn offer_clippy_fix(exercise: &Exercise, target_dir: &Path) -> Result<()> {
+    println!(
+        "\n`cargo clippy --fix` can apply the suggestions of Clippy which are marked as machine-applicable.
+It rewrites {} in place. Compare the result with the suggestions above to learn what changed.",
+        exercise.path,
+    );
+
+    match decide(FileState::of(exercise.path), ask_consent)? {
+        FixDecision::Apply => (),
+        FixDecision::Declined => return Ok(()),
+        FixDecision::Unsafe(FileState::Modified) => {
+            println!(
+                "{}",
+                "The exercise file has uncommitted changes. Commit them first to be able to restore them after applying the fixes"
+                    .yellow(),
+            );
+            return Ok(());
+        }
+        FixDecision::Unsafe(_) => {
+            println!(
+                "{}",
+                "The exercise file isn't committed in a Git repository. Commit it first to be able to restore it after applying the fixes"
+                    .yellow(),
+            );
+            return Ok(());
+        }
+    }
+
+    let mut output = Vec::with_capacity(OUTPUT_CAPACITY);
+    // Other files than the exercise file may be dirty.
+    let success = CargoCmd {
+        subcommand: "clippy",
+        args: &[
+            "--profile",
+            "test",
+            "--fix",
+            "--allow-dirty",
+            "--allow-staged",
+        ],
+        bin_name: exercise.name,
+        description: "cargo clippy --fix …",
+        hide_warnings: false,
+        target_dir,
+        output: &mut output,
+        dev: DEBUG_PROFILE && in_official_repo(),
+    }
+    .run()
+    .context("Failed to apply the fixes of Clippy")?;
+
+    io::stdout().lock().write_all(&output)?;
+    if success {
+        println!(
+            "{}",
+            format!(
+                "Applied the fixes. Run `git diff {}` to see them or `git checkout {0}` to undo them",
+                exercise.path,
+            )
+            .green(),
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consent_flow() {
+        assert_eq!(FileState::parse_status(b""), FileState::Clean);
+        assert_eq!(
+            FileState::parse_status(b" M exercises/intro1.rs\n"),
+            FileState::Modified,
+        );
+        assert_eq!(
+            FileState::parse_status(b"?? exercises/intro1.rs\n"),
+            FileState::NotVersioned,
+        );
+        assert_eq!(
+            FileState::parse_status(b"!! exercises/intro1.rs\n"),
+            FileState::NotVersioned,
+        );
+
+        let answer = |answer: &'static str| move || Ok(String::from(answer));
+        assert_eq!(
+            decide(FileState::Clean, answer("y\n")).unwrap(),
+            FixDecision::Apply,
+        );
+        assert_eq!(
+            decide(FileState::Clean, answer(" Yes\n")).unwrap(),
+            FixDecision::Apply,
+        );
+        assert_eq!(
+            decide(FileState::Clean, answer("\n")).unwrap(),
+            FixDecision::Declined,
+        );
+        assert_eq!(
+            decide(FileState::Clean, answer("yep\n")).unwrap(),
+            FixDecision::Declined,
+        );
+
+        // The learner isn't asked if the work could be lost.
+        let never_asked = || -> io::Result<String> { panic!("Asked for consent") };
+        assert_eq!(
+            decide(FileState::Modified, never_asked).unwrap(),
+            FixDecision::Unsafe(FileState::Modified),
+        );
+        assert_eq!(
+            decide(FileState::NotVersioned, never_asked).unwrap(),
+            FixDecision::Unsafe(FileState::NotVersioned),
+        );
+    }
+}
@@ -1,9 +1,358 @@
-use anyhow::{Context, Result};
-use std::{io::Read, path::Path, process::Command};
+#[cfg(feature = "noir")]
+use anyhow::anyhow;
+use anyhow::{bail, Context, Result};
+use std::{
+    ffi::OsStr,
+    fmt::{self, Display, Formatter},
+    io::{self, Read, Write},
+    path::Path,
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+/// OS-level resource limits for a spawned command.
+/// They are enforced with `setrlimit` on Unix and ignored on other platforms.
+/// Linux enforces both limits. macOS ignores the memory limit.
+#[derive(Clone, Copy, Default)]
+pub struct ResourceLimits {
+    /// Maximum size of the address space in bytes.
+    pub max_memory: Option<u64>,
+    /// Maximum CPU time in seconds.
+    pub max_cpu_time: Option<u64>,
+}
+
+impl ResourceLimits {
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.max_memory.is_none() && self.max_cpu_time.is_none()
+    }
+
+    /// Apply the limits to the command before it is spawned.
+    /// Returns `false` if there are limits which can't be enforced on this platform.
+    #[cfg(unix)]
+    pub fn apply(self, cmd: &mut Command) -> bool {
+        use std::{io, os::unix::process::CommandExt};
+
+        if self.is_empty() {
+            return true;
+        }
+
+        let rlimit = |soft: u64, hard: u64| libc::rlimit {
+            rlim_cur: soft as libc::rlim_t,
+            rlim_max: hard as libc::rlim_t,
+        };
+
+        // SAFETY: Only `setrlimit` is called in the child between `fork` and `exec`.
+        // It is async-signal-safe and doesn't allocate.
+        unsafe {
+            cmd.pre_exec(move || {
+                if let Some(max_memory) = self.max_memory {
+                    if libc::setrlimit(libc::RLIMIT_AS, &rlimit(max_memory, max_memory)) != 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                }
+
+                if let Some(max_cpu_time) = self.max_cpu_time {
+                    // The soft limit sends `SIGXCPU`, the hard limit one second later `SIGKILL`.
+                    let limit = rlimit(max_cpu_time, max_cpu_time.saturating_add(1));
+                    if libc::setrlimit(libc::RLIMIT_CPU, &limit) != 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                }
+
+                Ok(())
+            });
+        }
+
+        true
+    }
+
+    #[cfg(not(unix))]
+    pub fn apply(self, _cmd: &mut Command) -> bool {
+        self.is_empty()
+    }
+
+    /// Guess which limit terminated a command based on its exit status and its `output`.
+    #[cfg(unix)]
+    pub fn exceeded_limit(self, status: ExitStatus, output: &[u8]) -> Option<String> {
+        use std::os::unix::process::ExitStatusExt;
+
+        let signal = status.signal()?;
+
+        if let Some(max_cpu_time) = self.max_cpu_time {
+            // The `SIGKILL` of the hard limit can't be told apart from other kills like the one of
+            // the OOM killer. It is only reported as a kill by `termination_signal`.
+            if signal == libc::SIGXCPU {
+                return Some(format!("CPU time limit of {max_cpu_time}s"));
+            }
+        }
+
+        if let Some(max_memory) = self.max_memory {
+            // A failed allocation of a Rust binary prints this message before aborting.
+            // Other aborts and segfaults are only reported as such by `termination_signal`.
+            if signal == libc::SIGABRT && contains(output, ALLOCATION_FAILURE) {
+                return Some(format!("memory limit of {max_memory} bytes"));
+            }
+        }
+
+        None
+    }
+
+    #[cfg(not(unix))]
+    pub fn exceeded_limit(self, _status: ExitStatus, _output: &[u8]) -> Option<String> {
+        None
+    }
+}
+
+#[cfg(unix)]
+const ALLOCATION_FAILURE: &[u8] = b"memory allocation of ";
+
+#[cfg(unix)]
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+/// The signal which terminated a command, e.g. `SIGKILL` of the OOM killer.
+/// `None` if the command exited by itself.
+#[cfg(unix)]
+pub fn termination_signal(status: ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+
+    status.signal()
+}
+
+#[cfg(not(unix))]
+pub fn termination_signal(_status: ExitStatus) -> Option<i32> {
+    None
+}
+
+/// The default maximum number of bytes of the output of a command which are kept.
+/// The rest is read and discarded to let the command finish.
+pub const MAX_CMD_OUTPUT_LEN: usize = 1 << 20;
+
+static MAX_OUTPUT_LEN: AtomicUsize = AtomicUsize::new(MAX_CMD_OUTPUT_LEN);
+
+/// Keep at most `max_len` bytes of the output of each command instead of `MAX_CMD_OUTPUT_LEN`.
+/// Set by the global argument `--max-output`.
+pub fn set_max_output_len(max_len: usize) {
+    MAX_OUTPUT_LEN.store(max_len, Ordering::Relaxed);
+}
+
+// The size of the chunks in which the output is read.
+const CHUNK_LEN: usize = 1 << 13;
+
+static STREAM_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Forward the output of commands to stderr while they run, for example to follow a slow build
+/// or proof. The output is still captured and shown afterwards. Set by the global argument
+/// `--stream-output`.
+pub fn stream_output() {
+    STREAM_OUTPUT.store(true, Ordering::Relaxed);
+}
+
+// The writer of the live output if streaming is enabled.
+fn live_output() -> Option<io::Stderr> {
+    STREAM_OUTPUT.load(Ordering::Relaxed).then(io::stderr)
+}
+
+// Forward what was appended to the captured output. The command's output is still captured if
+// writing to the terminal fails. Therefore, errors are ignored.
+fn forward(live: &mut Option<impl Write>, bytes: &[u8]) {
+    if let Some(live) = live {
+        let _ = live.write_all(bytes).and_then(|()| live.flush());
+    }
+}
+
+// Read everything in chunks and append at most `max_len` bytes to the output.
+// The kept bytes are also forwarded to `live`. Discarded bytes are neither captured nor shown.
+// The writer is blocked by the pipe until a chunk was read. Therefore, the memory stays bounded
+// regardless of how much the command outputs.
+// Returns the number of discarded bytes.
+fn read_capped(
+    reader: &mut impl Read,
+    output: &mut Vec<u8>,
+    max_len: usize,
+    live: &mut Option<impl Write>,
+) -> io::Result<u64> {
+    let mut chunk = [0; CHUNK_LEN];
+    let mut kept = 0;
+    let mut discarded = 0;
+
+    loop {
+        let n = match reader.read(&mut chunk) {
+            Ok(0) => return Ok(discarded),
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        let n_kept = n.min(max_len - kept);
+        output.extend_from_slice(&chunk[..n_kept]);
+        forward(live, &chunk[..n_kept]);
+        kept += n_kept;
+        discarded += (n - n_kept) as u64;
+    }
+}
+
+/// The version `MAJOR.MINOR.PATCH` of an external tool.
+#[cfg_attr(not(feature = "circom"), allow(dead_code))]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct ToolVersion(pub u32, pub u32, pub u32);
+
+#[cfg_attr(not(feature = "circom"), allow(dead_code))]
+impl ToolVersion {
+    /// The first version in the output of `TOOL --version` like `circom compiler 2.1.9`.
+    /// Suffixes like `-beta.3` are ignored.
+    pub fn parse(version_output: &str) -> Option<Self> {
+        version_output.split_whitespace().find_map(|word| {
+            let word = word.strip_prefix('v').unwrap_or(word);
+            let mut parts = word.splitn(3, '.');
+            let major = parts.next()?.parse().ok()?;
+            let minor = parts.next()?.parse().ok()?;
+            let patch = parts.next()?;
+            let patch_len = patch
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(patch.len());
+            let patch = patch[..patch_len].parse().ok()?;
+
+            Some(Self(major, minor, patch))
+        })
+    }
+}
+
+impl Display for ToolVersion {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+    }
+}
+
+/// Check that `TOOL --version` reports at least the version `min`.
+/// The error tells how to install the tool with `install_hint`.
+#[cfg_attr(not(feature = "circom"), allow(dead_code))]
+pub fn check_tool_version(tool: &str, min: ToolVersion, install_hint: &str) -> Result<()> {
+    let version_output = match Command::new(tool)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .output()
+    {
+        Ok(version_output) => version_output,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            bail!("{tool} {min} or newer is required but it isn't installed. {install_hint}");
+        }
+        Err(e) => return Err(e).with_context(|| format!("Failed to run `{tool} --version`")),
+    };
+
+    let stdout = String::from_utf8_lossy(&version_output.stdout);
+    let Some(version) = ToolVersion::parse(&stdout) else {
+        bail!(
+            "{tool} {min} or newer is required but its version couldn't be determined from `{}`. {install_hint}",
+            stdout.trim(),
+        );
+    };
+    if version < min {
+        bail!("{tool} {min} or newer is required, found {version}. {install_hint}");
+    }
+
+    Ok(())
+}
+
+/// The default timeout of running an exercise's binary.
+pub const BIN_TIMEOUT: Duration = Duration::from_secs(60);
+/// The default timeout of compiling a circuit. Proving has no timeout because it can
+/// legitimately take long for large circuits.
+#[cfg(feature = "circom")]
+pub const CIRCOM_TIMEOUT: Duration = Duration::from_secs(300);
 
 /// Run a command with a description for a possible error and append the merged stdout and stderr.
+/// Output beyond `MAX_CMD_OUTPUT_LEN` bytes (see `set_max_output_len`) is truncated.
 /// The boolean in the returned `Result` is true if the command's exit status is success.
-pub fn run_cmd(mut cmd: Command, description: &str, output: &mut Vec<u8>) -> Result<bool> {
+pub fn run_cmd(cmd: Command, description: &str, output: &mut Vec<u8>) -> Result<bool> {
+    run_cmd_status(cmd, description, output).map(|status| status.success())
+}
+
+/// Same as `run_cmd` but kill the command if it didn't exit after `timeout`.
+/// The output until then is kept and followed by a message about the timeout.
+/// The boolean in the returned `Result` is false if the command timed out.
+pub fn run_cmd_with_timeout(
+    cmd: Command,
+    description: &str,
+    output: &mut Vec<u8>,
+    timeout: Duration,
+) -> Result<bool> {
+    run_cmd_status_with_timeout(cmd, description, output, timeout)
+        .map(|status| status.is_some_and(|status| status.success()))
+}
+
+/// Same as `run_cmd` but return the exit status of the command.
+pub fn run_cmd_status(cmd: Command, description: &str, output: &mut Vec<u8>) -> Result<ExitStatus> {
+    let status = run_cmd_inner(cmd, description, output, None)?;
+    Ok(status.expect("A command without a timeout can't time out"))
+}
+
+/// Same as `run_cmd_with_timeout` but return the exit status of the command.
+/// `None` if the command timed out.
+pub fn run_cmd_status_with_timeout(
+    cmd: Command,
+    description: &str,
+    output: &mut Vec<u8>,
+    timeout: Duration,
+) -> Result<Option<ExitStatus>> {
+    run_cmd_inner(cmd, description, output, Some(timeout))
+}
+
+// Kill the child and the processes it spawned, like the test binary of `cargo test` or the
+// workers of `snarkjs`. They also hold the write end of the pipe. Therefore, only killing the
+// child wouldn't end reading. The child is the leader of its own process group, see
+// `run_cmd_inner`. Returns `true` if a process was killed.
+#[cfg(unix)]
+fn kill_process_group(child: &mut Child) -> bool {
+    let Ok(pgid) = libc::pid_t::try_from(child.id()) else {
+        return false;
+    };
+
+    // SAFETY: `killpg` only sends a signal. The group can't be reused by other processes as long
+    // as the child isn't waited on or one of its processes is alive.
+    unsafe { libc::killpg(pgid, libc::SIGKILL) == 0 }
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(child: &mut Child) -> bool {
+    child.kill().is_ok()
+}
+
+// Kill the command if the reading of its output didn't finish before the timeout.
+// Returns `true` if a process of the command was killed.
+fn watch_timeout(child: &Mutex<Child>, done: &mpsc::Receiver<()>, timeout: Duration) -> bool {
+    match done.recv_timeout(timeout) {
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            let mut child = child.lock().unwrap();
+            // A child which exited but wasn't waited on can still be signaled. Waiting on it
+            // first only leaves the processes which it spawned to be killed.
+            let exited = matches!(child.try_wait(), Ok(Some(_)));
+            if exited && cfg!(not(unix)) {
+                return false;
+            }
+
+            kill_process_group(&mut child)
+        }
+        // The sender is dropped after reading.
+        Ok(()) | Err(mpsc::RecvTimeoutError::Disconnected) => false,
+    }
+}
+
+fn run_cmd_inner(
+    mut cmd: Command,
+    description: &str,
+    output: &mut Vec<u8>,
+    timeout: Option<Duration>,
+) -> Result<Option<ExitStatus>> {
     let (mut reader, writer) = os_pipe::pipe()
         .with_context(|| format!("Failed to create a pipe to run the command `{description}``"))?;
 
@@ -11,7 +360,17 @@ pub fn run_cmd(mut cmd: Command, description: &str, output: &mut Vec<u8>) -> Res
         format!("Failed to clone the pipe writer for the command `{description}`")
     })?;
 
-    let mut handle = cmd
+    // Processes spawned by the command are killed together with it on timeout.
+    #[cfg(unix)]
+    if timeout.is_some() {
+        use std::os::unix::process::CommandExt;
+
+        cmd.process_group(0);
+    }
+
+    // Commands never wait for input, also not when running non-interactively.
+    let handle = cmd
+        .stdin(Stdio::null())
         .stdout(writer_clone)
         .stderr(writer)
         .spawn()
@@ -20,16 +379,95 @@ pub fn run_cmd(mut cmd: Command, description: &str, output: &mut Vec<u8>) -> Res
     // Prevent pipe deadlock.
     drop(cmd);
 
-    reader
-        .read_to_end(output)
+    // The child isn't waited on before the watcher is done. Therefore, it can't be replaced by
+    // another process before the watcher kills it.
+    let handle = Mutex::new(handle);
+    let max_len = MAX_OUTPUT_LEN.load(Ordering::Relaxed);
+    let mut live = live_output();
+    let (done_sender, done_receiver) = mpsc::channel();
+    let (read_res, timed_out) = thread::scope(|s| {
+        let watcher = timeout.map(|timeout| {
+            let handle = &handle;
+            s.spawn(move || watch_timeout(handle, &done_receiver, timeout))
+        });
+
+        // Killing the command's process group closes the pipe which ends reading.
+        let read_res = read_capped(&mut reader, output, max_len, &mut live);
+        drop(done_sender);
+
+        let timed_out = watcher.is_some_and(|watcher| watcher.join().unwrap());
+        (read_res, timed_out)
+    });
+
+    let discarded = read_res
         .with_context(|| format!("Failed to read the output of the command `{description}`"))?;
+    // Everything appended after the command's output is also forwarded to keep the shown output
+    // identical to the captured one.
+    let streamed_len = output.len();
+    if discarded > 0 {
+        output.extend_from_slice(
+            format!(
+                "\n… output truncated after {max_len} bytes, {discarded} more bytes were discarded"
+            )
+            .as_bytes(),
+        );
+    }
 
     output.push(b'\n');
 
-    handle
+    let status = handle
+        .into_inner()
+        .unwrap()
         .wait()
-        .with_context(|| format!("Failed to wait on the command `{description}` to exit"))
-        .map(|status| status.success())
+        .with_context(|| format!("Failed to wait on the command `{description}` to exit"))?;
+
+    if let (true, Some(timeout)) = (timed_out, timeout) {
+        output.extend_from_slice(
+            format!(
+                "The command `{description}` timed out after {} seconds and was killed\n",
+                timeout.as_secs_f32(),
+            )
+            .as_bytes(),
+        );
+        forward(&mut live, &output[streamed_len..]);
+        return Ok(None);
+    }
+
+    forward(&mut live, &output[streamed_len..]);
+    Ok(Some(status))
+}
+
+/// A command that would be run for an exercise, see `RunnableExercise::plan`.
+pub struct PlannedCmd {
+    pub description: String,
+    pub cmd: Command,
+}
+
+impl Display for PlannedCmd {
+    // Format the command like it would be entered in a shell: `ENV=VALUE PROGRAM ARGS`.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let quoted = |arg: &OsStr| {
+            let arg = arg.to_string_lossy();
+            if arg.is_empty() || arg.contains(char::is_whitespace) {
+                format!("'{arg}'")
+            } else {
+                arg.into_owned()
+            }
+        };
+
+        for (key, value) in self.cmd.get_envs() {
+            if let Some(value) = value {
+                write!(f, "{}={} ", key.to_string_lossy(), quoted(value))?;
+            }
+        }
+
+        f.write_str(&quoted(self.cmd.get_program()))?;
+        for arg in self.cmd.get_args() {
+            write!(f, " {}", quoted(arg))?;
+        }
+
+        Ok(())
+    }
 }
 
 pub struct CargoCmd<'a> {
@@ -39,7 +477,7 @@ pub struct CargoCmd<'a> {
     pub description: &'a str,
     /// RUSTFLAGS="-A warnings"
     pub hide_warnings: bool,
-    /// Added as `--target-dir` if `Self::dev` is true.
+    /// Passed as `--target-dir`, e.g. the own target directory of a worker of `pool`.
     pub target_dir: &'a Path,
     /// The output buffer to append the merged stdout and stderr.
     pub output: &'a mut Vec<u8>,
@@ -48,17 +486,16 @@ pub struct CargoCmd<'a> {
 }
 
 impl<'a> CargoCmd<'a> {
-    /// Run `cargo SUBCOMMAND --bin EXERCISE_NAME … ARGS`.
-    pub fn run(&mut self) -> Result<bool> {
+    /// Build the command `cargo SUBCOMMAND --bin EXERCISE_NAME … ARGS`.
+    pub fn command(&self) -> Command {
         let mut cmd = Command::new("cargo");
-        cmd.arg(self.subcommand);
+        cmd.arg(self.subcommand)
+            .arg("--target-dir")
+            .arg(self.target_dir);
 
         // A hack to make `cargo run` work when developing Rustlings.
         if self.dev {
-            cmd.arg("--manifest-path")
-                .arg("dev/Cargo.toml")
-                .arg("--target-dir")
-                .arg(self.target_dir);
+            cmd.arg("--manifest-path").arg("dev/Cargo.toml");
         }
 
         cmd.arg("--color")
@@ -72,13 +509,21 @@ impl<'a> CargoCmd<'a> {
             cmd.env("RUSTFLAGS", "-A warnings");
         }
 
-        run_cmd(cmd, self.description, self.output)
+        cmd
+    }
+
+    /// Run `cargo SUBCOMMAND --bin EXERCISE_NAME … ARGS`.
+    pub fn run(&mut self) -> Result<bool> {
+        run_cmd(self.command(), self.description, self.output)
     }
 }
 
+#[cfg(feature = "circom")]
 pub struct CircomCmd<'a> {
     pub args: &'a [&'a str],
     pub circuit_name: &'a str,
+    /// The prime of the curve passed to `--prime`. `circom` chooses `bn128` if `None`.
+    pub prime: Option<&'a str>,
     pub description: &'a str,
     /// The output buffer to append the merged stdout and stderr.
     pub output: &'a mut Vec<u8>,
@@ -86,14 +531,96 @@ pub struct CircomCmd<'a> {
     pub circuit_dir: &'a Path,
 }
 
+#[cfg(feature = "circom")]
 impl<'a> CircomCmd<'a> {
-    pub fn run(&mut self) -> Result<bool> {
+    /// Build the command `circom CIRCUIT_NAME ARGS` in the circuit directory.
+    pub fn command(&self) -> Command {
         let mut cmd = Command::new("circom");
         cmd.current_dir(self.circuit_dir)
             .arg(self.circuit_name)
             .args(self.args);
+        if let Some(prime) = self.prime {
+            cmd.arg("--prime").arg(prime);
+        }
+
+        cmd
+    }
+
+    /// Run `circom CIRCUIT_NAME ARGS` with the timeout `CIRCOM_TIMEOUT`.
+    pub fn run(&mut self) -> Result<bool> {
+        run_cmd_with_timeout(
+            self.command(),
+            self.description,
+            self.output,
+            CIRCOM_TIMEOUT,
+        )
+    }
+}
+
+#[cfg(feature = "circom")]
+pub struct SnarkjsCmd<'a> {
+    pub args: &'a [&'a str],
+    pub description: &'a str,
+    /// The output buffer to append the merged stdout and stderr.
+    pub output: &'a mut Vec<u8>,
+    /// Directory where the circuit file and its build artifacts are located
+    pub circuit_dir: &'a Path,
+}
+
+#[cfg(feature = "circom")]
+impl<'a> SnarkjsCmd<'a> {
+    /// Build the command `snarkjs ARGS` in the circuit directory.
+    pub fn command(&self) -> Command {
+        let mut cmd = Command::new("snarkjs");
+        cmd.current_dir(self.circuit_dir).args(self.args);
 
-        run_cmd(cmd, self.description, self.output)
+        cmd
+    }
+
+    /// Run `snarkjs ARGS` in the circuit directory.
+    pub fn run(&mut self) -> Result<bool> {
+        run_cmd(self.command(), self.description, self.output)
+    }
+}
+
+/// The error for running `nargo` which isn't installed.
+#[cfg(feature = "noir")]
+const NARGO_NOT_FOUND_ERR: &str = "Noir toolchain not found. Install `nargo` with `noirup` (see https://noir-lang.org/docs/getting_started/quick_start) and make sure that it is in your `PATH`";
+
+#[cfg(feature = "noir")]
+pub struct NargoCmd<'a> {
+    pub subcommand: &'a str,
+    pub description: &'a str,
+    /// The output buffer to append the merged stdout and stderr.
+    pub output: &'a mut Vec<u8>,
+    /// Directory of the Noir package
+    pub package_dir: &'a Path,
+}
+
+#[cfg(feature = "noir")]
+impl<'a> NargoCmd<'a> {
+    /// Build the command `nargo SUBCOMMAND --program-dir PACKAGE_DIR`.
+    pub fn command(&self) -> Command {
+        let mut cmd = Command::new("nargo");
+        cmd.arg(self.subcommand)
+            .arg("--program-dir")
+            .arg(self.package_dir);
+
+        cmd
+    }
+
+    /// Run `nargo SUBCOMMAND` in the package directory.
+    pub fn run(&mut self) -> Result<bool> {
+        run_cmd(self.command(), self.description, self.output).map_err(|e| {
+            let not_found = e
+                .downcast_ref::<io::Error>()
+                .is_some_and(|e| e.kind() == io::ErrorKind::NotFound);
+            if not_found {
+                return anyhow!(NARGO_NOT_FOUND_ERR);
+            }
+
+            e
+        })
     }
 }
 
@@ -111,4 +638,183 @@ mod tests {
 
         assert_eq!(output, b"Hello\n\n");
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_cmd_timeout() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo started; exec sleep 10"]);
+
+        let mut output = Vec::new();
+        let status =
+            run_cmd_status_with_timeout(cmd, "sleep", &mut output, Duration::from_millis(200))
+                .unwrap();
+
+        assert!(status.is_none());
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("started\n"));
+        assert!(output.contains("timed out after 0.2 seconds"));
+
+        let mut cmd = Command::new("echo");
+        cmd.arg("Hello");
+        let mut output = Vec::new();
+        assert!(run_cmd_with_timeout(cmd, "echo …", &mut output, Duration::from_secs(10)).unwrap());
+        assert_eq!(output, b"Hello\n\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_cmd_timeout_kills_spawned_processes() {
+        // `sleep` isn't executed in place of the shell and keeps the pipe open.
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "sleep 10; echo done"]);
+
+        let start = std::time::Instant::now();
+        let status =
+            run_cmd_status_with_timeout(cmd, "sleep", &mut Vec::new(), Duration::from_millis(200))
+                .unwrap();
+
+        assert!(status.is_none());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exited_child_isnt_timed_out() {
+        use std::os::unix::process::CommandExt;
+
+        let mut cmd = Command::new("true");
+        cmd.process_group(0);
+        let child = Mutex::new(cmd.spawn().unwrap());
+        // The child exits but isn't waited on.
+        thread::sleep(Duration::from_millis(200));
+
+        let (_done_sender, done_receiver) = mpsc::channel();
+        assert!(!watch_timeout(&child, &done_receiver, Duration::ZERO));
+        assert!(child.into_inner().unwrap().wait().unwrap().success());
+    }
+
+    #[test]
+    fn large_output_is_truncated() {
+        // 64 MiB which are generated while reading.
+        let mut reader = io::repeat(b'w').take(1 << 26);
+        let mut output = Vec::with_capacity(8);
+
+        let discarded =
+            read_capped(&mut reader, &mut output, 1 << 16, &mut None::<Vec<u8>>).unwrap();
+
+        assert_eq!(output.len(), 1 << 16);
+        assert!(output.iter().all(|&c| c == b'w'));
+        assert_eq!(discarded, (1 << 26) - (1 << 16));
+        // Only the kept output was allocated.
+        assert!(output.capacity() <= 1 << 17);
+    }
+
+    #[test]
+    fn streamed_output_is_captured() {
+        let mut reader = io::repeat(b's').take(3 * CHUNK_LEN as u64);
+        let mut output = Vec::new();
+        let mut live = Some(Vec::new());
+
+        let discarded = read_capped(&mut reader, &mut output, CHUNK_LEN + 1, &mut live).unwrap();
+
+        assert_eq!(discarded, 2 * CHUNK_LEN as u64 - 1);
+        assert_eq!(output.len(), CHUNK_LEN + 1);
+        assert_eq!(live.unwrap(), output);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn killed_without_exceeded_limit() {
+        let limits = ResourceLimits {
+            max_memory: None,
+            max_cpu_time: Some(1),
+        };
+        let status = |signal: &str| {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", &format!("kill -{signal} $$")]);
+            run_cmd_status(cmd, "kill", &mut Vec::new()).unwrap()
+        };
+
+        let killed = status("KILL");
+        assert_eq!(limits.exceeded_limit(killed, b""), None);
+        assert_eq!(termination_signal(killed), Some(libc::SIGKILL));
+
+        assert_eq!(
+            limits.exceeded_limit(status("XCPU"), b"").as_deref(),
+            Some("CPU time limit of 1s"),
+        );
+
+        // Segfaults and aborts without a failed allocation aren't blamed on the memory limit.
+        let limits = ResourceLimits {
+            max_memory: Some(1 << 28),
+            max_cpu_time: None,
+        };
+        for signal in ["SEGV", "ABRT"] {
+            let status = status(signal);
+            assert_eq!(limits.exceeded_limit(status, b"panicked"), None);
+            assert!(termination_signal(status).is_some());
+        }
+    }
+
+    // Only allocates when run in a child process by `run_cmd_memory_limit`.
+    #[test]
+    fn allocate_a_lot() {
+        if std::env::var_os("ZKLINGS_ALLOCATE_A_LOT").is_none() {
+            return;
+        }
+
+        let v = std::hint::black_box(vec![1_u8; 1 << 32]);
+        assert!(!v.is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn run_cmd_memory_limit() {
+        let limits = ResourceLimits {
+            max_memory: Some(1 << 28),
+            max_cpu_time: None,
+        };
+
+        let mut cmd = Command::new(std::env::current_exe().unwrap());
+        cmd.args([
+            "--exact",
+            "cmd::tests::allocate_a_lot",
+            "--test-threads",
+            "1",
+        ])
+        .env("ZKLINGS_ALLOCATE_A_LOT", "1");
+        assert!(limits.apply(&mut cmd));
+
+        let mut output = Vec::new();
+        let status = run_cmd_status(cmd, "allocate_a_lot", &mut output).unwrap();
+
+        assert!(!status.success());
+        assert_eq!(
+            limits.exceeded_limit(status, &output).as_deref(),
+            Some("memory limit of 268435456 bytes"),
+        );
+    }
+
+    #[test]
+    fn tool_version() {
+        assert_eq!(
+            ToolVersion::parse("circom compiler 2.1.9\n"),
+            Some(ToolVersion(2, 1, 9)),
+        );
+        assert_eq!(
+            ToolVersion::parse("nargo version = 1.0.0-beta.3"),
+            Some(ToolVersion(1, 0, 0)),
+        );
+        assert_eq!(ToolVersion::parse("v0.5.46"), Some(ToolVersion(0, 5, 46)));
+        assert_eq!(ToolVersion::parse("unknown"), None);
+        assert!(ToolVersion(0, 5, 46) < ToolVersion(2, 0, 0));
+
+        let e = check_tool_version("zklings-missing-tool", ToolVersion(2, 0, 0), "Install it.")
+            .unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "zklings-missing-tool 2.0.0 or newer is required but it isn't installed. Install it.",
+        );
+    }
 }
@@ -0,0 +1,77 @@
+// Styling of the output which can be disabled for logs and other tools.
+// Call sites use this module's `Stylize` instead of the one of `crossterm` which always writes
+// escape sequences, also for bold and underlined text which `crossterm` doesn't skip for
+// `NO_COLOR`.
+
+use crossterm::style;
+use std::{
+    env,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Disable the styling with `no_color` (the global argument `--no-color`) or if the environment
+/// variable `NO_COLOR` isn't empty (see https://no-color.org).
+pub fn init(no_color: bool) {
+    let no_color_env = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    if no_color || no_color_env {
+        DISABLED.store(true, Ordering::Relaxed);
+    }
+}
+
+#[inline]
+pub fn enabled() -> bool {
+    !DISABLED.load(Ordering::Relaxed)
+}
+
+#[inline]
+fn styled<T: style::Stylize>(content: T, style: fn(T) -> T::Styled) -> T::Styled {
+    if enabled() {
+        style(content)
+    } else {
+        content.stylize()
+    }
+}
+
+/// The styles of `crossterm::style::Stylize` which are used in the output.
+/// Without styling, the content is written as plain text.
+pub trait Stylize: style::Stylize + Sized {
+    fn bold(self) -> Self::Styled {
+        styled(self, style::Stylize::bold)
+    }
+
+    fn dim(self) -> Self::Styled {
+        styled(self, style::Stylize::dim)
+    }
+
+    fn underlined(self) -> Self::Styled {
+        styled(self, style::Stylize::underlined)
+    }
+
+    fn red(self) -> Self::Styled {
+        styled(self, style::Stylize::red)
+    }
+
+    fn green(self) -> Self::Styled {
+        styled(self, style::Stylize::green)
+    }
+
+    fn yellow(self) -> Self::Styled {
+        styled(self, style::Stylize::yellow)
+    }
+
+    fn blue(self) -> Self::Styled {
+        styled(self, style::Stylize::blue)
+    }
+
+    fn magenta(self) -> Self::Styled {
+        styled(self, style::Stylize::magenta)
+    }
+
+    fn cyan(self) -> Self::Styled {
+        styled(self, style::Stylize::cyan)
+    }
+}
+
+impl<T: style::Stylize> Stylize for T {}
@@ -0,0 +1,226 @@
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use std::collections::HashSet;
+
+use crate::answer_syntax::{self, CASE_INSENSITIVE_LANG};
+
+/// How the answer of a markdown exercise is compared with the answer of its solution.
+/// The comparator is declared after the language of the solution's answer block, for example
+/// ```` ```math set ````. Without a declaration, the answers have to be equal.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Comparator {
+    /// The trimmed answers are equal. Whitespace at the end of the lines of multi-line answers
+    /// is ignored.
+    Exact,
+    /// The answers are equal ignoring the case and the amount of whitespace.
+    Normalized,
+    /// The answers are the same number. Decimal numbers may differ by a rounding error.
+    Numeric,
+    /// The answer fully matches the solution's answer as a regular expression.
+    Regex,
+    /// The answer contains all comma-separated keywords of the solution's answer, ignoring the case.
+    Keywords,
+    /// The answers are the same set of comma-separated elements in any order.
+    Set,
+    /// The answer is valid code in the language of the answer block (`rust` or `circom`).
+    /// The solution's answer is only an example. The answer blocks of both the exercise and the
+    /// solution have to declare it, e.g. ```` ```rust parses ````. Other `rust` and `circom`
+    /// blocks are examples.
+    Parses,
+}
+
+fn comma_separated(answer: &str) -> impl Iterator<Item = &str> {
+    answer
+        .split(',')
+        .map(str::trim)
+        .filter(|element| !element.is_empty())
+}
+
+// Multi-line answers like snippets shouldn't fail because of trailing spaces.
+fn trim_line_ends(answer: &str) -> String {
+    answer
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn normalize(answer: &str) -> String {
+    answer
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn numbers_equal(answer: &str, expected: &str) -> bool {
+    // Integers like field elements are compared exactly.
+    if let (Ok(answer), Ok(expected)) = (answer.parse::<i128>(), expected.parse::<i128>()) {
+        return answer == expected;
+    }
+
+    match (answer.parse::<f64>(), expected.parse::<f64>()) {
+        (Ok(answer), Ok(expected)) => (answer - expected).abs() <= 1e-9 * expected.abs().max(1.0),
+        _ => false,
+    }
+}
+
+impl Comparator {
+    /// Parse the declaration of the comparator in the meta string of a code block.
+    pub fn from_meta(meta: Option<&str>) -> Result<Self> {
+        let comparator = match meta.map(str::trim) {
+            None | Some("") | Some("exact") => Self::Exact,
+            Some("normalized") => Self::Normalized,
+            Some("numeric") => Self::Numeric,
+            Some("regex") => Self::Regex,
+            Some("keywords") => Self::Keywords,
+            Some("set") => Self::Set,
+            Some("parses") => Self::Parses,
+            Some(unknown) => bail!(
+                "Unknown answer comparator `{unknown}`. Supported comparators: exact, normalized, numeric, regex, keywords, set, parses"
+            ),
+        };
+
+        Ok(comparator)
+    }
+
+    /// The comparator of an answer block with the language `lang` and the meta string `meta`.
+    /// Blocks with the language `answer-ci` are compared with `Normalized`.
+    pub fn from_block(lang: &str, meta: Option<&str>) -> Result<Self> {
+        if lang != CASE_INSENSITIVE_LANG {
+            return Self::from_meta(meta);
+        }
+
+        if meta.is_some_and(|meta| !meta.trim().is_empty()) {
+            bail!("An `{CASE_INSENSITIVE_LANG}` answer block is compared ignoring the case and can't declare another comparator");
+        }
+
+        Ok(Self::Normalized)
+    }
+
+    /// Check if the `answer` matches the `expected` answer of the solution.
+    pub fn matches(self, answer: &str, expected: &str) -> Result<bool> {
+        let answer = answer.trim();
+        let expected = expected.trim();
+
+        let matches = match self {
+            Self::Exact => trim_line_ends(answer) == trim_line_ends(expected),
+            Self::Normalized => normalize(answer) == normalize(expected),
+            Self::Numeric => numbers_equal(answer, expected),
+            Self::Regex => Regex::new(&format!("^(?:{expected})$"))
+                .with_context(|| format!("Invalid regex `{expected}` in the solution's answer"))?
+                .is_match(&trim_line_ends(answer)),
+            Self::Keywords => {
+                let answer = answer.to_lowercase();
+                comma_separated(expected).all(|keyword| answer.contains(&keyword.to_lowercase()))
+            }
+            Self::Set => {
+                comma_separated(answer).collect::<HashSet<_>>()
+                    == comma_separated(expected).collect::<HashSet<_>>()
+            }
+            Self::Parses => {
+                bail!("Checking if the answer parses requires the language of its block")
+            }
+        };
+
+        Ok(matches)
+    }
+
+    /// Like `matches` but for an answer block in the language `lang`.
+    /// The reason why the answer doesn't parse is appended to the `output` buffer.
+    pub fn matches_in(
+        self,
+        lang: &str,
+        answer: &str,
+        expected: &str,
+        output: &mut Vec<u8>,
+    ) -> Result<bool> {
+        match self {
+            Self::Parses => answer_syntax::answer_parses(lang, answer.trim(), output),
+            _ => self.matches(answer, expected),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_equality() {
+        let set = Comparator::from_meta(Some("set")).unwrap();
+        assert_eq!(set, Comparator::Set);
+
+        assert!(set.matches("1, 2, 3", "1,2,3").unwrap());
+        assert!(set.matches("3,1, 2", "1, 2, 3").unwrap());
+        assert!(set.matches("2, 1, 2, 3,", "1, 2, 3").unwrap());
+        assert!(!set.matches("1, 2", "1, 2, 3").unwrap());
+        assert!(!set.matches("1, 2, 3, 4", "1, 2, 3").unwrap());
+        assert!(!set.matches("1 2 3", "1, 2, 3").unwrap());
+    }
+
+    #[test]
+    fn builtin_comparators() {
+        assert_eq!(Comparator::from_meta(None).unwrap(), Comparator::Exact);
+        assert!(Comparator::from_meta(Some("fuzzy")).is_err());
+
+        assert!(!Comparator::Exact.matches("X + 1", "x + 1").unwrap());
+        assert!(Comparator::Normalized.matches(" X  +\t1", "x + 1").unwrap());
+        assert!(Comparator::Numeric
+            .matches("0.30000000000000004", "0.3")
+            .unwrap());
+        assert!(!Comparator::Numeric
+            .matches(
+                "170141183460469231731687303715884105726",
+                "170141183460469231731687303715884105727"
+            )
+            .unwrap());
+        assert!(Comparator::Regex.matches("x^2", r"x\^2|x\*x").unwrap());
+        assert!(!Comparator::Regex.matches("x^2 + 1", r"x\^2|x\*x").unwrap());
+        assert!(Comparator::Keywords
+            .matches(
+                "It is Soundness and completeness",
+                "soundness, completeness"
+            )
+            .unwrap());
+    }
+
+    #[test]
+    fn case_insensitive_block() {
+        let comparator = Comparator::from_block("answer-ci", None).unwrap();
+        assert_eq!(comparator, Comparator::Normalized);
+        assert_eq!(
+            Comparator::from_block("math", None).unwrap(),
+            Comparator::Exact
+        );
+        assert!(Comparator::from_block("answer-ci", Some("set")).is_err());
+
+        assert!(comparator.matches("  Groth16 ", "groth16").unwrap());
+        assert!(comparator
+            .matches("zero   knowledge\tPROOF", "Zero knowledge proof")
+            .unwrap());
+        assert!(comparator.matches("ΣΑΣ Straße", "σας straße").unwrap());
+        assert!(!comparator.matches("", "groth16").unwrap());
+        assert!(!comparator.matches("groth 16", "groth16").unwrap());
+        // The default stays exact.
+        assert!(!Comparator::Exact.matches("Groth16", "groth16").unwrap());
+    }
+
+    #[test]
+    fn multi_line_answer() {
+        let expected = "signal input a;\nsignal output b;\n\nb <== a * a;";
+
+        assert!(Comparator::Exact
+            .matches(
+                "signal input a;  \r\nsignal output b;\t\n\nb <== a * a;\n",
+                expected
+            )
+            .unwrap());
+        assert!(!Comparator::Exact
+            .matches("signal input a;\nsignal output b; b <== a * a;", expected)
+            .unwrap());
+        assert!(Comparator::Regex
+            .matches("x = 1 \ny = 2", r"x = \d\ny = \d")
+            .unwrap());
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::Result;
+use clap::{builder::PossibleValuesParser, Command};
+use clap_complete::{generate, Shell};
+use std::io::{self, Write};
+
+use crate::info_file::InfoFile;
+
+// The subcommands with an argument `name`. It always takes an exercise name.
+fn exercise_name_subcommands(cmd: &Command) -> Vec<String> {
+    cmd.get_subcommands()
+        .filter(|subcommand| subcommand.get_arguments().any(|arg| arg.get_id() == "name"))
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect()
+}
+
+fn write_completion(
+    mut cmd: Command,
+    shell: Shell,
+    exercise_names: &[&'static str],
+    writer: &mut impl Write,
+) {
+    for subcommand in exercise_name_subcommands(&cmd) {
+        cmd = cmd.mut_subcommand(subcommand, |subcommand| {
+            subcommand.mut_arg("name", |arg| {
+                arg.value_parser(PossibleValuesParser::new(exercise_names.iter().copied()))
+            })
+        });
+    }
+
+    generate(shell, &mut cmd, "zklings", writer);
+}
+
+/// Write the completion script for `shell` to stdout.
+/// The exercise names are completed from the info file that is loaded when generating the script.
+pub fn completion(cmd: Command, shell: Shell) -> Result<()> {
+    let info_file = InfoFile::parse()?;
+    // Leaking is not a problem because the program exits after writing the script.
+    let exercise_names = info_file
+        .exercises
+        .into_iter()
+        .map(|exercise_info| &*exercise_info.name.leak())
+        .collect::<Vec<_>>();
+
+    let mut stdout = io::stdout().lock();
+    write_completion(cmd, shell, &exercise_names, &mut stdout);
+    stdout.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+    use crate::Args;
+
+    #[test]
+    fn script_references_subcommands() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let mut output = Vec::new();
+            write_completion(
+                Args::command(),
+                shell,
+                &["intro1", "poseidon2"],
+                &mut output,
+            );
+            let output = String::from_utf8(output).unwrap();
+
+            for subcommand in ["run", "reset", "hint", "list-types", "completion", "dev"] {
+                assert!(output.contains(subcommand), "{shell}: {subcommand}");
+            }
+            assert!(output.contains("intro1"), "{shell}");
+            assert!(output.contains("poseidon2"), "{shell}");
+        }
+    }
+
+    #[test]
+    fn subcommands_with_exercise_name() {
+        let subcommands = exercise_name_subcommands(&Args::command());
+        for subcommand in ["run", "run-once", "reset", "hint", "package", "plan"] {
+            assert!(
+                subcommands.iter().any(|name| name == subcommand),
+                "{subcommand}"
+            );
+        }
+        assert_eq!(
+            subcommands.iter().any(|name| name == "structure"),
+            cfg!(feature = "circom"),
+        );
+        assert!(!subcommands.iter().any(|name| name == "completion"));
+    }
+}
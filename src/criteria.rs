@@ -0,0 +1,368 @@
+use serde::{Deserialize, Serialize, Serializer};
+use std::{
+    fmt::{self, Display, Formatter},
+    io::{self, Write},
+    time::Duration,
+};
+
+use crate::color::Stylize;
+
+/// Conditions besides passing which an exercise has to meet to be fully solved (done).
+/// A condition is only checked if its value was measured while running the exercise.
+#[derive(Clone, Copy, Default)]
+pub struct SolvedCriteria {
+    /// Maximum number of constraints of a Circom circuit.
+    pub max_constraints: Option<u64>,
+    /// Maximum duration of verifying the proof of a Circom circuit.
+    pub max_verify_time: Option<Duration>,
+    /// No Clippy warnings in a Rust exercise.
+    pub no_warnings: bool,
+}
+
+/// Whether the stages of running an exercise succeeded.
+/// `None` if a stage wasn't reached or doesn't exist for the exercise type.
+#[derive(Clone, Copy, Default, Serialize, PartialEq, Eq, Debug)]
+pub struct Stages {
+    /// The Rust exercise was built or the circuit was compiled.
+    pub compiled: Option<bool>,
+    /// Clippy passed, for example without denied lints.
+    pub clippy_passed: Option<bool>,
+    pub tests_passed: Option<bool>,
+    /// The binary of the Rust exercise ran successfully.
+    pub ran: Option<bool>,
+}
+
+/// Values measured while running an exercise. `None` if not measured.
+#[derive(Clone, Copy, Default, Serialize)]
+pub struct Measurements {
+    pub constraints: Option<u64>,
+    /// Serialized in seconds.
+    #[serde(serialize_with = "serialize_secs")]
+    pub verify_time: Option<Duration>,
+    pub warnings: Option<usize>,
+    #[serde(flatten)]
+    pub stages: Stages,
+}
+
+fn serialize_secs<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    duration
+        .map(|duration| duration.as_secs_f64())
+        .serialize(serializer)
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum UnmetCriterion {
+    ConstraintBudget {
+        constraints: u64,
+        max: u64,
+    },
+    VerifyTimeBudget {
+        verify_time: Duration,
+        max: Duration,
+    },
+    Warnings(usize),
+}
+
+impl Display for UnmetCriterion {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::ConstraintBudget { constraints, max } => write!(
+                f,
+                "The circuit has {constraints} constraints but the budget is {max}",
+            ),
+            Self::VerifyTimeBudget { verify_time, max } => write!(
+                f,
+                "The verification took {} ms but the time budget is {} ms",
+                verify_time.as_millis(),
+                max.as_millis(),
+            ),
+            Self::Warnings(1) => f.write_str("Clippy reported 1 warning"),
+            Self::Warnings(warnings) => write!(f, "Clippy reported {warnings} warnings"),
+        }
+    }
+}
+
+// Serialized as its explanation.
+impl Serialize for UnmetCriterion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl SolvedCriteria {
+    /// The criteria which the measured values don't meet.
+    pub fn unmet(self, measurements: &Measurements) -> Vec<UnmetCriterion> {
+        let mut unmet = Vec::new();
+
+        if let (Some(max), Some(constraints)) = (self.max_constraints, measurements.constraints) {
+            if constraints > max {
+                unmet.push(UnmetCriterion::ConstraintBudget { constraints, max });
+            }
+        }
+
+        if let (Some(max), Some(verify_time)) = (self.max_verify_time, measurements.verify_time) {
+            if verify_time > max {
+                unmet.push(UnmetCriterion::VerifyTimeBudget { verify_time, max });
+            }
+        }
+
+        if let (true, Some(warnings)) = (self.no_warnings, measurements.warnings) {
+            if warnings > 0 {
+                unmet.push(UnmetCriterion::Warnings(warnings));
+            }
+        }
+
+        unmet
+    }
+}
+
+/// The structured result of running an exercise with its stages, diagnostics and measurements.
+/// Serialized in reports like the JSON report of `verify`.
+#[derive(Serialize)]
+pub struct Outcome {
+    /// The exercise compiled and ran successfully.
+    pub passed: bool,
+    /// The criteria that a passing exercise doesn't meet. Always empty if it didn't pass.
+    /// Serialized as the list `diagnostics` of their explanations.
+    #[serde(rename = "diagnostics")]
+    pub unmet: Vec<UnmetCriterion>,
+    /// The exercise didn't pass because Clippy failed.
+    #[serde(skip)]
+    pub clippy_failed: bool,
+    /// Including the stages.
+    #[serde(flatten)]
+    pub measurements: Measurements,
+}
+
+impl Outcome {
+    pub fn new(passed: bool, criteria: SolvedCriteria, measurements: Measurements) -> Self {
+        let unmet = if passed {
+            criteria.unmet(&measurements)
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            passed,
+            unmet,
+            clippy_failed: !passed && measurements.stages.clippy_passed == Some(false),
+            measurements,
+        }
+    }
+
+    /// The exercise passed and meets all criteria.
+    #[inline]
+    pub fn is_solved(&self) -> bool {
+        self.passed && self.unmet.is_empty()
+    }
+
+    /// Explain why a passing exercise isn't fully solved yet.
+    pub fn write_unmet(&self, output: &mut Vec<u8>) -> io::Result<()> {
+        if self.unmet.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(
+            output,
+            "\n{}",
+            "The exercise passed but isn't fully solved yet:"
+                .bold()
+                .yellow(),
+        )?;
+        for unmet in &self.unmet {
+            writeln!(output, "  {}", unmet.to_string().yellow())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Count the warnings in the output of Clippy, ignoring the summary line with the number of
+/// warnings.
+pub fn count_warnings(clippy_output: &str) -> usize {
+    clippy_output
+        .lines()
+        .filter(|line| {
+            line.strip_prefix("warning")
+                .is_some_and(|rest| rest.starts_with(':') || rest.starts_with('['))
+                && !line.contains(" generated ")
+        })
+        .count()
+}
+
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<Diagnostic>,
+}
+
+#[derive(Deserialize)]
+struct Diagnostic {
+    message: String,
+    level: String,
+    rendered: Option<String>,
+    spans: Vec<serde::de::IgnoredAny>,
+}
+
+/// The diagnostics of `cargo clippy --message-format=json-diagnostic-rendered-ansi`.
+pub struct ClippyReport {
+    /// The rendered diagnostics and the lines which aren't JSON messages.
+    pub rendered: String,
+    pub warnings: usize,
+}
+
+impl ClippyReport {
+    pub fn parse(clippy_output: &str) -> Self {
+        let mut rendered = String::with_capacity(clippy_output.len());
+        let mut warnings = 0;
+
+        for line in clippy_output.lines() {
+            let Ok(cargo_message) = serde_json::from_str::<CargoMessage>(line) else {
+                rendered.push_str(line);
+                rendered.push('\n');
+                continue;
+            };
+
+            let Some(diagnostic) = cargo_message
+                .message
+                .filter(|_| cargo_message.reason == "compiler-message")
+            else {
+                continue;
+            };
+
+            // Skip summaries like `2 warnings emitted` which don't point to the code.
+            if diagnostic.spans.is_empty() && diagnostic.message.ends_with(" emitted") {
+                continue;
+            }
+
+            if diagnostic.level == "warning" {
+                warnings += 1;
+            }
+
+            if let Some(diagnostic_rendered) = diagnostic.rendered {
+                rendered.push_str(&diagnostic_rendered);
+            }
+        }
+
+        Self { rendered, warnings }
+    }
+
+    /// Write the rendered diagnostics followed by the number of warnings.
+    pub fn write(&self, output: &mut Vec<u8>) -> io::Result<()> {
+        output.extend_from_slice(self.rendered.as_bytes());
+
+        match self.warnings {
+            0 => writeln!(
+                output,
+                "{}",
+                "Clippy reported no warnings. Your code is clean!".green(),
+            ),
+            1 => writeln!(output, "{}", "Clippy reported 1 warning".yellow()),
+            warnings => writeln!(
+                output,
+                "{}",
+                format!("Clippy reported {warnings} warnings").yellow(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passed_but_not_solved() {
+        let criteria = SolvedCriteria {
+            max_constraints: Some(10),
+            max_verify_time: Some(Duration::from_millis(100)),
+            no_warnings: true,
+        };
+
+        let measurements = Measurements {
+            constraints: Some(5),
+            verify_time: Some(Duration::from_millis(250)),
+            warnings: None,
+            stages: Stages::default(),
+        };
+        let outcome = Outcome::new(true, criteria, measurements);
+        assert!(outcome.passed);
+        assert!(!outcome.is_solved());
+        assert_eq!(
+            outcome.unmet,
+            [UnmetCriterion::VerifyTimeBudget {
+                verify_time: Duration::from_millis(250),
+                max: Duration::from_millis(100),
+            }],
+        );
+
+        let mut output = Vec::new();
+        outcome.write_unmet(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("isn't fully solved"));
+        assert!(output.contains("took 250 ms but the time budget is 100 ms"));
+
+        // A failure isn't about the criteria.
+        let outcome = Outcome::new(false, criteria, measurements);
+        assert!(!outcome.is_solved());
+        assert!(outcome.unmet.is_empty());
+
+        let measurements = Measurements {
+            constraints: Some(10),
+            verify_time: Some(Duration::from_millis(100)),
+            warnings: Some(0),
+            stages: Stages::default(),
+        };
+        assert!(Outcome::new(true, criteria, measurements).is_solved());
+        // Nothing to check without criteria.
+        assert!(Outcome::new(true, SolvedCriteria::default(), Measurements::default()).is_solved());
+    }
+
+    #[test]
+    fn clippy_warnings() {
+        let clippy_output = "warning: unused variable: `x`
+ --> exercises/intro/intro1.rs:2:9
+warning[E0170]: pattern binding `None` is named the same as one of the variants
+warning: `exercises` (bin \"intro1\") generated 2 warnings
+a warning: in the program output";
+        assert_eq!(count_warnings(clippy_output), 2);
+        assert_eq!(count_warnings(""), 0);
+    }
+
+    #[test]
+    fn clippy_json_warnings() {
+        let clippy_output = r#"{"reason":"compiler-artifact","package_id":"exercises","target":{"name":"intro1"}}
+{"reason":"compiler-message","message":{"message":"unused variable: `x`","level":"warning","spans":[{"file_name":"exercises/intro1.rs"}],"rendered":"warning: unused variable: `x`\n"}}
+{"reason":"compiler-message","message":{"message":"this looks like you are trying to swap","level":"warning","spans":[{"file_name":"exercises/intro1.rs"}],"rendered":"warning: this looks like you are trying to swap\n"}}
+{"reason":"compiler-message","message":{"message":"2 warnings emitted","level":"warning","spans":[],"rendered":"warning: 2 warnings emitted\n"}}
+{"reason":"build-finished","success":true}
+warning: `exercises` (bin "intro1") generated 2 warnings"#;
+
+        let report = ClippyReport::parse(clippy_output);
+        assert_eq!(report.warnings, 2);
+        assert_eq!(
+            report.rendered,
+            "warning: unused variable: `x`
+warning: this looks like you are trying to swap
+warning: `exercises` (bin \"intro1\") generated 2 warnings
+",
+        );
+
+        let mut output = Vec::new();
+        report.write(&mut output).unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("Clippy reported 2 warnings"));
+
+        let report = ClippyReport::parse(r#"{"reason":"build-finished","success":true}"#);
+        assert_eq!(report.warnings, 0);
+        let mut output = Vec::new();
+        report.write(&mut output).unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("Your code is clean!"));
+    }
+}
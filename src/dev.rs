@@ -1,12 +1,14 @@
 use anyhow::{bail, Context, Result};
 use clap::Subcommand;
-use std::path::PathBuf;
+use std::{num::NonZeroUsize, path::PathBuf};
 
 use crate::DEBUG_PROFILE;
 
 mod check;
+mod lint_content;
 mod new;
 mod update;
+mod verify_solutions;
 
 #[derive(Subcommand)]
 pub enum DevCommands {
@@ -26,6 +28,17 @@ pub enum DevCommands {
     },
     /// Update the `Cargo.toml` file for the exercises
     Update,
+    /// Lint the exercise content for common authoring mistakes
+    LintContent,
+    /// Run all solutions and report how long it took
+    VerifySolutions {
+        /// Run the solutions on a pool of workers with their own target directories
+        #[arg(long)]
+        parallel: bool,
+        /// The number of workers. Defaults to the available parallelism
+        #[arg(short, long, requires = "parallel")]
+        jobs: Option<NonZeroUsize>,
+    },
 }
 
 impl DevCommands {
@@ -40,6 +53,10 @@ impl DevCommands {
             }
             Self::Check { require_solutions } => check::check(require_solutions),
             Self::Update => update::update(),
+            Self::LintContent => lint_content::lint_content(),
+            Self::VerifySolutions { parallel, jobs } => {
+                verify_solutions::verify_solutions(parallel, jobs)
+            }
         }
     }
 }
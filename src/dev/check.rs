@@ -47,6 +47,33 @@ fn check_cargo_toml(
     Ok(())
 }
 
+// `prove = false` is only for Circom exercises and excludes all options of the proof.
+fn check_prove(exercise_info: &ExerciseInfo) -> Result<()> {
+    if exercise_info.prove {
+        return Ok(());
+    }
+
+    let name = &exercise_info.name;
+    if exercise_info.ext != "circom" {
+        bail!("The exercise `{name}` has `prove = false` but only Circom exercises are proved");
+    }
+
+    let proof_options = [
+        ("external_proof", exercise_info.external_proof),
+        ("check_vkey", exercise_info.check_vkey),
+        ("ptau", exercise_info.ptau.is_some()),
+        ("max_verify_time", exercise_info.max_verify_time.is_some()),
+        ("rust_verifier", exercise_info.rust_verifier.is_some()),
+        ("input_spec", !exercise_info.input_spec.is_empty()),
+        ("backends", !exercise_info.backends.is_empty()),
+    ];
+    if let Some((option, _)) = proof_options.iter().find(|(_, is_set)| *is_set) {
+        bail!("The exercise `{name}` has `prove = false` but sets `{option}` which is only used by the proof");
+    }
+
+    Ok(())
+}
+
 // Check the info of all exercises and return their paths in a set.
 fn check_info_file_exercises(info_file: &InfoFile) -> Result<hashbrown::HashSet<PathBuf>> {
     let mut names = hashbrown::HashSet::with_capacity(info_file.exercises.len());
@@ -79,6 +106,8 @@ fn check_info_file_exercises(info_file: &InfoFile) -> Result<hashbrown::HashSet<
             bail!("The exercise name `{name}` is duplicated. Exercise names must all be unique");
         }
 
+        check_prove(exercise_info)?;
+
         let path = exercise_info.path();
 
         OpenOptions::new()
@@ -185,7 +214,7 @@ fn check_exercises_unsolved(info_file: &InfoFile, target_dir: &Path) -> Result<(
                 };
 
                 let mut output = Vec::with_capacity(OUTPUT_CAPACITY);
-                match exercise_info.run_exercise(&mut output, target_dir) {
+                match exercise_info.run_exercise(&mut output, target_dir, None) {
                     Ok(true) => error(b"Already solved!"),
                     Ok(false) => (),
                     Err(e) => error(e.to_string().as_bytes()),
@@ -0,0 +1,377 @@
+use anyhow::{bail, Context, Result};
+use markdown::{
+    mdast::{Node, Root},
+    to_mdast, ParseOptions,
+};
+use serde::Deserialize;
+use std::{fs, path::Path};
+
+use crate::{
+    answer_syntax::{answer_block, TAGGED_ANSWER_LANG},
+    color::Stylize,
+    comparator::Comparator,
+    exercise::{split_questions, RunnableExercise},
+    info_file::{ExerciseInfo, InfoFile},
+    input_spec,
+    terminal_link::TerminalFileLink,
+    DEBUG_PROFILE,
+};
+
+// An authoring mistake in the file at `path`.
+#[derive(PartialEq, Eq, Debug)]
+struct Finding {
+    path: String,
+    message: String,
+}
+
+impl Finding {
+    fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+// An entry of the `bin` list in `Cargo.toml`.
+#[derive(Deserialize)]
+struct Bin {
+    name: String,
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct CargoToml {
+    bin: Vec<Bin>,
+}
+
+// Markdown exercises need a level 1 heading with the question and a `math`, `rust` or `circom`
+// code block with the answer for each of their questions.
+// See `RunnableExercise::extract_question_and_answer`.
+fn lint_markdown(path: &str, content: &str, findings: &mut Vec<Finding>) {
+    let Ok(ast) = to_mdast(content, &ParseOptions::default()) else {
+        findings.push(Finding::new(path, "Failed to parse the markdown file"));
+        return;
+    };
+
+    for question in split_questions(&ast) {
+        if let Node::Root(root) = question {
+            lint_question(path, &root, findings);
+        }
+    }
+}
+
+fn lint_question(path: &str, root: &Root, findings: &mut Vec<Finding>) {
+    let has_question = root
+        .children
+        .iter()
+        .any(|child| matches!(child, Node::Heading(heading) if heading.depth == 1));
+    if !has_question {
+        findings.push(Finding::new(
+            path,
+            "No question as a level 1 heading (`# …`)",
+        ));
+    }
+
+    let answer = match answer_block(&root.children) {
+        Ok(answer) => answer,
+        Err(e) => {
+            findings.push(Finding::new(path, e.to_string()));
+            return;
+        }
+    };
+    match answer {
+        Some(answer) => match Comparator::from_block(
+            answer.lang.as_deref().unwrap_or_default(),
+            answer.meta.as_deref(),
+        ) {
+            Ok(Comparator::Parses)
+                if matches!(answer.lang.as_deref(), Some("math" | TAGGED_ANSWER_LANG)) =>
+            {
+                findings.push(Finding::new(
+                    path,
+                    "The comparator `parses` requires a `rust` or `circom` answer block",
+                ));
+            }
+            Ok(_) => (),
+            Err(e) => findings.push(Finding::new(path, e.to_string())),
+        },
+        None => findings.push(Finding::new(
+            path,
+            "No code block with the answer (```math)",
+        )),
+    }
+}
+
+fn lint_circom(exercise_info: &ExerciseInfo, path: &str, findings: &mut Vec<Finding>) {
+    // The proof of exercises with an external proof is generated elsewhere.
+    if exercise_info.external_proof || !exercise_info.prove {
+        return;
+    }
+
+    // The input file is generated from the input spec.
+    if !exercise_info.input_spec.is_empty() {
+        if let Err(e) =
+            input_spec::generate_input(&exercise_info.input_spec, exercise_info.field_modulus())
+        {
+            findings.push(Finding::new(path, format!("{e:#}")));
+        }
+        return;
+    }
+
+    let circuit_dir = Path::new(path).parent().unwrap_or(Path::new(""));
+    let specific_input = format!("{}.input.json", exercise_info.name);
+    if !circuit_dir.join(&specific_input).is_file() && !circuit_dir.join("input.json").is_file() {
+        findings.push(Finding::new(
+            path,
+            format!("No `input.json` or `{specific_input}` next to the circuit"),
+        ));
+    }
+}
+
+// The binary of a Rust exercise in `Cargo.toml` must have the exercise's name.
+fn lint_bin(
+    exercise_info: &ExerciseInfo,
+    path: &str,
+    bins: &[Bin],
+    exercise_path_prefix: &str,
+    findings: &mut Vec<Finding>,
+) {
+    let bin = bins.iter().find(|bin| {
+        bin.path
+            .strip_prefix(exercise_path_prefix)
+            .is_some_and(|bin_path| bin_path == path)
+    });
+
+    match bin {
+        Some(bin) if bin.name == exercise_info.name => (),
+        Some(bin) => findings.push(Finding::new(
+            path,
+            format!(
+                "The binary of this exercise in `Cargo.toml` is named `{}` instead of `{}`",
+                bin.name, exercise_info.name,
+            ),
+        )),
+        None => findings.push(Finding::new(
+            path,
+            "No binary for this exercise in `Cargo.toml`. Run `zklings dev update`",
+        )),
+    }
+}
+
+fn lint_exercise(
+    exercise_info: &ExerciseInfo,
+    bins: &[Bin],
+    exercise_path_prefix: &str,
+    findings: &mut Vec<Finding>,
+) {
+    let path = exercise_info.path();
+
+    if exercise_info.hint.trim().is_empty() {
+        findings.push(Finding::new(
+            "info.toml",
+            format!("The exercise `{}` has an empty hint", exercise_info.name),
+        ));
+    }
+
+    match exercise_info.ext.as_str() {
+        "rs" => lint_bin(exercise_info, &path, bins, exercise_path_prefix, findings),
+        "circom" => lint_circom(exercise_info, &path, findings),
+        "md" => {
+            let sol_path = exercise_info.sol_path();
+            for path in [path, sol_path] {
+                // Missing files are reported by `dev check`.
+                if let Ok(content) = fs::read_to_string(&path) {
+                    lint_markdown(&path, &content, findings);
+                }
+            }
+        }
+        _ => (),
+    }
+}
+
+pub fn lint_content() -> Result<()> {
+    let info_file = InfoFile::parse()?;
+
+    // A hack to make `cargo run -- dev lint-content` work when developing Rustlings.
+    let (cargo_toml, exercise_path_prefix) = if DEBUG_PROFILE {
+        (include_str!("../../dev-Cargo.toml").to_string(), "../")
+    } else {
+        (
+            fs::read_to_string("Cargo.toml").context("Failed to read the file `Cargo.toml`")?,
+            "",
+        )
+    };
+    let cargo_toml = toml_edit::de::from_str::<CargoToml>(&cargo_toml)
+        .context("Failed to parse the `bin` list in `Cargo.toml`")?;
+
+    let mut findings = Vec::new();
+    for exercise_info in &info_file.exercises {
+        lint_exercise(
+            exercise_info,
+            &cargo_toml.bin,
+            exercise_path_prefix,
+            &mut findings,
+        );
+    }
+
+    if findings.is_empty() {
+        println!("No authoring mistakes found");
+        return Ok(());
+    }
+
+    for finding in &findings {
+        println!(
+            "{}: {}",
+            TerminalFileLink(&finding.path).to_string().underlined(),
+            finding.message,
+        );
+    }
+
+    bail!(
+        "Found {} authoring mistakes. See the output above",
+        findings.len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exercise_info(name: &str, ext: &str) -> ExerciseInfo {
+        ExerciseInfo {
+            dir: Some(String::from("d")),
+            test: true,
+            hint: String::from("A hint"),
+            ..ExerciseInfo::new(name, ext)
+        }
+    }
+
+    #[test]
+    fn markdown_without_answer() {
+        let mut findings = Vec::new();
+        lint_markdown("q.md", "# Question\n\n```math\n42\n```\n", &mut findings);
+        assert!(findings.is_empty());
+
+        lint_markdown("q.md", "# Question\n\n```\n42\n```\n", &mut findings);
+        assert_eq!(
+            findings,
+            [Finding::new(
+                "q.md",
+                "No code block with the answer (```math)"
+            )],
+        );
+
+        findings.clear();
+        lint_markdown("q.md", "Question?\n\n```math\n42\n```\n", &mut findings);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("No question"));
+
+        findings.clear();
+        lint_markdown(
+            "q.md",
+            "# Question\n\n```math set\n1, 2\n```\n",
+            &mut findings,
+        );
+        assert!(findings.is_empty());
+        lint_markdown(
+            "q.md",
+            "# Question\n\n```math fuzzy\n1\n```\n",
+            &mut findings,
+        );
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0]
+            .message
+            .contains("Unknown answer comparator `fuzzy`"));
+
+        findings.clear();
+        lint_markdown(
+            "q.md",
+            "# Question\n\n```rust parses\nfn f() {}\n```\n",
+            &mut findings,
+        );
+        assert!(findings.is_empty());
+        lint_markdown(
+            "q.md",
+            "# Question\n\n```math parses\n1\n```\n",
+            &mut findings,
+        );
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0]
+            .message
+            .contains("requires a `rust` or `circom`"));
+
+        findings.clear();
+        lint_markdown(
+            "q.md",
+            "# First\n\n```math\n1\n```\n\n# Second\n\nNo answer\n",
+            &mut findings,
+        );
+        assert_eq!(
+            findings,
+            [Finding::new(
+                "q.md",
+                "No code block with the answer (```math)"
+            )],
+        );
+    }
+
+    #[test]
+    fn bin_name_mismatch() {
+        let bins = [
+            Bin {
+                name: String::from("intro_1"),
+                path: String::from("../exercises/d/intro1.rs"),
+            },
+            Bin {
+                name: String::from("intro2"),
+                path: String::from("../exercises/d/intro2.rs"),
+            },
+        ];
+        let mut findings = Vec::new();
+
+        let intro2 = exercise_info("intro2", "rs");
+        lint_bin(&intro2, &intro2.path(), &bins, "../", &mut findings);
+        assert!(findings.is_empty());
+
+        let intro1 = exercise_info("intro1", "rs");
+        lint_bin(&intro1, &intro1.path(), &bins, "../", &mut findings);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0]
+            .message
+            .contains("named `intro_1` instead of `intro1`"));
+
+        let intro3 = exercise_info("intro3", "rs");
+        lint_bin(&intro3, &intro3.path(), &bins, "../", &mut findings);
+        assert_eq!(findings.len(), 2);
+        assert!(findings[1].message.starts_with("No binary"));
+    }
+
+    #[test]
+    fn empty_hint_and_missing_input() {
+        let mut circuit = exercise_info("zklings_lint_missing", "circom");
+        circuit.hint = String::from("  \n");
+        let mut findings = Vec::new();
+        lint_exercise(&circuit, &[], "", &mut findings);
+
+        assert_eq!(
+            findings,
+            [
+                Finding::new(
+                    "info.toml",
+                    "The exercise `zklings_lint_missing` has an empty hint",
+                ),
+                Finding::new(
+                    "exercises/d/zklings_lint_missing.circom",
+                    "No `input.json` or `zklings_lint_missing.input.json` next to the circuit",
+                ),
+            ],
+        );
+
+        circuit.external_proof = true;
+        circuit.hint = String::from("A hint");
+        findings.clear();
+        lint_exercise(&circuit, &[], "", &mut findings);
+        assert!(findings.is_empty());
+    }
+}
@@ -75,6 +75,9 @@ pub fn new(path: &Path, no_git: bool) -> Result<()> {
 }
 
 pub const GITIGNORE: &[u8] = b".zklings-state.txt
+.zklings-state.txt.bak
+.zklings-state.txt.tmp
+.zklings-failed.txt
 Cargo.lock
 target
 .vscode
@@ -98,6 +101,17 @@ welcome_message = """Welcome to these third-party Rustlings exercises."""
 # Optional multi-line message to be shown to users after finishing all exercises.
 final_message = """We hope that you found the exercises helpful :D"""
 
+# Optional: Set to "off" to append the output of each run in the watch mode below the previous one
+# instead of clearing the terminal. Users can override it with `--watch-clear`.
+# watch_clear = "off"
+
+# Optional modulus in decimal of the field of all exercises. The scalar field of BN254 by default.
+# field_modulus = "21888242871839275222246405745257275088548364400416034343698204186575808495617"
+
+# Optional for assessments: The maximum number of exercises whose hint can be shown.
+# Showing the same hint again doesn't count.
+# hint_budget = 5
+
 # Repeat this section for every exercise.
 [[exercises]]
 # Exercise name which is the exercise file name without the `.rs` extension.
@@ -117,6 +131,88 @@ name = "???"
 # You can optionally set `strict_clippy` to `true` (the default is `false`) to only consider
 # the exercise as done when there are no warnings left.
 # strict_clippy = false
+# With `no_warnings`, the exercise still runs with warnings but isn't done until they are fixed.
+# no_warnings = false
+# With `report_clippy_warnings`, the number of warnings is only shown to nudge the learner.
+# report_clippy_warnings = false
+
+# You can optionally limit the memory (in bytes) and CPU time (in seconds) of the exercise binary.
+# The limits are enforced on Unix. macOS only enforces the CPU time limit.
+# Other platforms ignore both limits and show a warning.
+# max_memory = 268435456
+# max_cpu_time = 10
+
+# After compiling a Circom circuit, the witness is computed from `input.json` next to the circuit
+# (or `NAME.input.json`). The exercise fails if the input doesn't satisfy the constraints.
+# Then, a Groth16 proof is generated and verified. Its proving key is either the checked-in
+# `circuit_final.zkey` next to the circuit (or `NAME.circuit_final.zkey`) or generated by a setup
+# with the Powers of Tau file `ptau`. Without an input file or a key, the exercise fails.
+# Exercises about writing circuits which are done once the circuit compiles set `prove = false`.
+# prove = true
+# If there is an `expected.json` next to the circuit (or `NAME.expected.json`), the public signals
+# of the verified proof must match it. It has the format of `public.json` of `snarkjs`.
+
+# Circom exercises can provide a proof generated elsewhere in the files `proof.json` and
+# `public.json` next to the circuit (or `NAME.proof.json` and `NAME.public.json`).
+# If `external_proof` is `true`, the exercise is only done when the verification key generated
+# from the user's circuit verifies that proof.
+# The Groth16 setup uses the Powers of Tau file `ptau` (path relative to this directory).
+# external_proof = false
+# With `check_vkey`, the protocol, the curve and the number of public signals of the exported
+# verification key are checked before verifying the proof to explain mismatches precisely.
+# check_vkey = false
+# ptau = "???"
+# The verification time is always reported. It can be limited with the budget `max_verify_time`
+# in milliseconds to show that verifying is much cheaper than proving.
+# max_verify_time = 1000
+# The number of constraints of a circuit can be limited with the budget `max_constraints`.
+# An exercise which passes but exceeds one of its budgets isn't done yet.
+# max_constraints = 100
+# After the proof was verified, a Rust program next to the circuit (here `???_verifier.rs`) can
+# verify it too, e.g. with arkworks. `zklings dev update` adds it to the `bin` list of `Cargo.toml`.
+# It gets the paths of the files in the environment variables `ZKLINGS_VKEY`, `ZKLINGS_PROOF` and
+# `ZKLINGS_PUBLIC`. The exercise is only done if both pass.
+# rust_verifier = "???_verifier"
+# Instead of writing `input.json` by hand, its signals can be declared. The file `???.input.json`
+# is generated in a temporary directory before running the circuit. Every signal has a fixed `value` (a list
+# for arrays) or a `generator` expression of the element index `i`. The values are checked to be
+# field elements (or bits with `type = "bit"`).
+# input_spec = [
+#   { signal = "a", value = 3 },
+#   { signal = "bits", type = "bit", len = 8, generator = "i % 2" },
+# ]
+# A circuit can be proven with multiple backends of snarkjs to compare their setup time, proving
+# time, verification time and proof size. It requires `ptau` and `input.json`.
+# backends = ["groth16", "plonk"]
+# Otherwise, the circuit is proven with Groth16 (the default) or PLONK. PLONK's universal setup
+# requires `ptau`. Groth16 uses a checked-in `circuit_final.zkey` if there is no `ptau`.
+# proving_system = "plonk"
+# The prime of `circom --prime`, i.e. the curve of the proof. `snarkjs` only supports `bn128`
+# (BN254, the default) and `bls12381`.
+# prime = "bls12381"
+# A minimum security level in bits of the curve of `prime`. BN254 provides about 100 bits.
+# min_security_bits = 110
+# The input spec is checked against the scalar field of BN254 unless another field modulus in
+# decimal is configured for the exercise or for all exercises at the top of this file.
+# field_modulus = "52435875175126190479447740508185965837690552500527637822603658699938581184513"
+
+# Markdown exercises can replace the label "Your answer:" of the user's answer.
+# prompt = "Enter the field element:"
+
+# Rust exercises can require or forbid APIs. Before building, the source file is searched for
+# each regular expression `regex` (comments included). The `api` is shown in the failure message.
+# required_patterns = [{ regex = '\.map\(', api = ".map" }]
+# forbidden_patterns = [{ regex = '\.unwrap\(\)', api = ".unwrap()" }]
+
+# Rust exercises can read input files at runtime (paths relative to this directory).
+# Files inside the `exercises/` directory are watched and a change of only them runs the exercise
+# again without rebuilding it.
+# input_files = ["exercises/???/input.txt"]
+
+# Volatile output like absolute paths or timestamps can be replaced before the output is compared
+# with its snapshot in `snapshots/` or written to it by `zklings verify --snapshot`
+# and checked by `zklings verify --check-snapshots`.
+# normalize = [{ regex = '/\S*/target/', replacement = "<TARGET>/" }]
 
 # A multi-line hint to be shown to users on request.
 hint = """???"""
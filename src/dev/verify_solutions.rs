@@ -0,0 +1,118 @@
+use anyhow::{bail, Result};
+use std::{
+    io::{self, Write},
+    num::NonZeroUsize,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    app_state::parse_target_dir,
+    color::Stylize,
+    exercise::{RunnableExercise, OUTPUT_CAPACITY},
+    info_file::{ExerciseInfo, InfoFile},
+    pool,
+};
+
+struct SolutionResult {
+    success: bool,
+    duration: Duration,
+    output: Vec<u8>,
+}
+
+fn run_solution(exercise_info: &ExerciseInfo, target_dir: &Path) -> SolutionResult {
+    let mut output = Vec::with_capacity(OUTPUT_CAPACITY);
+
+    let start = Instant::now();
+    let success = match exercise_info.run_solution(&mut output, target_dir) {
+        Ok(success) => success,
+        Err(e) => {
+            output.extend_from_slice(format!("\n{e:?}\n").as_bytes());
+            false
+        }
+    };
+
+    SolutionResult {
+        success,
+        duration: start.elapsed(),
+        output,
+    }
+}
+
+/// Run all solutions and report how long it took.
+/// With `parallel`, the solutions are run by a pool of workers which have their own target
+/// directories to avoid waiting for Cargo's lock. Their directories are seeded with the
+/// dependencies compiled in the shared target directory and kept for the next run.
+pub fn verify_solutions(parallel: bool, jobs: Option<NonZeroUsize>) -> Result<()> {
+    let info_file = InfoFile::parse()?;
+    let target_dir = parse_target_dir()?;
+
+    let exercise_infos = info_file
+        .exercises
+        .iter()
+        .filter(|exercise_info| Path::new(&exercise_info.sol_path()).exists())
+        .collect::<Vec<_>>();
+    let Some((first, rest)) = exercise_infos.split_first() else {
+        println!("There are no solutions to verify");
+        return Ok(());
+    };
+
+    let n_workers = pool::n_workers(parallel, jobs);
+
+    println!(
+        "Running {} solutions with {n_workers} worker(s). This may take a while…\n",
+        exercise_infos.len(),
+    );
+    let start = Instant::now();
+
+    // The first solution compiles the dependencies in the shared target directory.
+    let mut results = vec![run_solution(first, &target_dir)];
+    if n_workers == 1 {
+        results.extend(rest.iter().map(|info| run_solution(info, &target_dir)));
+    } else {
+        let worker_dirs = pool::worker_dirs(&target_dir, n_workers)?;
+        results.extend(pool::run_pool(rest, &worker_dirs, |info, worker_dir| {
+            run_solution(info, worker_dir)
+        }));
+    }
+
+    let wall_time = start.elapsed();
+
+    let mut stdout = io::stdout().lock();
+    let mut n_failures = 0;
+    for (exercise_info, result) in exercise_infos.iter().zip(&results) {
+        if result.success {
+            writeln!(
+                stdout,
+                "{} {} ({:.1}s)",
+                "ok".green(),
+                exercise_info.name,
+                result.duration.as_secs_f64(),
+            )?;
+        } else {
+            n_failures += 1;
+            writeln!(stdout, "{} {}", "FAILED".red(), exercise_info.name)?;
+            stdout.write_all(&result.output)?;
+        }
+    }
+
+    // Running the solutions serially takes about as long as all of them together.
+    let serial_time = results
+        .iter()
+        .map(|result| result.duration)
+        .sum::<Duration>();
+    writeln!(
+        stdout,
+        "\nFinished in {:.1}s. Running serially takes about {:.1}s (speedup {:.1}x)",
+        wall_time.as_secs_f64(),
+        serial_time.as_secs_f64(),
+        serial_time.as_secs_f64() / wall_time.as_secs_f64().max(f64::EPSILON),
+    )?;
+    stdout.flush()?;
+
+    if n_failures > 0 {
+        bail!("{n_failures} of {} solutions failed", results.len());
+    }
+
+    Ok(())
+}
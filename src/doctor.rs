@@ -0,0 +1,13 @@
+use anyhow::{Context, Result};
+
+use crate::{color::Stylize, fixtures, list_types};
+
+/// Check the environment of the course: the tools required by the exercise types and the
+/// fixtures listed in `fixtures.toml`. Fails if a fixture is missing or corrupt.
+pub fn doctor() -> Result<()> {
+    println!("{}", "Exercise types".bold());
+    list_types::list_types().context("Failed to list the exercise types")?;
+
+    println!("\n{}", "Fixtures".bold());
+    fixtures::verify_fixtures()
+}
@@ -0,0 +1,99 @@
+use anyhow::{bail, Context, Result};
+use std::{env, fs, path::Path, process::Command};
+
+use crate::exercise::Exercise;
+
+const NO_EDITOR_ERR: &str = "No editor is configured.
+Set the environment variable `EDITOR` (or `VISUAL`) to the command of your editor, for example:
+  export EDITOR=nano";
+
+/// The number of the first line with a `// TODO` or `/* TODO */` marker.
+fn first_todo_line(source: &str) -> Option<usize> {
+    source
+        .lines()
+        .position(|line| line.contains("// TODO") || line.contains("/* TODO"))
+        .map(|ind| ind + 1)
+}
+
+// The arguments to open the file at the line if the editor is known to support it.
+fn file_args(program: &str, path: &str, line: Option<usize>) -> Vec<String> {
+    let Some(line) = line else {
+        return vec![path.to_string()];
+    };
+
+    let name = Path::new(program)
+        .file_stem()
+        .and_then(|name| name.to_str())
+        .unwrap_or(program);
+    match name {
+        "vi" | "vim" | "nvim" | "gvim" | "nano" | "emacs" | "emacsclient" | "kak" | "micro" => {
+            vec![format!("+{line}"), path.to_string()]
+        }
+        "code" | "codium" => vec![String::from("--goto"), format!("{path}:{line}")],
+        "subl" | "hx" | "helix" | "zed" => vec![format!("{path}:{line}")],
+        _ => vec![path.to_string()],
+    }
+}
+
+/// Open the exercise file in the editor of the environment variable `EDITOR` or `VISUAL` at the
+/// first TODO marker.
+pub fn edit(exercise: &Exercise) -> Result<()> {
+    let editor = ["EDITOR", "VISUAL"]
+        .into_iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty());
+    let Some(editor) = editor else {
+        bail!(NO_EDITOR_ERR);
+    };
+
+    // The editor can have arguments like `code -w`.
+    let mut words = editor.split_whitespace();
+    let Some(program) = words.next() else {
+        bail!(NO_EDITOR_ERR);
+    };
+
+    let line = fs::read_to_string(exercise.path)
+        .ok()
+        .and_then(|source| first_todo_line(&source));
+
+    let status = Command::new(program)
+        .args(words)
+        .args(file_args(program, exercise.path, line))
+        .status()
+        .with_context(|| format!("Failed to run the editor `{editor}`"))?;
+    if !status.success() {
+        bail!("The editor `{editor}` exited with {status}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn todo_line() {
+        let source = "// intro2.rs
+fn main() {
+    /* TODO: Print something */
+    // TODO: Fix the compiler error
+}";
+        assert_eq!(first_todo_line(source), Some(3));
+        assert_eq!(first_todo_line("fn main() {}\n// TODO\n"), Some(2));
+        assert_eq!(first_todo_line("// Everything is done\nfn main() {}"), None);
+
+        assert_eq!(
+            file_args("/usr/bin/nvim", "exercises/intro2.rs", Some(3)),
+            ["+3", "exercises/intro2.rs"],
+        );
+        assert_eq!(
+            file_args("code", "exercises/intro2.rs", Some(3)),
+            ["--goto", "exercises/intro2.rs:3"],
+        );
+        assert_eq!(
+            file_args("gedit", "exercises/intro2.rs", Some(3)),
+            ["exercises/intro2.rs"],
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+
+use crate::{
+    app_state::AppState,
+    exercise::{Exercise, RunnableExercise, OUTPUT_CAPACITY},
+    verify::strip_ansi,
+};
+
+/// The version of the message schema. Increase it on incompatible changes.
+pub const PROTOCOL_VERSION: u8 = 1;
+
+/// A request of an editor. Sent as one line of JSON, for example:
+/// `{"version":1,"id":3,"method":"hint","exercise":"intro1"}`
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct RequestMessage {
+    pub version: u8,
+    /// Echoed in the response to match it with the request.
+    pub id: u64,
+    #[serde(flatten)]
+    pub request: Request,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Request {
+    /// Run an exercise without changing its state.
+    /// Runs the current exercise if the exercise name is not specified.
+    Run { exercise: Option<String> },
+    /// Get the hint of an exercise.
+    /// Uses the current exercise if the exercise name is not specified.
+    Hint { exercise: Option<String> },
+    /// Get the number of done exercises, the current exercise and the hint usage.
+    Progress,
+}
+
+/// The response to a request. Sent as one line of JSON with either a `result` or an `error`.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct ResponseMessage {
+    pub version: u8,
+    /// `None` if the request couldn't be parsed.
+    pub id: Option<u64>,
+    #[serde(flatten)]
+    pub body: ResponseBody,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseBody {
+    Result(Response),
+    Error(String),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Response {
+    Run {
+        exercise: String,
+        path: String,
+        success: bool,
+        /// The output of running the exercise without colors.
+        output: String,
+    },
+    Hint {
+        exercise: String,
+        hint: String,
+    },
+    Progress {
+        done: u16,
+        total: usize,
+        current_exercise: String,
+        current_path: String,
+        /// The number of exercises whose hint was shown.
+        hints_used: u32,
+        /// The maximum number of hints in an assessment.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        hint_budget: Option<u32>,
+    },
+}
+
+fn exercise_ind(app_state: &AppState, name: Option<&str>) -> Result<usize> {
+    match name {
+        Some(name) => app_state.exercise_ind_by_name(name),
+        None => Ok(app_state.current_exercise_ind()),
+    }
+}
+
+fn exercise<'a>(app_state: &'a AppState, name: Option<&str>) -> Result<&'a Exercise> {
+    Ok(&app_state.exercises()[exercise_ind(app_state, name)?])
+}
+
+fn handle_request(app_state: &mut AppState, request: Request) -> Result<Response> {
+    let response = match request {
+        Request::Run { exercise: name } => {
+            let exercise = exercise(app_state, name.as_deref())?;
+            let mut output = Vec::with_capacity(OUTPUT_CAPACITY);
+            let success = exercise.run_exercise(&mut output, app_state.target_dir(), None)?;
+
+            Response::Run {
+                exercise: exercise.name.to_string(),
+                path: exercise.path.to_string(),
+                success,
+                output: strip_ansi(&String::from_utf8_lossy(&output)),
+            }
+        }
+        Request::Hint { exercise: name } => {
+            let ind = exercise_ind(app_state, name.as_deref())?;
+            let Some(hint) = app_state.reveal_hint(ind)? else {
+                bail!("{}", app_state.hint_budget_exhausted_msg());
+            };
+            let hint = hint.trim().to_string();
+
+            Response::Hint {
+                exercise: app_state.exercises()[ind].name.to_string(),
+                hint,
+            }
+        }
+        Request::Progress => {
+            let current_exercise = app_state.current_exercise();
+
+            Response::Progress {
+                done: app_state.n_done(),
+                total: app_state.exercises().len(),
+                current_exercise: current_exercise.name.to_string(),
+                current_path: current_exercise.path.to_string(),
+                hints_used: app_state.hints_used(),
+                hint_budget: app_state.hint_budget(),
+            }
+        }
+    };
+
+    Ok(response)
+}
+
+fn parse_request(line: &str) -> Result<RequestMessage> {
+    let message = serde_json::from_str::<RequestMessage>(line).context("Invalid request")?;
+
+    if message.version != PROTOCOL_VERSION {
+        bail!(
+            "Unsupported protocol version {}. The supported version is {PROTOCOL_VERSION}",
+            message.version,
+        );
+    }
+
+    Ok(message)
+}
+
+// Errors are reported in the response instead of stopping the server.
+fn handle_line(app_state: &mut AppState, line: &str) -> ResponseMessage {
+    let (id, body) = match parse_request(line) {
+        Ok(message) => {
+            let body = match handle_request(app_state, message.request) {
+                Ok(response) => ResponseBody::Result(response),
+                Err(e) => ResponseBody::Error(format!("{e:#}")),
+            };
+
+            (Some(message.id), body)
+        }
+        Err(e) => (None, ResponseBody::Error(format!("{e:#}"))),
+    };
+
+    ResponseMessage {
+        version: PROTOCOL_VERSION,
+        id,
+        body,
+    }
+}
+
+/// Answer requests of an editor integration. Every line on stdin is a request and every line on
+/// stdout is the response to one request. Stops at the end of stdin.
+pub fn serve(app_state: &mut AppState) -> Result<()> {
+    let stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+
+    for line in stdin.lines() {
+        let line = line.context("Failed to read a request from stdin")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = handle_line(app_state, &line);
+        serde_json::to_writer(&mut stdout, &response).context("Failed to write a response")?;
+        stdout.write_all(b"\n")?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_round_trip() {
+        let line = r#"{"version":1,"id":3,"method":"hint","exercise":"intro1"}"#;
+        let message = parse_request(line).unwrap();
+        assert_eq!(
+            message,
+            RequestMessage {
+                version: 1,
+                id: 3,
+                request: Request::Hint {
+                    exercise: Some(String::from("intro1")),
+                },
+            },
+        );
+        assert_eq!(
+            serde_json::from_str::<RequestMessage>(&serde_json::to_string(&message).unwrap())
+                .unwrap(),
+            message,
+        );
+
+        let message = parse_request(r#"{"version":1,"id":4,"method":"progress"}"#).unwrap();
+        assert_eq!(message.request, Request::Progress);
+
+        let message = parse_request(r#"{"version":1,"id":5,"method":"run"}"#).unwrap();
+        assert_eq!(message.request, Request::Run { exercise: None });
+
+        assert!(parse_request(r#"{"version":2,"id":6,"method":"progress"}"#).is_err());
+        assert!(parse_request(r#"{"version":1,"id":7,"method":"reset"}"#).is_err());
+    }
+
+    #[test]
+    fn response_round_trip() {
+        let message = ResponseMessage {
+            version: PROTOCOL_VERSION,
+            id: Some(4),
+            body: ResponseBody::Result(Response::Progress {
+                done: 2,
+                total: 10,
+                current_exercise: String::from("intro3"),
+                current_path: String::from("exercises/intro/intro3.rs"),
+                hints_used: 1,
+                hint_budget: None,
+            }),
+        };
+        let line = serde_json::to_string(&message).unwrap();
+        assert_eq!(
+            line,
+            r#"{"version":1,"id":4,"result":{"method":"progress","done":2,"total":10,"current_exercise":"intro3","current_path":"exercises/intro/intro3.rs","hints_used":1}}"#,
+        );
+        assert_eq!(
+            serde_json::from_str::<ResponseMessage>(&line).unwrap(),
+            message,
+        );
+
+        // The hint budget of an assessment.
+        let line = r#"{"version":1,"id":4,"result":{"method":"progress","done":2,"total":10,"current_exercise":"intro3","current_path":"exercises/intro/intro3.rs","hints_used":3,"hint_budget":3}}"#;
+        let ResponseBody::Result(Response::Progress {
+            hints_used,
+            hint_budget,
+            ..
+        }) = serde_json::from_str::<ResponseMessage>(line).unwrap().body
+        else {
+            panic!("Not a progress response");
+        };
+        assert_eq!((hints_used, hint_budget), (3, Some(3)));
+
+        let message = ResponseMessage {
+            version: PROTOCOL_VERSION,
+            id: None,
+            body: ResponseBody::Error(String::from("Invalid request")),
+        };
+        let line = serde_json::to_string(&message).unwrap();
+        assert_eq!(line, r#"{"version":1,"id":null,"error":"Invalid request"}"#);
+        assert_eq!(
+            serde_json::from_str::<ResponseMessage>(&line).unwrap(),
+            message,
+        );
+    }
+}
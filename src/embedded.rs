@@ -1,6 +1,6 @@
 use anyhow::{Context, Error, Result};
 use std::{
-    fs::{create_dir, OpenOptions},
+    fs::{self, create_dir, OpenOptions},
     io::{self, Write},
 };
 
@@ -110,6 +110,13 @@ impl EmbeddedFiles {
         WriteStrategy::Overwrite.write(path, exercise_files.exercise)
     }
 
+    /// The exercise file at `path` differs from the embedded original.
+    /// A missing file doesn't differ because resetting it loses nothing.
+    pub fn exercise_differs(&self, exercise_ind: usize, path: &str) -> bool {
+        let original = self.exercise_files[exercise_ind].exercise;
+        fs::read(path).is_ok_and(|content| content != original)
+    }
+
     /// Write the solution file to disk and return its path.
     pub fn write_solution_to_disk(
         &self,
@@ -165,4 +172,18 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn changed_exercise_differs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("exercise.rs");
+        let path_str = path.to_str().unwrap();
+        assert!(!EMBEDDED_FILES.exercise_differs(0, path_str));
+
+        fs::write(&path, EMBEDDED_FILES.exercise_files[0].exercise).unwrap();
+        assert!(!EMBEDDED_FILES.exercise_differs(0, path_str));
+
+        fs::write(&path, "// Changed by the learner\n").unwrap();
+        assert!(EMBEDDED_FILES.exercise_differs(0, path_str));
+    }
 }
@@ -1,38 +1,269 @@
-use anyhow::Result;
-use crossterm::style::{style, StyledContent, Stylize};
-use markdown::{mdast::Node, to_mdast, ParseOptions};
+use anyhow::{Context, Error, Result};
+use crossterm::style::{style, StyledContent};
+use markdown::{
+    mdast::{Node, Root},
+    to_mdast, ParseOptions,
+};
 use std::{
+    collections::hash_map::DefaultHasher,
     fmt::{self, Display, Formatter},
     fs,
-    io::Write,
+    hash::{Hash, Hasher},
+    io::{self, Write},
     path::{Path, PathBuf},
     process::Command,
+    ptr,
+    sync::Mutex,
+    time::Duration,
 };
 
 use crate::{
-    cmd::{run_cmd, CargoCmd, CircomCmd},
+    answer_syntax::answer_block,
+    choices,
+    cmd::{
+        run_cmd_status_with_timeout, termination_signal, CargoCmd, PlannedCmd, ResourceLimits,
+        BIN_TIMEOUT,
+    },
+    color::Stylize,
+    comparator::Comparator,
+    criteria::{count_warnings, ClippyReport, Measurements, Outcome, SolvedCriteria},
+    field::BN128_MODULUS,
     in_official_repo,
+    info_file::Backend,
+    input_spec::InputSpec,
+    snapshot::NormalizeRule,
+    source_patterns::{check_source_file, SourcePattern},
     terminal_link::TerminalFileLink,
+    verify::strip_ansi,
     DEBUG_PROFILE,
 };
 
 /// The initial capacity of the output buffer.
 pub const OUTPUT_CAPACITY: usize = 1 << 14;
 
-// Run an exercise binary and append its output to the `output` buffer.
-// Compilation must be done before calling this method.
-fn run_bin(bin_name: &str, output: &mut Vec<u8>, target_dir: &Path) -> Result<bool> {
-    writeln!(output, "{}", "Output".underlined())?;
+/// An exercise type supported by `RunnableExercise::run_exercise`.
+pub struct ExerciseType {
+    pub name: &'static str,
+    /// Extension of the exercise files.
+    pub ext: &'static str,
+    /// External programs required to run exercises of this type.
+    pub tools: &'static [&'static str],
+    /// The Cargo feature required to run exercises of this type.
+    pub feature: Option<&'static str>,
+    /// The exercise type is supported by this build.
+    pub enabled: bool,
+}
+
+impl ExerciseType {
+    /// The error for running an exercise of this type while its feature is disabled.
+    pub fn disabled_err(&self) -> Error {
+        let mut msg = format!("This build doesn't support {} exercises", self.name);
+        if let Some(feature) = self.feature {
+            msg.push_str(&format!(". Rebuild with the Cargo feature `{feature}`"));
+        }
+
+        Error::msg(msg)
+    }
+}
+
+const RUST_TYPE: ExerciseType = ExerciseType {
+    name: "rust",
+    ext: "rs",
+    tools: &["cargo"],
+    feature: None,
+    enabled: true,
+};
+
+pub const CIRCOM_TYPE: ExerciseType = ExerciseType {
+    name: "circom",
+    ext: "circom",
+    tools: &["circom", "snarkjs"],
+    feature: Some("circom"),
+    enabled: cfg!(feature = "circom"),
+};
+
+const NOIR_TYPE: ExerciseType = ExerciseType {
+    name: "noir",
+    ext: "nr",
+    tools: &["nargo"],
+    feature: Some("noir"),
+    enabled: cfg!(feature = "noir"),
+};
+
+const MARKDOWN_TYPE: ExerciseType = ExerciseType {
+    name: "markdown",
+    ext: "md",
+    tools: &[],
+    feature: None,
+    enabled: true,
+};
+
+/// All exercise types known to this program, including the ones disabled in this build.
+pub const EXERCISE_TYPES: &[ExerciseType] = &[RUST_TYPE, CIRCOM_TYPE, NOIR_TYPE, MARKDOWN_TYPE];
+
+// The arguments of `cargo test` which are passed to the test binary.
+const TEST_ARGS: [&str; 4] = ["--", "--color", "always", "--show-output"];
+
+// `cargo test FILTER -- …` only runs the tests whose names contain the filter.
+fn filtered_test_args(filter: &str) -> Vec<&str> {
+    let mut args = Vec::with_capacity(TEST_ARGS.len() + 1);
+    args.push(filter);
+    args.extend_from_slice(&TEST_ARGS);
+    args
+}
+
+const CLIPPY_JSON_FORMAT: &str = "--message-format=json-diagnostic-rendered-ansi";
+
+// The `cargo` commands which compile and check a Rust exercise in this order.
+#[derive(Clone, Copy)]
+enum CargoStep {
+    Build,
+    // With `json`, the diagnostics are printed as JSON including their rendered text.
+    Clippy { strict: bool, json: bool },
+    Test,
+}
+
+impl CargoStep {
+    fn cmd<'a>(
+        self,
+        bin_name: &'a str,
+        target_dir: &'a Path,
+        output: &'a mut Vec<u8>,
+        dev: bool,
+    ) -> CargoCmd<'a> {
+        let (subcommand, args, description, hide_warnings): (_, &[&str], _, _) = match self {
+            Self::Build => ("build", &[], "cargo build …", false),
+            // `--profile test` is required to also check code with `[cfg(test)]`.
+            Self::Clippy { strict, json } => {
+                let args: &[&str] = match (strict, json) {
+                    (false, false) => &["--profile", "test"],
+                    (true, false) => &["--profile", "test", "--", "-D", "warnings"],
+                    (false, true) => &["--profile", "test", CLIPPY_JSON_FORMAT],
+                    (true, true) => &[
+                        "--profile",
+                        "test",
+                        CLIPPY_JSON_FORMAT,
+                        "--",
+                        "-D",
+                        "warnings",
+                    ],
+                };
+
+                ("clippy", args, "cargo clippy …", false)
+            }
+            // Hide warnings because they are shown by Clippy.
+            Self::Test => ("test", &TEST_ARGS, "cargo test …", true),
+        };
+
+        CargoCmd {
+            subcommand,
+            args,
+            bin_name,
+            description,
+            hide_warnings,
+            target_dir,
+            output,
+            dev,
+        }
+    }
+}
+
+/// The last successful build and Clippy check of a Rust exercise with input files.
+pub struct BuildCache {
+    // Fingerprint of the checked source file.
+    fingerprint: u64,
+    clippy_output: Vec<u8>,
+}
+
+// Fingerprint of a source file to detect if it changed since the last build.
+fn source_fingerprint(source_path: &str) -> Result<u64> {
+    let source =
+        fs::read(source_path).with_context(|| format!("Failed to read the file {source_path}"))?;
+
+    let mut hasher = DefaultHasher::new();
+    source_path.hash(&mut hasher);
+    source.hash(&mut hasher);
+
+    Ok(hasher.finish())
+}
+
+// The Clippy output of the last successful check if the source file didn't change since then.
+fn cached_clippy_output(
+    build_cache: &Mutex<Option<BuildCache>>,
+    fingerprint: u64,
+) -> Option<Vec<u8>> {
+    build_cache
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|build_cache| build_cache.fingerprint == fingerprint)
+        .map(|build_cache| build_cache.clippy_output.clone())
+}
 
+// Path of a compiled exercise binary.
+fn bin_path(bin_name: &str, target_dir: &Path) -> PathBuf {
     // 7 = "/debug/".len()
     let mut bin_path = PathBuf::with_capacity(target_dir.as_os_str().len() + 7 + bin_name.len());
     bin_path.push(target_dir);
     bin_path.push("debug");
     bin_path.push(bin_name);
+    bin_path
+}
+
+// Run an exercise binary with the environment variables `env` and append its output to the
+// `output` buffer. Compilation must be done before calling this method.
+fn run_bin(
+    bin_name: &str,
+    output: &mut Vec<u8>,
+    target_dir: &Path,
+    limits: ResourceLimits,
+    env: &[(&str, PathBuf)],
+) -> Result<bool> {
+    writeln!(output, "{}", "Output".underlined())?;
+
+    let bin_path = bin_path(bin_name, target_dir);
+    let mut cmd = Command::new(&bin_path);
+    cmd.envs(env.iter().map(|(key, value)| (key, value)));
+    if !limits.apply(&mut cmd) {
+        writeln!(
+            output,
+            "{}",
+            "Resource limits aren't supported on this platform. Running without them".yellow(),
+        )?;
+    }
 
-    let success = run_cmd(Command::new(&bin_path), &bin_path.to_string_lossy(), output)?;
+    let output_start = output.len();
+    let Some(status) =
+        run_cmd_status_with_timeout(cmd, &bin_path.to_string_lossy(), output, BIN_TIMEOUT)?
+    else {
+        writeln!(
+            output,
+            "{}",
+            "The exercise was terminated because it ran too long. Is there an infinite loop?"
+                .bold()
+                .red(),
+        )?;
+        return Ok(false);
+    };
+    let success = status.success();
 
-    if !success {
+    if let Some(limit) = limits.exceeded_limit(status, &output[output_start..]) {
+        writeln!(
+            output,
+            "{}",
+            format!("The exercise was terminated because it exceeded its {limit}")
+                .bold()
+                .red(),
+        )?;
+    } else if let Some(signal) = termination_signal(status) {
+        writeln!(
+            output,
+            "{}",
+            format!("The exercise was killed by the signal {signal}")
+                .bold()
+                .red(),
+        )?;
+    } else if !success {
         // This output is important to show the user that something went wrong.
         // Otherwise, calling something like `exit(1)` in an exercise without further output
         // leaves the user confused about why the exercise isn't done yet.
@@ -48,6 +279,105 @@ fn run_bin(bin_name: &str, output: &mut Vec<u8>, target_dir: &Path) -> Result<bo
     Ok(success)
 }
 
+/// The label of the user's answer in markdown exercises without a custom `prompt`.
+const DEFAULT_PROMPT: &str = "Your answer:";
+
+// Write the result of checking the user's answer of a markdown exercise.
+fn write_markdown_result(
+    output: &mut Vec<u8>,
+    prompt: Option<&str>,
+    answer: &str,
+    success: bool,
+) -> io::Result<()> {
+    let prompt = prompt.unwrap_or(DEFAULT_PROMPT);
+
+    if success {
+        writeln!(
+            output,
+            "Correct! Your solution matches the expected answer."
+        )?;
+        writeln!(output, "{} '{answer}'", prompt.bold())?;
+    } else {
+        writeln!(output, "{}", "Fix me!".red())?;
+        writeln!(output, "Your answer doesn't match the expected solution.")?;
+        writeln!(output, "{} '{answer}'", prompt.bold())?;
+        writeln!(
+            output,
+            "Check the file below and write the correct solution to the proposed problem."
+        )?;
+    }
+
+    Ok(())
+}
+
+// Parse the `content` of the markdown file at `path`. `kind` is "exercise" or "solution" for the
+// errors. Exercise files are edited by users. Therefore, a parsing failure must not panic.
+fn parse_markdown(content: &str, path: &str, kind: &str) -> Result<Node> {
+    to_mdast(content, &ParseOptions::gfm())
+        .map_err(|e| Error::msg(format!("Failed to parse the markdown {kind} {path}: {e}")))
+}
+
+// Read the markdown file at `path`. See `parse_markdown`.
+fn read_markdown_source(path: &str, kind: &str) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Failed to read the markdown {kind} {path}"))
+}
+
+// Read and parse the markdown file at `path`. See `parse_markdown`.
+fn read_markdown(path: &str, kind: &str) -> Result<Node> {
+    parse_markdown(&read_markdown_source(path, kind)?, path, kind)
+}
+
+/// Split a markdown exercise into its questions. Every level 1 heading starts a question which
+/// ends with its answer block. The result has at least one question.
+pub fn split_questions(ast: &Node) -> Vec<Node> {
+    let Node::Root(root) = ast else {
+        return vec![ast.clone()];
+    };
+
+    let mut questions = vec![Vec::new()];
+    let mut has_heading = false;
+    for child in &root.children {
+        if matches!(child, Node::Heading(heading) if heading.depth == 1) {
+            // Content before the first heading belongs to the first question.
+            if has_heading {
+                questions.push(Vec::new());
+            }
+            has_heading = true;
+        }
+
+        if let Some(question) = questions.last_mut() {
+            question.push(child.clone());
+        }
+    }
+
+    questions
+        .into_iter()
+        .map(|children| {
+            Node::Root(Root {
+                children,
+                position: None,
+            })
+        })
+        .collect()
+}
+
+// Show the question of a markdown exercise with its choices (if any) and the expected answer.
+fn write_markdown_solution(
+    output: &mut Vec<u8>,
+    question: &str,
+    choices: &[String],
+    answer: &str,
+) -> io::Result<()> {
+    writeln!(output, "{}", question.trim().bold())?;
+    choices::write_choices(choices, output)?;
+    writeln!(output, "{} '{}'", "Expected answer:".bold(), answer.trim())
+}
+
+/// Write the hint of an exercise with a header.
+pub fn write_hint(writer: &mut impl Write, hint: &str) -> io::Result<()> {
+    writeln!(writer, "{}\n{}\n", "Hint".bold().cyan().underlined(), hint)
+}
+
 /// See `info_file::ExerciseInfo`
 pub struct Exercise {
     pub dir: Option<&'static str>,
@@ -57,11 +387,79 @@ pub struct Exercise {
     pub path: &'static str,
     pub test: bool,
     pub strict_clippy: bool,
+    pub no_warnings: bool,
+    pub report_clippy_warnings: bool,
+    pub max_memory: Option<u64>,
+    pub max_cpu_time: Option<u64>,
+    pub external_proof: bool,
+    pub prove: bool,
+    pub check_vkey: bool,
+    pub ptau: Option<&'static str>,
+    pub max_verify_time: Option<u64>,
+    pub max_constraints: Option<u64>,
+    pub rust_verifier: Option<&'static str>,
+    pub input_spec: Vec<InputSpec>,
+    pub backends: Vec<Backend>,
+    pub proving_system: Backend,
+    pub prime: Option<&'static str>,
+    pub min_security_bits: Option<u32>,
+    pub field_modulus: Option<&'static str>,
+    pub prompt: Option<&'static str>,
+    pub required_patterns: Vec<SourcePattern>,
+    pub forbidden_patterns: Vec<SourcePattern>,
+    pub input_files: Vec<String>,
+    pub normalize: Vec<NormalizeRule>,
     pub hint: String,
     pub done: bool,
+    pub skipped: bool,
+    /// The hint was shown. Counts against the hint budget only once.
+    pub hint_shown: bool,
+    pub build_cache: Mutex<Option<BuildCache>>,
 }
 
 impl Exercise {
+    /// An exercise without any options of `info.toml`, for example pointing to fixture files in
+    /// a temporary directory. The solution is expected at `path` with `exercises` replaced by
+    /// `solutions`.
+    #[cfg(test)]
+    pub fn new(name: &'static str, ext: &'static str, path: &'static str) -> Self {
+        Self {
+            dir: None,
+            name,
+            ext,
+            path,
+            test: false,
+            strict_clippy: false,
+            no_warnings: false,
+            report_clippy_warnings: false,
+            max_memory: None,
+            max_cpu_time: None,
+            external_proof: false,
+            prove: true,
+            check_vkey: false,
+            ptau: None,
+            max_verify_time: None,
+            max_constraints: None,
+            rust_verifier: None,
+            input_spec: Vec::new(),
+            backends: Vec::new(),
+            proving_system: Backend::Groth16,
+            prime: None,
+            min_security_bits: None,
+            field_modulus: None,
+            prompt: None,
+            required_patterns: Vec::new(),
+            forbidden_patterns: Vec::new(),
+            input_files: Vec::new(),
+            normalize: Vec::new(),
+            hint: String::new(),
+            done: false,
+            skipped: false,
+            hint_shown: false,
+            build_cache: Mutex::new(None),
+        }
+    }
+
     pub fn terminal_link(&self) -> StyledContent<TerminalFileLink<'_>> {
         style(TerminalFileLink(self.path)).underlined().blue()
     }
@@ -83,11 +481,90 @@ impl Exercise {
         self.ext == "circom"
     }
 
+    pub fn is_noir(&self) -> bool {
+        self.ext == "nr" || self.ext == "noir"
+    }
+
     pub fn is_md(&self) -> bool {
         self.ext == "md"
     }
 }
 
+// Two exercises are equal if they have the same options and state. The build cache is ignored.
+impl PartialEq for Exercise {
+    fn eq(&self, other: &Self) -> bool {
+        // Destructured to not forget comparing new fields.
+        let Self {
+            dir,
+            name,
+            ext,
+            path,
+            test,
+            strict_clippy,
+            no_warnings,
+            report_clippy_warnings,
+            max_memory,
+            max_cpu_time,
+            external_proof,
+            prove,
+            check_vkey,
+            ptau,
+            max_verify_time,
+            max_constraints,
+            rust_verifier,
+            input_spec,
+            backends,
+            proving_system,
+            prime,
+            min_security_bits,
+            field_modulus,
+            prompt,
+            required_patterns,
+            forbidden_patterns,
+            input_files,
+            normalize,
+            hint,
+            done,
+            skipped,
+            hint_shown,
+            build_cache: _,
+        } = self;
+
+        *dir == other.dir
+            && *name == other.name
+            && *ext == other.ext
+            && *path == other.path
+            && *test == other.test
+            && *strict_clippy == other.strict_clippy
+            && *no_warnings == other.no_warnings
+            && *report_clippy_warnings == other.report_clippy_warnings
+            && *max_memory == other.max_memory
+            && *max_cpu_time == other.max_cpu_time
+            && *external_proof == other.external_proof
+            && *prove == other.prove
+            && *check_vkey == other.check_vkey
+            && *ptau == other.ptau
+            && *max_verify_time == other.max_verify_time
+            && *max_constraints == other.max_constraints
+            && *rust_verifier == other.rust_verifier
+            && *input_spec == other.input_spec
+            && *backends == other.backends
+            && *proving_system == other.proving_system
+            && *prime == other.prime
+            && *min_security_bits == other.min_security_bits
+            && *field_modulus == other.field_modulus
+            && *prompt == other.prompt
+            && *required_patterns == other.required_patterns
+            && *forbidden_patterns == other.forbidden_patterns
+            && *input_files == other.input_files
+            && *normalize == other.normalize
+            && *hint == other.hint
+            && *done == other.done
+            && *skipped == other.skipped
+            && *hint_shown == other.hint_shown
+    }
+}
+
 impl Display for Exercise {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         self.path.fmt(f)
@@ -97,175 +574,483 @@ impl Display for Exercise {
 pub trait RunnableExercise {
     fn name(&self) -> &str;
     fn strict_clippy(&self) -> bool;
+    fn report_clippy_warnings(&self) -> bool;
     fn test(&self) -> bool;
     fn is_rust(&self) -> bool;
     fn is_circom(&self) -> bool;
+    fn is_noir(&self) -> bool;
     fn is_md(&self) -> bool;
     fn path(&self) -> String;
+    fn resource_limits(&self) -> ResourceLimits;
+    // Only used by the Circom runner.
+    #[cfg_attr(not(feature = "circom"), allow(dead_code))]
+    fn external_proof(&self) -> bool;
+    #[cfg_attr(not(feature = "circom"), allow(dead_code))]
+    fn prove(&self) -> bool;
+    #[cfg_attr(not(feature = "circom"), allow(dead_code))]
+    fn check_vkey(&self) -> bool;
+    #[cfg_attr(not(feature = "circom"), allow(dead_code))]
+    fn ptau(&self) -> Option<&str>;
+    fn solved_criteria(&self) -> SolvedCriteria;
+    fn rust_verifier(&self) -> Option<&str>;
+    #[cfg_attr(not(feature = "circom"), allow(dead_code))]
+    fn input_spec(&self) -> &[InputSpec];
+    #[cfg_attr(not(feature = "circom"), allow(dead_code))]
+    fn backends(&self) -> &[Backend];
+    #[cfg_attr(not(feature = "circom"), allow(dead_code))]
+    fn proving_system(&self) -> Backend;
+    #[cfg_attr(not(feature = "circom"), allow(dead_code))]
+    fn prime(&self) -> Option<&str>;
+    #[cfg_attr(not(feature = "circom"), allow(dead_code))]
+    fn min_security_bits(&self) -> Option<u32>;
+    #[cfg_attr(not(feature = "circom"), allow(dead_code))]
+    fn field_modulus(&self) -> &str;
+    fn prompt(&self) -> Option<&str>;
+    fn required_patterns(&self) -> &[SourcePattern];
+    fn forbidden_patterns(&self) -> &[SourcePattern];
+    fn input_files(&self) -> &[String];
+    // `None` if builds can't be skipped because the exercise isn't run repeatedly.
+    fn build_cache(&self) -> Option<&Mutex<Option<BuildCache>>>;
+
+    /// Path of the source file of the Rust verifier next to the exercise file.
+    fn rust_verifier_path(&self) -> Option<PathBuf> {
+        let rust_verifier = self.rust_verifier()?;
+        Some(Path::new(&self.path()).with_file_name(format!("{rust_verifier}.rs")))
+    }
 
     // Compile, check and run the exercise or its solution (depending on `bin_name´).
+    // `source_path` is checked against the required and forbidden patterns before building.
+    // With `test_filter`, only the tests whose names contain it are run.
     // The output is written to the `output` buffer after clearing it.
-    fn run(&self, bin_name: &str, output: &mut Vec<u8>, target_dir: &Path) -> Result<bool> {
-        output.clear();
-
-        // Developing the official Rustlings.
-        let dev = DEBUG_PROFILE && in_official_repo();
-
-        let build_success = CargoCmd {
-            subcommand: "build",
-            args: &[],
+    #[inline]
+    fn run(
+        &self,
+        bin_name: &str,
+        source_path: &str,
+        output: &mut Vec<u8>,
+        target_dir: &Path,
+        test_filter: Option<&str>,
+    ) -> Result<bool> {
+        self.run_with_env(
             bin_name,
-            description: "cargo build …",
-            hide_warnings: false,
+            source_path,
+            output,
             target_dir,
+            &[],
+            test_filter,
+            &mut Measurements::default(),
+        )
+    }
+
+    // Same as `run` but the binary is run with the environment variables `env`.
+    // The number of Clippy warnings is stored in `measurements`.
+    #[allow(clippy::too_many_arguments)]
+    fn run_with_env(
+        &self,
+        bin_name: &str,
+        source_path: &str,
+        output: &mut Vec<u8>,
+        target_dir: &Path,
+        env: &[(&str, PathBuf)],
+        test_filter: Option<&str>,
+        measurements: &mut Measurements,
+    ) -> Result<bool> {
+        output.clear();
+
+        if !check_source_file(
+            source_path,
+            self.required_patterns(),
+            self.forbidden_patterns(),
             output,
-            dev,
-        }
-        .run()?;
-        if !build_success {
+        )? {
             return Ok(false);
         }
 
-        // Discard the output of `cargo build` because it will be shown again by Clippy.
-        output.clear();
+        // Developing the official Rustlings.
+        let dev = DEBUG_PROFILE && in_official_repo();
 
-        // `--profile test` is required to also check code with `[cfg(test)]`.
-        let clippy_args: &[&str] = if self.strict_clippy() {
-            &["--profile", "test", "--", "-D", "warnings"]
+        // Skip building and Clippy if only the input files changed since the last successful
+        // check. Only exercises with input files are cached to always show fresh Clippy output
+        // otherwise.
+        let build_cache = if self.input_files().is_empty() {
+            None
         } else {
-            &["--profile", "test"]
+            self.build_cache()
         };
-        let clippy_success = CargoCmd {
-            subcommand: "clippy",
-            args: clippy_args,
-            bin_name,
-            description: "cargo clippy …",
-            hide_warnings: false,
-            target_dir,
-            output,
-            dev,
-        }
-        .run()?;
-        if !clippy_success {
-            return Ok(false);
+        let fingerprint = build_cache
+            .map(|_| source_fingerprint(source_path))
+            .transpose()?;
+
+        if let Some(clippy_output) = build_cache
+            .zip(fingerprint)
+            .and_then(|(build_cache, fingerprint)| cached_clippy_output(build_cache, fingerprint))
+        {
+            output.extend_from_slice(&clippy_output);
+            measurements.stages.compiled = Some(true);
+            measurements.stages.clippy_passed = Some(true);
+        } else {
+            if let Some(build_cache) = build_cache {
+                // The binary will be replaced by the build.
+                *build_cache.lock().unwrap() = None;
+            }
+
+            let build_success = CargoStep::Build
+                .cmd(bin_name, target_dir, output, dev)
+                .run()?;
+            measurements.stages.compiled = Some(build_success);
+            if !build_success {
+                return Ok(false);
+            }
+
+            // Discard the output of `cargo build` because it will be shown again by Clippy.
+            output.clear();
+
+            let report_warnings = self.report_clippy_warnings();
+            let clippy_success = CargoStep::Clippy {
+                strict: self.strict_clippy(),
+                json: report_warnings,
+            }
+            .cmd(bin_name, target_dir, output, dev)
+            .run()?;
+
+            if report_warnings {
+                let report = ClippyReport::parse(&String::from_utf8_lossy(output));
+                output.clear();
+                report.write(output)?;
+            }
+
+            measurements.stages.clippy_passed = Some(clippy_success);
+            if !clippy_success {
+                return Ok(false);
+            }
+
+            if let (Some(build_cache), Some(fingerprint)) = (build_cache, fingerprint) {
+                *build_cache.lock().unwrap() = Some(BuildCache {
+                    fingerprint,
+                    clippy_output: output.clone(),
+                });
+            }
         }
 
+        // The output only contains the output of Clippy at this point.
+        measurements.warnings = Some(count_warnings(&strip_ansi(&String::from_utf8_lossy(
+            output,
+        ))));
+
         if !self.test() {
-            return run_bin(bin_name, output, target_dir);
+            let run_success = run_bin(bin_name, output, target_dir, self.resource_limits(), env)?;
+            measurements.stages.ran = Some(run_success);
+            return Ok(run_success);
         }
 
-        let test_success = CargoCmd {
-            subcommand: "test",
-            args: &["--", "--color", "always", "--show-output"],
-            bin_name,
-            description: "cargo test …",
-            // Hide warnings because they are shown by Clippy.
-            hide_warnings: true,
-            target_dir,
-            output,
-            dev,
+        let test_args = test_filter.map(filtered_test_args);
+        let mut test_cmd = CargoStep::Test.cmd(bin_name, target_dir, output, dev);
+        if let Some(test_args) = &test_args {
+            test_cmd.args = test_args;
         }
-        .run()?;
+        let test_success = test_cmd.run()?;
+        measurements.stages.tests_passed = Some(test_success);
 
-        let run_success = run_bin(bin_name, output, target_dir)?;
+        let run_success = run_bin(bin_name, output, target_dir, self.resource_limits(), env)?;
+        measurements.stages.ran = Some(run_success);
 
         Ok(test_success && run_success)
     }
 
-    /// Function for running Circom exercises
-    fn run_circom(&self, output: &mut Vec<u8>, _target_dir: &Path) -> Result<bool> {
-        writeln!(output, "{}", "Compiling Circom circuit...".underlined())?;
+    /// The commands that running the exercise executes in this order, without executing them.
+    fn plan(&self, target_dir: &Path) -> Result<Vec<PlannedCmd>> {
+        if self.is_circom() {
+            #[cfg(feature = "circom")]
+            return crate::circom::plan_circom(self, target_dir);
+            #[cfg(not(feature = "circom"))]
+            return Err(CIRCOM_TYPE.disabled_err());
+        }
 
-        let path = self.path().clone();
-        let full_path = Path::new(&path);
-        let circuit_dir = full_path.parent().unwrap_or(Path::new(""));
-        let circuit_file = full_path.file_name().unwrap_or_default();
+        if self.is_noir() {
+            #[cfg(feature = "noir")]
+            return Ok(crate::noir::plan_noir(self.name(), target_dir));
+            #[cfg(not(feature = "noir"))]
+            return Err(NOIR_TYPE.disabled_err());
+        }
 
-        writeln!(output, "Circuit directory: {:?}", circuit_dir)?;
-        writeln!(output, "Circuit file: {:?}", circuit_file)?;
+        if !self.is_rust() {
+            // Markdown exercises are checked without external commands.
+            return Ok(Vec::new());
+        }
 
-        let mut compile_cmd = CircomCmd {
-            args: &["--r1cs", "--wasm", "--sym"],
-            circuit_name: circuit_file.to_str().unwrap_or(self.name()),
-            description: "Compiling Circom circuit",
-            output,
-            circuit_dir,
-        };
+        Ok(self.plan_rust(self.name(), target_dir, &[]))
+    }
+
+    /// The commands of `run_with_env` in this order, without executing them.
+    fn plan_rust(
+        &self,
+        bin_name: &str,
+        target_dir: &Path,
+        env: &[(&str, PathBuf)],
+    ) -> Vec<PlannedCmd> {
+        let dev = DEBUG_PROFILE && in_official_repo();
+        // Only used when running the commands.
+        let mut output = Vec::new();
+
+        let mut steps = vec![
+            CargoStep::Build,
+            CargoStep::Clippy {
+                strict: self.strict_clippy(),
+                json: self.report_clippy_warnings(),
+            },
+        ];
+        if self.test() {
+            steps.push(CargoStep::Test);
+        }
+
+        let mut plan = steps
+            .into_iter()
+            .map(|step| {
+                let cargo_cmd = step.cmd(bin_name, target_dir, &mut output, dev);
+                PlannedCmd {
+                    description: cargo_cmd.description.to_string(),
+                    cmd: cargo_cmd.command(),
+                }
+            })
+            .collect::<Vec<_>>();
 
-        let compile_success = compile_cmd.run()?;
+        let bin_path = bin_path(bin_name, target_dir);
+        let mut cmd = Command::new(&bin_path);
+        cmd.envs(env.iter().map(|(key, value)| (key, value)));
+        plan.push(PlannedCmd {
+            description: bin_path.to_string_lossy().into_owned(),
+            cmd,
+        });
+
+        plan
+    }
 
-        if !compile_success {
+    /// Function for running Circom exercises.
+    /// The Rust verifier (if any) is only run after the circuit passed.
+    #[cfg(feature = "circom")]
+    fn run_circom(
+        &self,
+        output: &mut Vec<u8>,
+        target_dir: &Path,
+        measurements: &mut Measurements,
+    ) -> Result<bool> {
+        if !crate::circom::run_circom(self, &self.path(), output, measurements)? {
             return Ok(false);
         }
 
-        writeln!(output, "{}", "Generating proof...".underlined())?;
+        if let (Some(max), Some(constraints)) = (
+            self.solved_criteria().max_constraints,
+            measurements.constraints,
+        ) {
+            if constraints > max {
+                crate::circom::explain_constraints(self, target_dir, output)?;
+            }
+        }
 
-        // Here you would implement the logic to generate a proof
-        // This is a placeholder and would need to be expanded based on your specific requirements
-        let proof_success = true;
+        if self.rust_verifier().is_none() {
+            return Ok(true);
+        }
 
-        writeln!(output, "{}", "Verifying proof...".underlined())?;
+        crate::circom::run_rust_verifier(self, output, target_dir, measurements)
+    }
 
-        // Here you would implement the logic to verify the proof
-        // This is a placeholder and would need to be expanded based on your specific requirements
-        let verify_success = true;
+    #[cfg(not(feature = "circom"))]
+    fn run_circom(
+        &self,
+        _output: &mut Vec<u8>,
+        _target_dir: &Path,
+        _measurements: &mut Measurements,
+    ) -> Result<bool> {
+        Err(CIRCOM_TYPE.disabled_err())
+    }
 
-        Ok(compile_success && proof_success && verify_success)
+    /// Compile and prove the solution circuit like the exercise's circuit.
+    /// The constraints aren't compared with the solution's and the Rust verifier isn't run.
+    #[cfg(feature = "circom")]
+    fn run_circom_solution(&self, output: &mut Vec<u8>) -> Result<bool> {
+        crate::circom::run_circom(self, &self.sol_path(), output, &mut Measurements::default())
     }
 
-    fn run_markdown(&self, output: &mut Vec<u8>) -> Result<bool> {
-        let user_content = fs::read_to_string(self.path())?;
-        let solution_content = fs::read_to_string(self.sol_path())?;
+    #[cfg(not(feature = "circom"))]
+    fn run_circom_solution(&self, _output: &mut Vec<u8>) -> Result<bool> {
+        Err(CIRCOM_TYPE.disabled_err())
+    }
+
+    /// Function for running Noir exercises.
+    /// `package_name` is the name of the package generated for the Noir file at `source_path`.
+    #[cfg(feature = "noir")]
+    fn run_noir(
+        &self,
+        package_name: &str,
+        source_path: &str,
+        output: &mut Vec<u8>,
+        target_dir: &Path,
+        measurements: &mut Measurements,
+    ) -> Result<bool> {
+        crate::noir::run_noir(
+            package_name,
+            self.name(),
+            source_path,
+            output,
+            target_dir,
+            measurements,
+        )
+    }
 
-        println!("User content:\n{}", user_content);
-        println!("Solution content:\n{}", solution_content);
+    #[cfg(not(feature = "noir"))]
+    fn run_noir(
+        &self,
+        _package_name: &str,
+        _source_path: &str,
+        _output: &mut Vec<u8>,
+        _target_dir: &Path,
+        _measurements: &mut Measurements,
+    ) -> Result<bool> {
+        Err(NOIR_TYPE.disabled_err())
+    }
 
-        let user_ast = to_mdast(&user_content, &ParseOptions::gfm()).unwrap();
-        let solution_ast = to_mdast(&solution_content, &ParseOptions::gfm()).unwrap();
+    fn run_markdown(&self, output: &mut Vec<u8>) -> Result<bool> {
+        let user_markdown = read_markdown_source(&self.path(), "exercise")?;
+        let solution_markdown = read_markdown_source(&self.sol_path(), "solution")?;
+        self.run_markdown_source(&user_markdown, &solution_markdown, output)
+    }
 
-        let (user_question, user_answer) = self.extract_question_and_answer(&user_ast)?;
-        let (solution_question, solution_answer) =
-            self.extract_question_and_answer(&solution_ast)?;
+    /// Same as `run_markdown` but with the content of the exercise file and of the solution file
+    /// instead of reading them.
+    fn run_markdown_source(
+        &self,
+        user_markdown: &str,
+        solution_markdown: &str,
+        output: &mut Vec<u8>,
+    ) -> Result<bool> {
+        let user_ast = parse_markdown(user_markdown, &self.path(), "exercise")?;
+        let solution_ast = parse_markdown(solution_markdown, &self.sol_path(), "solution")?;
+        self.check_markdown(&user_ast, &solution_ast, output)
+    }
 
-        println!(
-            "User question: '{}'\nUser answer: '{}'",
-            user_question.trim(),
-            user_answer.trim()
-        );
-        println!(
-            "Solution question: '{}'\nSolution answer: '{}'",
-            solution_question.trim(),
-            solution_answer.trim()
-        );
+    /// Check the parsed exercise file of a markdown exercise against its parsed solution file.
+    fn check_markdown(
+        &self,
+        user_ast: &Node,
+        solution_ast: &Node,
+        output: &mut Vec<u8>,
+    ) -> Result<bool> {
+        let user_questions = split_questions(user_ast);
+        let solution_questions = split_questions(solution_ast);
+        if user_questions.len() != solution_questions.len() {
+            anyhow::bail!(
+                "The exercise {} has {} questions but its solution has {}",
+                self.path(),
+                user_questions.len(),
+                solution_questions.len(),
+            );
+        }
 
-        let success = user_answer.trim() == solution_answer.trim();
-        if success {
-            writeln!(
-                output,
-                "Correct! Your solution matches the expected answer."
-            )?;
-            writeln!(output, "Your answer: '{}'", user_answer.trim())?;
-        } else {
-            writeln!(output, "{}", "Fix me!".red())?;
-            writeln!(output, "Your answer doesn't match the expected solution.")?;
-            writeln!(output, "Your answer: '{}'", user_answer.trim())?;
+        // All questions are checked to show which ones are answered correctly.
+        let mut n_correct = 0;
+        for (user_question, solution_question) in user_questions.iter().zip(&solution_questions) {
+            if self.check_markdown_question(user_question, solution_question, output)? {
+                n_correct += 1;
+            }
+        }
+
+        let n_questions = solution_questions.len();
+        if n_questions > 1 {
             writeln!(
                 output,
-                "Check the file below and write the correct solution to the proposed problem."
+                "{}",
+                format!("{n_correct}/{n_questions} correct").bold(),
             )?;
         }
 
+        Ok(n_correct == n_questions)
+    }
+
+    // Check the answer to one question of a markdown exercise. See `split_questions`.
+    fn check_markdown_question(
+        &self,
+        user_ast: &Node,
+        solution_ast: &Node,
+        output: &mut Vec<u8>,
+    ) -> Result<bool> {
+        let (_, user_answer) = self.extract_question_and_answer(user_ast)?;
+        let (_, solution_answer) = self.extract_question_and_answer(solution_ast)?;
+
+        let (lang, comparator) = self.answer_comparator(solution_ast)?;
+        let choices = choices::extract_choices(solution_ast)?;
+        let success = if choices.is_empty() {
+            comparator.matches_in(lang, &user_answer, &solution_answer, output)?
+        } else {
+            choices::check_choice(&choices, comparator, &user_answer, &solution_answer, output)?
+        };
+        write_markdown_result(output, self.prompt(), user_answer.trim(), success)?;
+
+        Ok(success)
+    }
+
+    // Read and parse the solution file of a markdown exercise.
+    fn markdown_solution(&self) -> Result<Node> {
+        read_markdown(&self.sol_path(), "solution")
+    }
+
+    /// Check an answer of a markdown exercise which was submitted without the exercise file.
+    /// Only exercises with one question can be checked this way.
+    fn check_markdown_answer(&self, answer: &str, output: &mut Vec<u8>) -> Result<bool> {
+        self.answer_matches(answer, &self.markdown_solution()?, output)
+    }
+
+    /// Check a submitted answer against the parsed solution file of a markdown exercise.
+    /// See `check_markdown_answer`.
+    fn answer_matches(
+        &self,
+        answer: &str,
+        solution_ast: &Node,
+        output: &mut Vec<u8>,
+    ) -> Result<bool> {
+        let [solution_ast] = &split_questions(solution_ast)[..] else {
+            anyhow::bail!(
+                "The exercise {} has multiple questions. A submitted answer can only answer one",
+                self.name(),
+            );
+        };
+        let (_, solution_answer) = self.extract_question_and_answer(solution_ast)?;
+
+        let answer = answer.trim();
+        let (lang, comparator) = self.answer_comparator(solution_ast)?;
+        let choices = choices::extract_choices(solution_ast)?;
+        let success = if choices.is_empty() {
+            comparator.matches_in(lang, answer, &solution_answer, output)?
+        } else {
+            choices::check_choice(&choices, comparator, answer, &solution_answer, output)?
+        };
+        write_markdown_result(output, self.prompt(), answer, success)?;
+
         Ok(success)
     }
 
     fn sol_path(&self) -> String;
 
+    /// The language of the answer block and the comparator declared in its meta string
+    /// (```` ```LANG MODE ````).
+    fn answer_comparator<'a>(&self, ast: &'a Node) -> Result<(&'a str, Comparator)> {
+        let block = answer_block(ast.children().map_or(&[], Vec::as_slice))?;
+        let (lang, meta) = block.map_or(("math", None), |code| {
+            (
+                code.lang.as_deref().unwrap_or_default(),
+                code.meta.as_deref(),
+            )
+        });
+
+        Ok((lang, Comparator::from_block(lang, meta)?))
+    }
+
     fn extract_question_and_answer(&self, ast: &Node) -> Result<(String, String)> {
         let mut question = String::new();
         let mut answer = String::new();
         let mut in_question = false;
 
         if let Node::Root(root) = ast {
+            let answer_block = answer_block(&root.children)?;
             for child in &root.children {
                 match child {
                     Node::Heading(heading) if heading.depth == 1 => {
@@ -283,7 +1068,7 @@ pub trait RunnableExercise {
                             }
                         }
                     }
-                    Node::Code(code) if code.lang.as_deref() == Some("math") => {
+                    Node::Code(code) if answer_block.is_some_and(|block| ptr::eq(block, code)) => {
                         answer = code.value.trim().to_string();
                         break;
                     }
@@ -296,36 +1081,104 @@ pub trait RunnableExercise {
             anyhow::bail!("Failed to extract question or answer from markdown");
         }
 
-        println!("Extracted question: '{}'", question.trim());
-        println!("Extracted answer: '{}'", answer.trim());
-
         Ok((question, answer))
     }
 
-    /// Compile, check and run the exercise.
+    /// Compile, check and run the exercise and evaluate its `solved_criteria`.
+    /// Returns the structured result with the reached stages, the measurements and the unmet
+    /// criteria as diagnostics.
+    /// With `test_filter`, only the tests of a Rust exercise whose names contain it are run.
     /// The output is written to the `output` buffer after clearing it.
-    #[inline]
-    fn run_exercise(&self, output: &mut Vec<u8>, target_dir: &Path) -> Result<bool> {
-        if self.is_rust() {
-            self.run(self.name(), output, target_dir)
+    /// Unmet criteria of a passing exercise are appended to the output.
+    fn run_exercise_structured(
+        &self,
+        output: &mut Vec<u8>,
+        target_dir: &Path,
+        test_filter: Option<&str>,
+    ) -> Result<Outcome> {
+        let mut measurements = Measurements::default();
+
+        let passed = if self.is_rust() {
+            self.run_with_env(
+                self.name(),
+                &self.path(),
+                output,
+                target_dir,
+                &[],
+                test_filter,
+                &mut measurements,
+            )?
         } else if self.is_circom() {
-            self.run_circom(output, target_dir)
+            self.run_circom(output, target_dir, &mut measurements)?
+        } else if self.is_noir() {
+            self.run_noir(
+                self.name(),
+                &self.path(),
+                output,
+                target_dir,
+                &mut measurements,
+            )?
         } else if self.is_md() {
-            self.run_markdown(output)
+            self.run_markdown(output)?
         } else {
             anyhow::bail!("Unsupported exercise type")
-        }
+        };
+
+        let outcome = Outcome::new(passed, self.solved_criteria(), measurements);
+        outcome.write_unmet(output)?;
+
+        Ok(outcome)
+    }
+
+    /// Compile, check and run the exercise.
+    /// Returns `true` if the exercise is fully solved, see `run_exercise_structured`.
+    #[inline]
+    fn run_exercise(
+        &self,
+        output: &mut Vec<u8>,
+        target_dir: &Path,
+        test_filter: Option<&str>,
+    ) -> Result<bool> {
+        self.run_exercise_structured(output, target_dir, test_filter)
+            .map(|outcome| outcome.is_solved())
     }
 
     /// Compile, check and run the exercise's solution.
+    /// The solution of a markdown exercise is its expected answer which is only shown.
     /// The output is written to the `output` buffer after clearing it.
     fn run_solution(&self, output: &mut Vec<u8>, target_dir: &Path) -> Result<bool> {
+        if self.is_circom() {
+            output.clear();
+            return self.run_circom_solution(output);
+        }
+
         let name = self.name();
         let mut bin_name = String::with_capacity(name.len());
         bin_name.push_str(name);
         bin_name.push_str("_sol");
 
-        self.run(&bin_name, output, target_dir)
+        if self.is_noir() {
+            return self.run_noir(
+                &bin_name,
+                &self.sol_path(),
+                output,
+                target_dir,
+                &mut Measurements::default(),
+            );
+        }
+
+        if self.is_md() {
+            output.clear();
+            let solution_ast = self.markdown_solution()?;
+            for question_ast in split_questions(&solution_ast) {
+                let (question, answer) = self.extract_question_and_answer(&question_ast)?;
+                let choices = choices::extract_choices(&question_ast)?;
+                write_markdown_solution(output, &question, &choices, &answer)?;
+            }
+            return Ok(true);
+        }
+
+        self.run(&bin_name, &self.sol_path(), output, target_dir, None)
     }
 }
 
@@ -345,11 +1198,113 @@ impl RunnableExercise for Exercise {
         self.strict_clippy
     }
 
+    #[inline]
+    fn report_clippy_warnings(&self) -> bool {
+        self.report_clippy_warnings
+    }
+
     #[inline]
     fn test(&self) -> bool {
         self.test
     }
 
+    #[inline]
+    fn resource_limits(&self) -> ResourceLimits {
+        ResourceLimits {
+            max_memory: self.max_memory,
+            max_cpu_time: self.max_cpu_time,
+        }
+    }
+
+    #[inline]
+    fn external_proof(&self) -> bool {
+        self.external_proof
+    }
+
+    #[inline]
+    fn prove(&self) -> bool {
+        self.prove
+    }
+
+    #[inline]
+    fn check_vkey(&self) -> bool {
+        self.check_vkey
+    }
+
+    #[inline]
+    fn ptau(&self) -> Option<&str> {
+        self.ptau
+    }
+
+    #[inline]
+    fn solved_criteria(&self) -> SolvedCriteria {
+        SolvedCriteria {
+            max_constraints: self.max_constraints,
+            max_verify_time: self.max_verify_time.map(Duration::from_millis),
+            no_warnings: self.no_warnings,
+        }
+    }
+
+    #[inline]
+    fn rust_verifier(&self) -> Option<&str> {
+        self.rust_verifier
+    }
+
+    #[inline]
+    fn input_spec(&self) -> &[InputSpec] {
+        &self.input_spec
+    }
+
+    #[inline]
+    fn backends(&self) -> &[Backend] {
+        &self.backends
+    }
+
+    #[inline]
+    fn proving_system(&self) -> Backend {
+        self.proving_system
+    }
+
+    #[inline]
+    fn prime(&self) -> Option<&str> {
+        self.prime
+    }
+
+    #[inline]
+    fn min_security_bits(&self) -> Option<u32> {
+        self.min_security_bits
+    }
+
+    #[inline]
+    fn field_modulus(&self) -> &str {
+        self.field_modulus.unwrap_or(BN128_MODULUS)
+    }
+
+    #[inline]
+    fn prompt(&self) -> Option<&str> {
+        self.prompt
+    }
+
+    #[inline]
+    fn required_patterns(&self) -> &[SourcePattern] {
+        &self.required_patterns
+    }
+
+    #[inline]
+    fn forbidden_patterns(&self) -> &[SourcePattern] {
+        &self.forbidden_patterns
+    }
+
+    #[inline]
+    fn input_files(&self) -> &[String] {
+        &self.input_files
+    }
+
+    #[inline]
+    fn build_cache(&self) -> Option<&Mutex<Option<BuildCache>>> {
+        Some(&self.build_cache)
+    }
+
     #[inline]
     fn is_rust(&self) -> bool {
         self.is_rust()
@@ -360,6 +1315,11 @@ impl RunnableExercise for Exercise {
         self.is_circom()
     }
 
+    #[inline]
+    fn is_noir(&self) -> bool {
+        self.is_noir()
+    }
+
     #[inline]
     fn is_md(&self) -> bool {
         self.is_md()
@@ -367,9 +1327,204 @@ impl RunnableExercise for Exercise {
 
     #[inline]
     fn sol_path(&self) -> String {
-        let exercise_path = self.path();
-        println!("PATH: {}", self.path);
+        self.path.replace("exercises", "solutions")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_args() {
+        let mut output = Vec::new();
+        let cargo_cmd = CargoStep::Test.cmd("intro1", Path::new("target"), &mut output, false);
+        assert_eq!(cargo_cmd.args, TEST_ARGS);
+
+        assert_eq!(
+            filtered_test_args("adds_one"),
+            ["adds_one", "--", "--color", "always", "--show-output"],
+        );
+    }
+
+    #[test]
+    fn disabled_type_err() {
+        assert_eq!(
+            CIRCOM_TYPE.disabled_err().to_string(),
+            "This build doesn't support circom exercises. Rebuild with the Cargo feature `circom`",
+        );
+        assert_eq!(
+            NOIR_TYPE.disabled_err().to_string(),
+            "This build doesn't support noir exercises. Rebuild with the Cargo feature `noir`",
+        );
+        assert_eq!(
+            RUST_TYPE.disabled_err().to_string(),
+            "This build doesn't support rust exercises",
+        );
+    }
+
+    #[test]
+    fn custom_prompt() {
+        let mut output = Vec::new();
+        write_markdown_result(&mut output, Some("Enter the field element:"), "3", true).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Enter the field element:"));
+        assert!(output.contains("'3'"));
+        assert!(!output.contains(DEFAULT_PROMPT));
+
+        let mut output = Vec::new();
+        write_markdown_result(&mut output, None, "4", false).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(DEFAULT_PROMPT));
+        assert!(output.contains("'4'"));
+    }
+
+    #[test]
+    fn multiple_questions() {
+        let quiz =
+            "Intro\n\n# First?\n\n```math\n1\n```\n\n# Second?\n\n- a\n- b\n\n```math\nb\n```\n";
+        let ast = to_mdast(quiz, &ParseOptions::gfm()).unwrap();
+        let questions = split_questions(&ast);
+        assert_eq!(questions.len(), 2);
+        assert_eq!(questions[0].children().unwrap().len(), 3);
+        assert!(choices::extract_choices(&questions[0]).unwrap().is_empty());
+        assert_eq!(choices::extract_choices(&questions[1]).unwrap(), ["a", "b"]);
+
+        let ast = to_mdast("No heading", &ParseOptions::gfm()).unwrap();
+        assert_eq!(split_questions(&ast).len(), 1);
+    }
+
+    #[test]
+    fn markdown_solution() {
+        let mut output = Vec::new();
+        write_markdown_solution(
+            &mut output,
+            "Which proving system?",
+            &[String::from("Groth16"), String::from("SHA-256")],
+            "a\n",
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Which proving system?"));
+        assert!(output.contains("  b) SHA-256\n"));
+        assert!(output.contains("'a'"));
+    }
+
+    #[test]
+    fn answer_against_parsed_solution() {
+        let exercise = Exercise::new("quiz", "md", "exercises/quiz.md");
+        let parse = |markdown: &str| parse_markdown(markdown, "quiz.md", "solution").unwrap();
+        let solution = parse("# How many constraints?\n\n```math\n42\n```\n");
+        let mut output = Vec::new();
+
+        assert!(exercise
+            .answer_matches(" 42\n", &solution, &mut output)
+            .unwrap());
+        assert!(!exercise
+            .answer_matches("43", &solution, &mut output)
+            .unwrap());
+
+        let solution = "# How many constraints?\n\n```math\n42\n```\n";
+        assert!(exercise
+            .run_markdown_source(solution, solution, &mut output)
+            .unwrap());
+        assert!(!exercise
+            .run_markdown_source(&solution.replace("42", "43"), solution, &mut output)
+            .unwrap());
+        let (question, answer) = exercise
+            .extract_question_and_answer(&parse(solution))
+            .unwrap();
+        assert_eq!(question.trim(), "How many constraints?");
+        assert_eq!(answer.trim(), "42");
+
+        let two_questions = parse("# A?\n\n```math\n1\n```\n\n# B?\n\n```math\n2\n```\n");
+        assert!(exercise
+            .answer_matches("1", &two_questions, &mut output)
+            .is_err());
+    }
+
+    #[test]
+    fn markdown_fixture_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let quiz = |answer: &str| format!("# How many constraints?\n\n```math\n{answer}\n```\n");
+        let exercise_path = dir.path().join("quiz.md");
+        let solution_path = dir.path().join("quiz_solution.md");
+        fs::write(&solution_path, quiz("3")).unwrap();
+        let exercise = Exercise::new("quiz", "md", "exercises/quiz.md");
+        let check = || {
+            let user_ast = read_markdown(exercise_path.to_str().unwrap(), "exercise").unwrap();
+            let solution_ast = read_markdown(solution_path.to_str().unwrap(), "solution").unwrap();
+            exercise
+                .check_markdown(&user_ast, &solution_ast, &mut Vec::new())
+                .unwrap()
+        };
+
+        fs::write(&exercise_path, quiz("2")).unwrap();
+        assert!(!check());
+        fs::write(&exercise_path, quiz("3")).unwrap();
+        assert!(check());
+    }
+
+    #[test]
+    fn equality_ignores_build_cache() {
+        let exercise = Exercise::new("intro1", "rs", "exercises/intro1.rs");
+        let built = Exercise::new("intro1", "rs", "exercises/intro1.rs");
+        *built.build_cache.lock().unwrap() = Some(BuildCache {
+            fingerprint: 0,
+            clippy_output: b"clippy".to_vec(),
+        });
+        assert!(exercise == built);
+
+        let mut done = Exercise::new("intro1", "rs", "exercises/intro1.rs");
+        done.done = true;
+        assert!(exercise != done);
+        assert!(exercise != Exercise::new("intro2", "rs", "exercises/intro2.rs"));
+    }
+
+    #[test]
+    fn input_change_keeps_build() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        let source = dir.join("main.rs");
+        let source = source.to_str().unwrap();
+        let input = dir.join("input.txt");
+        fs::write(source, "fn main() {}").unwrap();
+        fs::write(&input, "1").unwrap();
+
+        let build_cache = Mutex::new(Some(BuildCache {
+            fingerprint: source_fingerprint(source).unwrap(),
+            clippy_output: b"clippy".to_vec(),
+        }));
+
+        fs::write(&input, "2").unwrap();
+        assert_eq!(
+            cached_clippy_output(&build_cache, source_fingerprint(source).unwrap()).as_deref(),
+            Some(&b"clippy"[..]),
+        );
+
+        fs::write(source, "fn main() { println!(); }").unwrap();
+        assert!(cached_clippy_output(&build_cache, source_fingerprint(source).unwrap()).is_none());
+    }
+
+    #[cfg(not(feature = "circom"))]
+    #[test]
+    fn run_disabled_circom_exercise() {
+        let exercise = Exercise::new("0", "circom", "exercises/0.circom");
+
+        let err = exercise
+            .run_exercise(&mut Vec::new(), Path::new("target"), None)
+            .unwrap_err();
+        assert_eq!(err.to_string(), CIRCOM_TYPE.disabled_err().to_string());
+    }
+
+    #[cfg(not(feature = "noir"))]
+    #[test]
+    fn run_disabled_noir_exercise() {
+        let exercise = Exercise::new("0", "nr", "exercises/0.nr");
 
-        exercise_path.replace("exercises", "solutions")
+        let err = exercise
+            .run_exercise(&mut Vec::new(), Path::new("target"), None)
+            .unwrap_err();
+        assert_eq!(err.to_string(), NOIR_TYPE.disabled_err().to_string());
     }
 }
@@ -0,0 +1,64 @@
+use anyhow::{bail, Result};
+
+/// The order of the scalar field of BN254 (`bn128` in Circom) which is the field of the exercises
+/// if `info.toml` doesn't configure another one.
+pub const BN128_MODULUS: &str =
+    "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+
+// A non-negative decimal integer without leading zeros.
+fn decimal(value: &str) -> Result<&str> {
+    if value.is_empty() || !value.bytes().all(|c| c.is_ascii_digit()) {
+        bail!("`{value}` isn't a non-negative decimal integer");
+    }
+
+    let value = value.trim_start_matches('0');
+    Ok(if value.is_empty() { "0" } else { value })
+}
+
+// Compare two decimal integers without leading zeros.
+#[inline]
+fn less_than(a: &str, b: &str) -> bool {
+    (a.len(), a) < (b.len(), b)
+}
+
+/// Check that a configured field modulus is a decimal integer larger than 1.
+pub fn check_modulus(modulus: &str) -> Result<()> {
+    let modulus = decimal(modulus)?;
+    if less_than(modulus, "2") {
+        bail!("The field modulus has to be larger than 1");
+    }
+
+    Ok(())
+}
+
+/// Check that `value` is a decimal element of the field with the `modulus` and return it without
+/// leading zeros.
+pub fn field_element<'a>(value: &'a str, modulus: &str) -> Result<&'a str> {
+    let value = decimal(value)?;
+    if !less_than(value, modulus) {
+        bail!("`{value}` isn't smaller than the field modulus {modulus}");
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elements_of_configured_field() {
+        assert!(check_modulus(BN128_MODULUS).is_ok());
+        assert!(check_modulus("97").is_ok());
+        assert!(check_modulus("1").is_err());
+        assert!(check_modulus("0x61").is_err());
+
+        assert_eq!(field_element("0096", "97").unwrap(), "96");
+        assert_eq!(field_element("000", "97").unwrap(), "0");
+        assert!(field_element("97", "97")
+            .unwrap_err()
+            .to_string()
+            .contains("isn't smaller than the field modulus 97"));
+        assert!(field_element("100", BN128_MODULUS).is_ok());
+    }
+}
@@ -0,0 +1,131 @@
+use anyhow::{bail, Context, Error, Result};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{
+    fs::{self, File},
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use crate::color::Stylize;
+
+const MANIFEST_PATH: &str = "fixtures.toml";
+
+/// A file shared by exercises like a Powers of Tau file or a circuit library.
+#[derive(Deserialize)]
+struct Fixture {
+    /// Path relative to the project root.
+    path: String,
+    /// Expected SHA-256 checksum in hex.
+    sha256: String,
+    /// Where to fetch the file again if it is missing or corrupt.
+    url: String,
+}
+
+/// Deserialized from the `fixtures.toml` file.
+#[derive(Deserialize)]
+struct Manifest {
+    fixtures: Vec<Fixture>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum FixtureStatus {
+    Ok,
+    Missing,
+    Corrupt,
+}
+
+pub fn sha256_file(path: impl AsRef<Path>) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn check_fixture(fixture: &Fixture) -> Result<FixtureStatus> {
+    match sha256_file(&fixture.path) {
+        Ok(checksum) => {
+            if checksum.eq_ignore_ascii_case(fixture.sha256.trim()) {
+                Ok(FixtureStatus::Ok)
+            } else {
+                Ok(FixtureStatus::Corrupt)
+            }
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(FixtureStatus::Missing),
+        Err(e) => {
+            Err(Error::from(e).context(format!("Failed to read the fixture {}", fixture.path)))
+        }
+    }
+}
+
+/// Check the SHA-256 checksum of every fixture listed in `fixtures.toml`.
+pub fn verify_fixtures() -> Result<()> {
+    let manifest = match fs::read_to_string(MANIFEST_PATH) {
+        Ok(content) => toml_edit::de::from_str::<Manifest>(&content)
+            .with_context(|| format!("Failed to parse the file `{MANIFEST_PATH}`"))?,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            println!("There is no `{MANIFEST_PATH}` file. Nothing to verify");
+            return Ok(());
+        }
+        Err(e) => {
+            return Err(Error::from(e).context(format!("Failed to read the file `{MANIFEST_PATH}`")))
+        }
+    };
+
+    let mut n_failed = 0_usize;
+    for fixture in &manifest.fixtures {
+        let status = check_fixture(fixture)?;
+        let problem = match status {
+            FixtureStatus::Ok => {
+                println!("{} {}", "ok".green(), fixture.path);
+                continue;
+            }
+            FixtureStatus::Missing => "MISSING",
+            FixtureStatus::Corrupt => "CORRUPT",
+        };
+
+        n_failed += 1;
+        println!(
+            "{} {}\n  Fetch it again from {}",
+            problem.red(),
+            fixture.path,
+            fixture.url.as_str().underlined(),
+        );
+    }
+
+    if n_failed > 0 {
+        bail!("{n_failed} fixture(s) are missing or corrupt. See the output above");
+    }
+
+    println!("\nAll fixtures are intact");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // SHA-256 of "abc".
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    #[test]
+    fn tampered_fixture() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("fixture");
+        let fixture = Fixture {
+            path: path.to_string_lossy().into_owned(),
+            sha256: ABC_SHA256.to_owned(),
+            url: String::new(),
+        };
+
+        assert_eq!(check_fixture(&fixture).unwrap(), FixtureStatus::Missing);
+
+        fs::write(&path, b"abc").unwrap();
+        assert_eq!(check_fixture(&fixture).unwrap(), FixtureStatus::Ok);
+
+        fs::write(&path, b"abd").unwrap();
+        assert_eq!(check_fixture(&fixture).unwrap(), FixtureStatus::Corrupt);
+    }
+}
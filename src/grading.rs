@@ -0,0 +1,188 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    fs,
+    io::{BufRead, Read},
+    num::NonZeroUsize,
+    path::Path,
+};
+
+use crate::exercise::{Exercise, RunnableExercise};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Attempts {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Submitted answers of markdown exercises for non-interactive grading.
+/// The answers file maps exercise names to an answer or a list of attempts in order:
+///
+/// ```toml
+/// 01_basic_modulo = "3"
+/// 02_negative_modulo = ["-2", "5"]
+/// ```
+pub struct Grading {
+    answers: HashMap<String, Vec<String>>,
+    /// Only the first attempts up to this number are graded.
+    max_attempts: Option<NonZeroUsize>,
+}
+
+impl Grading {
+    pub fn new(answers_path: &Path, max_attempts: Option<NonZeroUsize>) -> Result<Self> {
+        let content = fs::read_to_string(answers_path).with_context(|| {
+            format!("Failed to read the answers file {}", answers_path.display())
+        })?;
+
+        Self::parse(&content, max_attempts).with_context(|| {
+            format!(
+                "Failed to parse the answers file {}",
+                answers_path.display(),
+            )
+        })
+    }
+
+    /// Grade the markdown exercise `name` with the answer read from `input` until its end,
+    /// e.g. `echo 3 | zklings run-once 01_basic_modulo --answer-stdin`.
+    pub fn read_answer(name: &str, mut input: impl BufRead) -> Result<Self> {
+        let mut answer = String::new();
+        input
+            .read_to_string(&mut answer)
+            .context("Failed to read the answer from stdin")?;
+
+        Ok(Self {
+            answers: HashMap::from([(name.to_string(), vec![answer])]),
+            max_attempts: None,
+        })
+    }
+
+    fn parse(content: &str, max_attempts: Option<NonZeroUsize>) -> Result<Self> {
+        let answers = toml_edit::de::from_str::<HashMap<String, Attempts>>(content)?
+            .into_iter()
+            .map(|(name, attempts)| {
+                let attempts = match attempts {
+                    Attempts::One(answer) => vec![answer],
+                    Attempts::Many(attempts) => attempts,
+                };
+
+                (name, attempts)
+            })
+            .collect();
+
+        Ok(Self {
+            answers,
+            max_attempts,
+        })
+    }
+
+    /// Grade the submitted attempts of a markdown exercise in order until one is correct.
+    /// Returns the reason of the failure if no graded attempt is correct.
+    pub fn grade(&self, exercise: &Exercise, output: &mut Vec<u8>) -> Result<Option<String>> {
+        let attempts = self
+            .answers
+            .get(exercise.name)
+            .map_or(&[][..], Vec::as_slice);
+
+        self.grade_attempts(
+            attempts,
+            |answer, output| exercise.check_markdown_answer(answer, output),
+            output,
+        )
+    }
+
+    fn grade_attempts(
+        &self,
+        attempts: &[String],
+        mut check: impl FnMut(&str, &mut Vec<u8>) -> Result<bool>,
+        output: &mut Vec<u8>,
+    ) -> Result<Option<String>> {
+        if attempts.is_empty() {
+            return Ok(Some(String::from("No answer was submitted")));
+        }
+
+        let n_graded = self.max_attempts.map_or(attempts.len(), |max_attempts| {
+            attempts.len().min(max_attempts.get())
+        });
+
+        for answer in &attempts[..n_graded] {
+            if check(answer, output)? {
+                return Ok(None);
+            }
+        }
+
+        let reason = match self.max_attempts {
+            Some(max_attempts) if attempts.len() >= max_attempts.get() => {
+                format!("No correct answer within the maximum of {max_attempts} attempts")
+            }
+            _ if n_graded == 1 => String::from("The submitted answer is wrong"),
+            _ => format!("None of the {n_graded} submitted answers is correct"),
+        };
+
+        Ok(Some(reason))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = r#"
+01_basic_modulo = "3"
+02_negative_modulo = ["-2", "7", "5"]
+"#;
+
+    fn grade(grading: &Grading, name: &str) -> (Option<String>, Vec<String>) {
+        let mut checked = Vec::new();
+        let attempts = grading.answers.get(name).map_or(&[][..], Vec::as_slice);
+        let reason = grading
+            .grade_attempts(
+                attempts,
+                |answer, _| {
+                    checked.push(answer.to_string());
+                    Ok(answer == "5")
+                },
+                &mut Vec::new(),
+            )
+            .unwrap();
+
+        (reason, checked)
+    }
+
+    #[test]
+    fn max_attempts_exceeded() {
+        let grading = Grading::parse(ANSWERS, NonZeroUsize::new(2)).unwrap();
+
+        // The correct third attempt isn't graded.
+        let (reason, checked) = grade(&grading, "02_negative_modulo");
+        assert_eq!(checked, ["-2", "7"]);
+        assert_eq!(
+            reason.as_deref(),
+            Some("No correct answer within the maximum of 2 attempts"),
+        );
+
+        let (reason, _) = grade(&grading, "01_basic_modulo");
+        assert_eq!(reason.as_deref(), Some("The submitted answer is wrong"),);
+
+        let (reason, _) = grade(&grading, "03_modular_addition");
+        assert_eq!(reason.as_deref(), Some("No answer was submitted"));
+
+        let grading = Grading::parse(ANSWERS, None).unwrap();
+        let (reason, checked) = grade(&grading, "02_negative_modulo");
+        assert_eq!(reason, None);
+        assert_eq!(checked.len(), 3);
+    }
+
+    #[test]
+    fn answer_from_reader() {
+        let grading = Grading::read_answer("01_basic_modulo", &b"5\n"[..]).unwrap();
+        let (reason, checked) = grade(&grading, "01_basic_modulo");
+        assert_eq!(reason, None);
+        assert_eq!(checked, ["5\n"]);
+
+        let grading = Grading::read_answer("01_basic_modulo", &b"4"[..]).unwrap();
+        let (reason, _) = grade(&grading, "01_basic_modulo");
+        assert_eq!(reason.as_deref(), Some("The submitted answer is wrong"));
+    }
+}
@@ -1,8 +1,27 @@
 use anyhow::{bail, Context, Error, Result};
 use serde::Deserialize;
-use std::{fs, io::ErrorKind};
+use std::{fs, io::ErrorKind, sync::Mutex, time::Duration};
 
-use crate::{embedded::EMBEDDED_FILES, exercise::RunnableExercise};
+use crate::{
+    cmd::ResourceLimits,
+    criteria::SolvedCriteria,
+    embedded::EMBEDDED_FILES,
+    exercise::{BuildCache, RunnableExercise},
+    field::{self, BN128_MODULUS},
+    input_spec::InputSpec,
+    snapshot::NormalizeRule,
+    source_patterns::SourcePattern,
+    watch::WatchClear,
+};
+
+/// A proving system of `snarkjs`.
+#[derive(Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    #[default]
+    Groth16,
+    Plonk,
+}
 
 /// Deserialized from the `info.toml` file.
 #[derive(Deserialize)]
@@ -19,6 +38,76 @@ pub struct ExerciseInfo {
     /// Deny all Clippy warnings.
     #[serde(default)]
     pub strict_clippy: bool,
+    /// The exercise is only done without Clippy warnings. Unlike with `strict_clippy`, it still
+    /// runs with warnings.
+    #[serde(default)]
+    pub no_warnings: bool,
+    /// Show the number of Clippy warnings without affecting whether the exercise is done.
+    #[serde(default)]
+    pub report_clippy_warnings: bool,
+    /// Maximum memory in bytes that the exercise binary is allowed to use.
+    pub max_memory: Option<u64>,
+    /// Maximum CPU time in seconds that the exercise binary is allowed to use.
+    pub max_cpu_time: Option<u64>,
+    /// Circom exercises: Verify the proof in `proof.json` and `public.json` next to the circuit
+    /// with the verification key generated from the user's circuit.
+    #[serde(default)]
+    pub external_proof: bool,
+    /// Circom exercises: Prove the circuit after compiling it. With `false`, the exercise is done
+    /// once the circuit compiles, for exercises about writing circuits without an input.
+    #[serde(default = "default_true")]
+    pub prove: bool,
+    /// Circom exercises with `external_proof`: Check the protocol, the curve and the number of
+    /// public signals of the exported verification key before verifying the proof.
+    #[serde(default)]
+    pub check_vkey: bool,
+    /// Circom exercises: Path to the Powers of Tau file used for the Groth16 setup.
+    pub ptau: Option<String>,
+    /// Circom exercises with `external_proof`: Maximum duration of the verification in milliseconds
+    /// for the exercise to be done.
+    pub max_verify_time: Option<u64>,
+    /// Circom exercises: Maximum number of constraints of the circuit for the exercise to be done.
+    pub max_constraints: Option<u64>,
+    /// Circom exercises with `external_proof`: Name of a Rust program next to the circuit which
+    /// is run after the proof was verified. It gets the paths of the verification key, the proof
+    /// and the public signals in environment variables.
+    pub rust_verifier: Option<String>,
+    /// Circom exercises: Signals of the `NAME.input.json` file which is generated in a temporary
+    /// directory before running the circuit. It's used instead of an input file next to the circuit.
+    #[serde(default)]
+    pub input_spec: Vec<InputSpec>,
+    /// Circom exercises: Prove the circuit with each of these backends and compare them.
+    #[serde(default)]
+    pub backends: Vec<Backend>,
+    /// Circom exercises without `external_proof` or `backends`: The proving system of the proof.
+    /// PLONK requires `ptau` for its universal setup.
+    #[serde(default)]
+    pub proving_system: Backend,
+    /// Circom exercises: The prime of `circom --prime`, i.e. the curve of the proof.
+    /// Only `bn128` (BN254, the default) and `bls12381` are supported by `snarkjs`.
+    pub prime: Option<String>,
+    /// Circom exercises: Minimum security level in bits of the curve of `prime`.
+    pub min_security_bits: Option<u32>,
+    /// Circom exercises: Modulus of the field of the input signals in decimal.
+    /// Overrides `field_modulus` of the info file.
+    pub field_modulus: Option<String>,
+    /// Markdown exercises: Custom label of the user's answer instead of "Your answer:".
+    pub prompt: Option<String>,
+    /// Rust exercises: Patterns that the source file must contain, e.g. to require `.map`.
+    #[serde(default)]
+    pub required_patterns: Vec<SourcePattern>,
+    /// Rust exercises: Patterns that the source file must not contain, e.g. to forbid `.unwrap()`.
+    #[serde(default)]
+    pub forbidden_patterns: Vec<SourcePattern>,
+    /// Rust exercises: Files read by the exercise at runtime.
+    /// If only they changed, the exercise is run again without rebuilding it.
+    #[serde(default)]
+    pub input_files: Vec<String>,
+    /// Replacements of volatile output like absolute paths before the output is compared with its
+    /// snapshot or written to it by
+    /// `verify --snapshot`.
+    #[serde(default)]
+    pub normalize: Vec<NormalizeRule>,
     /// The exercise's hint to be shown to the user on request.
     pub hint: String,
     /// The exercise is already solved. Ignore it when checking that all exercises are unsolved.
@@ -31,6 +120,43 @@ const fn default_true() -> bool {
 }
 
 impl ExerciseInfo {
+    /// An exercise in `exercises/` without a directory and with all options unset, for tests.
+    /// Other options can be set with `ExerciseInfo { test: true, ..ExerciseInfo::new(…) }`.
+    #[cfg(test)]
+    pub fn new(name: &str, ext: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ext: ext.to_string(),
+            dir: None,
+            test: false,
+            strict_clippy: false,
+            no_warnings: false,
+            report_clippy_warnings: false,
+            max_memory: None,
+            max_cpu_time: None,
+            external_proof: false,
+            prove: true,
+            check_vkey: false,
+            ptau: None,
+            max_verify_time: None,
+            max_constraints: None,
+            rust_verifier: None,
+            input_spec: Vec::new(),
+            backends: Vec::new(),
+            proving_system: Backend::Groth16,
+            prime: None,
+            min_security_bits: None,
+            field_modulus: None,
+            prompt: None,
+            required_patterns: Vec::new(),
+            forbidden_patterns: Vec::new(),
+            input_files: Vec::new(),
+            normalize: Vec::new(),
+            hint: String::new(),
+            skip_check_unsolved: false,
+        }
+    }
+
     /// Path to the exercise file starting with the `exercises/` directory.
     pub fn path(&self) -> String {
         let mut path = if let Some(dir) = &self.dir {
@@ -101,11 +227,113 @@ impl RunnableExercise for ExerciseInfo {
         self.strict_clippy
     }
 
+    #[inline]
+    fn report_clippy_warnings(&self) -> bool {
+        self.report_clippy_warnings
+    }
+
     #[inline]
     fn test(&self) -> bool {
         self.test
     }
 
+    #[inline]
+    fn resource_limits(&self) -> ResourceLimits {
+        ResourceLimits {
+            max_memory: self.max_memory,
+            max_cpu_time: self.max_cpu_time,
+        }
+    }
+
+    #[inline]
+    fn external_proof(&self) -> bool {
+        self.external_proof
+    }
+
+    #[inline]
+    fn prove(&self) -> bool {
+        self.prove
+    }
+
+    #[inline]
+    fn check_vkey(&self) -> bool {
+        self.check_vkey
+    }
+
+    #[inline]
+    fn ptau(&self) -> Option<&str> {
+        self.ptau.as_deref()
+    }
+
+    #[inline]
+    fn solved_criteria(&self) -> SolvedCriteria {
+        SolvedCriteria {
+            max_constraints: self.max_constraints,
+            max_verify_time: self.max_verify_time.map(Duration::from_millis),
+            no_warnings: self.no_warnings,
+        }
+    }
+
+    #[inline]
+    fn rust_verifier(&self) -> Option<&str> {
+        self.rust_verifier.as_deref()
+    }
+
+    #[inline]
+    fn input_spec(&self) -> &[InputSpec] {
+        &self.input_spec
+    }
+
+    #[inline]
+    fn backends(&self) -> &[Backend] {
+        &self.backends
+    }
+
+    #[inline]
+    fn proving_system(&self) -> Backend {
+        self.proving_system
+    }
+
+    #[inline]
+    fn prime(&self) -> Option<&str> {
+        self.prime.as_deref()
+    }
+
+    #[inline]
+    fn min_security_bits(&self) -> Option<u32> {
+        self.min_security_bits
+    }
+
+    #[inline]
+    fn field_modulus(&self) -> &str {
+        self.field_modulus.as_deref().unwrap_or(BN128_MODULUS)
+    }
+
+    #[inline]
+    fn prompt(&self) -> Option<&str> {
+        self.prompt.as_deref()
+    }
+
+    #[inline]
+    fn required_patterns(&self) -> &[SourcePattern] {
+        &self.required_patterns
+    }
+
+    #[inline]
+    fn forbidden_patterns(&self) -> &[SourcePattern] {
+        &self.forbidden_patterns
+    }
+
+    #[inline]
+    fn input_files(&self) -> &[String] {
+        &self.input_files
+    }
+
+    #[inline]
+    fn build_cache(&self) -> Option<&Mutex<Option<BuildCache>>> {
+        None
+    }
+
     fn is_rust(&self) -> bool {
         self.ext == "rs"
     }
@@ -114,6 +342,10 @@ impl RunnableExercise for ExerciseInfo {
         self.ext == "circom"
     }
 
+    fn is_noir(&self) -> bool {
+        self.ext == "nr" || self.ext == "noir"
+    }
+
     fn is_md(&self) -> bool {
         self.ext == "md"
     }
@@ -128,22 +360,52 @@ pub struct InfoFile {
     pub welcome_message: Option<String>,
     /// Shown to users after finishing all exercises.
     pub final_message: Option<String>,
+    /// Whether the watch mode clears the terminal before showing the output of a run.
+    pub watch_clear: Option<WatchClear>,
+    /// Modulus of the field of all exercises in decimal. The scalar field of BN254 by default.
+    pub field_modulus: Option<String>,
+    /// For assessments: Maximum number of exercises whose hint can be shown.
+    pub hint_budget: Option<u32>,
     /// List of all exercises.
     pub exercises: Vec<ExerciseInfo>,
 }
 
 impl InfoFile {
+    // Check the configured field moduli and use the one of the info file for the exercises which
+    // don't override it.
+    fn apply_field_modulus(&mut self) -> Result<()> {
+        if let Some(modulus) = &self.field_modulus {
+            field::check_modulus(modulus).context("Invalid `field_modulus` in `info.toml`")?;
+        }
+
+        for exercise_info in &mut self.exercises {
+            match &exercise_info.field_modulus {
+                Some(modulus) => field::check_modulus(modulus).with_context(|| {
+                    format!(
+                        "Invalid `field_modulus` of the exercise `{}`",
+                        exercise_info.name
+                    )
+                })?,
+                None => exercise_info.field_modulus.clone_from(&self.field_modulus),
+            }
+        }
+
+        Ok(())
+    }
+
     /// Official exercises: Parse the embedded `info.toml` file.
     /// Third-party exercises: Parse the `info.toml` file in the current directory.
     pub fn parse() -> Result<Self> {
         // Read a local `info.toml` if it exists.
-        let slf = match fs::read_to_string("info.toml") {
+        let mut slf = match fs::read_to_string("info.toml") {
             Ok(file_content) => toml_edit::de::from_str::<Self>(&file_content)
                 .context("Failed to parse the `info.toml` file")?,
             Err(e) => {
                 if e.kind() == ErrorKind::NotFound {
-                    return toml_edit::de::from_str(EMBEDDED_FILES.info_file)
-                        .context("Failed to parse the embedded `info.toml` file");
+                    let mut slf = toml_edit::de::from_str::<Self>(EMBEDDED_FILES.info_file)
+                        .context("Failed to parse the embedded `info.toml` file")?;
+                    slf.apply_field_modulus()?;
+                    return Ok(slf);
                 }
 
                 return Err(Error::from(e).context("Failed to read the `info.toml` file"));
@@ -154,6 +416,8 @@ impl InfoFile {
             bail!("{NO_EXERCISES_ERR}");
         }
 
+        slf.apply_field_modulus()?;
+
         Ok(slf)
     }
 }
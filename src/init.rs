@@ -1,5 +1,4 @@
 use anyhow::{bail, Context, Result};
-use crossterm::style::Stylize;
 use std::{
     env::set_current_dir,
     fs::{self, create_dir},
@@ -8,7 +7,9 @@ use std::{
     process::{Command, Stdio},
 };
 
-use crate::{cargo_toml::updated_cargo_toml, embedded::EMBEDDED_FILES, info_file::InfoFile};
+use crate::{
+    cargo_toml::updated_cargo_toml, color::Stylize, embedded::EMBEDDED_FILES, info_file::InfoFile,
+};
 
 pub fn init() -> Result<()> {
     // Prevent initialization in a directory that contains the file `Cargo.toml`.
@@ -91,6 +92,9 @@ const INIT_SOLUTION_FILE: &[u8] = b"fn main() {
 ";
 
 const GITIGNORE: &[u8] = b".zklings-state.txt
+.zklings-state.txt.bak
+.zklings-state.txt.tmp
+.zklings-failed.txt
 solutions
 Cargo.lock
 target
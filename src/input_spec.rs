@@ -0,0 +1,344 @@
+// Only the Circom runner generates inputs.
+#![cfg_attr(not(feature = "circom"), allow(dead_code))]
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::field;
+
+/// The type of the elements of an input signal.
+#[derive(Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum InputType {
+    /// Any element of the field.
+    #[default]
+    Field,
+    /// 0 or 1.
+    Bit,
+}
+
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+#[serde(untagged)]
+pub enum Scalar {
+    Integer(i64),
+    Decimal(String),
+}
+
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+#[serde(untagged)]
+pub enum FixedValue {
+    One(Scalar),
+    Many(Vec<Scalar>),
+}
+
+/// An input signal of the main component of a Circom exercise whose `input.json` is generated.
+/// Either `value` or `generator` is required.
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+pub struct InputSpec {
+    /// Name of the input signal.
+    pub signal: String,
+    #[serde(default, rename = "type")]
+    pub ty: InputType,
+    /// Number of elements of an array signal. A single element if not specified.
+    pub len: Option<usize>,
+    /// A fixed value or a list of values of an array signal.
+    pub value: Option<FixedValue>,
+    /// An integer expression of the element index `i` like `(i + 1) * 3 % 7`.
+    /// Supports `+`, `-`, `*`, `/`, `%` and parentheses.
+    pub generator: Option<String>,
+}
+
+// Recursive descent parser of generator expressions which evaluates while parsing.
+struct Evaluator<'a> {
+    expr: &'a [u8],
+    pos: usize,
+    i: i128,
+}
+
+impl Evaluator<'_> {
+    fn peek(&mut self) -> Option<u8> {
+        while self.expr.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+
+        self.expr.get(self.pos).copied()
+    }
+
+    // expr = term (("+" | "-") term)*
+    fn expr(&mut self) -> Result<i128> {
+        let mut value = self.term()?;
+
+        loop {
+            let op: fn(i128, i128) -> Option<i128> = match self.peek() {
+                Some(b'+') => i128::checked_add,
+                Some(b'-') => i128::checked_sub,
+                _ => return Ok(value),
+            };
+            self.pos += 1;
+            value = op(value, self.term()?).context("Overflow")?;
+        }
+    }
+
+    // term = factor (("*" | "/" | "%") factor)*
+    fn term(&mut self) -> Result<i128> {
+        let mut value = self.factor()?;
+
+        loop {
+            let op: fn(i128, i128) -> Option<i128> = match self.peek() {
+                Some(b'*') => i128::checked_mul,
+                Some(b'/') => i128::checked_div,
+                Some(b'%') => i128::checked_rem,
+                _ => return Ok(value),
+            };
+            self.pos += 1;
+            value = op(value, self.factor()?).context("Overflow or division by zero")?;
+        }
+    }
+
+    // factor = "-" factor | "(" expr ")" | "i" | number
+    fn factor(&mut self) -> Result<i128> {
+        match self.peek() {
+            Some(b'-') => {
+                self.pos += 1;
+                self.factor()?.checked_neg().context("Overflow")
+            }
+            Some(b'(') => {
+                self.pos += 1;
+                let value = self.expr()?;
+                if self.peek() != Some(b')') {
+                    bail!("Missing `)` at position {}", self.pos);
+                }
+                self.pos += 1;
+                Ok(value)
+            }
+            Some(b'i') => {
+                self.pos += 1;
+                Ok(self.i)
+            }
+            Some(b'0'..=b'9') => {
+                let start = self.pos;
+                while self.expr.get(self.pos).is_some_and(u8::is_ascii_digit) {
+                    self.pos += 1;
+                }
+                // Only ASCII digits.
+                let digits = std::str::from_utf8(&self.expr[start..self.pos]).unwrap_or_default();
+                digits.parse().context("Number too large")
+            }
+            Some(c) => bail!("Unexpected `{}` at position {}", char::from(c), self.pos),
+            None => bail!("Unexpected end of the expression"),
+        }
+    }
+}
+
+// Evaluate a generator expression for the element index `i`.
+fn evaluate(expr: &str, i: i128) -> Result<i128> {
+    let mut evaluator = Evaluator {
+        expr: expr.as_bytes(),
+        pos: 0,
+        i,
+    };
+
+    let value = evaluator
+        .expr()
+        .and_then(|value| match evaluator.peek() {
+            None => Ok(value),
+            Some(c) => bail!(
+                "Unexpected `{}` at position {}",
+                char::from(c),
+                evaluator.pos
+            ),
+        })
+        .with_context(|| format!("Invalid generator expression `{expr}`"))?;
+
+    Ok(value)
+}
+
+// Check that `value` is a decimal element of the field and of the type `ty`.
+fn field_element(value: &str, ty: InputType, modulus: &str) -> Result<String> {
+    let value = field::field_element(value, modulus)?;
+
+    if ty == InputType::Bit && value != "0" && value != "1" {
+        bail!("`{value}` isn't a bit (0 or 1)");
+    }
+
+    Ok(value.to_string())
+}
+
+impl InputSpec {
+    fn element(&self, i: usize, fixed: Option<&Scalar>, modulus: &str) -> Result<Value> {
+        let value = match (fixed, &self.generator) {
+            (Some(Scalar::Integer(value)), _) => value.to_string(),
+            (Some(Scalar::Decimal(value)), _) => value.trim().to_string(),
+            (None, Some(generator)) => evaluate(generator, i as i128)?.to_string(),
+            (None, None) => bail!("Neither `value` nor `generator` is specified"),
+        };
+
+        field_element(&value, self.ty, modulus).map(Value::String)
+    }
+
+    fn generate(&self, modulus: &str) -> Result<Value> {
+        if self.value.is_some() && self.generator.is_some() {
+            bail!("Only one of `value` and `generator` can be specified");
+        }
+
+        match (self.len, &self.value) {
+            (None, Some(FixedValue::Many(_))) => {
+                bail!("A list of values requires the length `len` of the array signal")
+            }
+            (None, Some(FixedValue::One(value))) => self.element(0, Some(value), modulus),
+            (None, None) => self.element(0, None, modulus),
+            (Some(len), Some(FixedValue::Many(values))) => {
+                if values.len() != len {
+                    bail!(
+                        "{} values for an array signal of length {len}",
+                        values.len()
+                    );
+                }
+
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| self.element(i, Some(value), modulus))
+                    .collect::<Result<_>>()
+                    .map(Value::Array)
+            }
+            (Some(len), Some(FixedValue::One(value))) => (0..len)
+                .map(|i| self.element(i, Some(value), modulus))
+                .collect::<Result<_>>()
+                .map(Value::Array),
+            (Some(len), None) => (0..len)
+                .map(|i| self.element(i, None, modulus))
+                .collect::<Result<_>>()
+                .map(Value::Array),
+        }
+    }
+}
+
+/// Generate the content of `input.json` from the input specs of an exercise whose field has the
+/// `modulus`.
+pub fn generate_input(specs: &[InputSpec], modulus: &str) -> Result<Map<String, Value>> {
+    let mut input = Map::with_capacity(specs.len());
+
+    for spec in specs {
+        let value = spec
+            .generate(modulus)
+            .with_context(|| format!("Invalid input spec of the signal `{}`", spec.signal))?;
+
+        if input.insert(spec.signal.clone(), value).is_some() {
+            bail!("The signal `{}` is specified more than once", spec.signal);
+        }
+    }
+
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(specs: &str) -> Vec<InputSpec> {
+        #[derive(Deserialize)]
+        struct Specs {
+            input_spec: Vec<InputSpec>,
+        }
+
+        toml_edit::de::from_str::<Specs>(specs).unwrap().input_spec
+    }
+
+    #[test]
+    fn generate_from_spec() {
+        let specs = parse(
+            r#"
+[[input_spec]]
+signal = "a"
+value = 3
+
+[[input_spec]]
+signal = "b"
+value = "21888242871839275222246405745257275088548364400416034343698204186575808495616"
+
+[[input_spec]]
+signal = "squares"
+len = 4
+generator = "(i + 1) * (i + 1)"
+
+[[input_spec]]
+signal = "bits"
+type = "bit"
+len = 3
+generator = "i % 2"
+"#,
+        );
+
+        let input = generate_input(&specs, field::BN128_MODULUS).unwrap();
+        assert_eq!(
+            Value::Object(input),
+            serde_json::json!({
+                "a": "3",
+                "b": "21888242871839275222246405745257275088548364400416034343698204186575808495616",
+                "squares": ["1", "4", "9", "16"],
+                "bits": ["0", "1", "0"],
+            }),
+        );
+    }
+
+    #[test]
+    fn invalid_values() {
+        let error = |specs: &str| {
+            format!(
+                "{:#}",
+                generate_input(&parse(specs), field::BN128_MODULUS).unwrap_err()
+            )
+        };
+
+        assert!(error(
+            r#"
+[[input_spec]]
+signal = "a"
+value = "21888242871839275222246405745257275088548364400416034343698204186575808495617"
+"#
+        )
+        .contains("isn't smaller than the field modulus"));
+        assert!(error(
+            r#"
+[[input_spec]]
+signal = "a"
+len = 2
+generator = "i - 1"
+"#
+        )
+        .contains("`-1` isn't a non-negative decimal integer"));
+        assert!(error(
+            r#"
+[[input_spec]]
+signal = "bits"
+type = "bit"
+len = 3
+generator = "i"
+"#
+        )
+        .contains("`2` isn't a bit"));
+        assert!(error(
+            r#"
+[[input_spec]]
+signal = "a"
+generator = "(i + 1"
+"#
+        )
+        .contains("Missing `)`"));
+
+        // The field of the exercise can be configured.
+        let specs = parse(
+            r#"
+[[input_spec]]
+signal = "a"
+len = 3
+generator = "i * 3"
+"#,
+        );
+        assert!(generate_input(&specs, "7").is_ok());
+        assert!(format!("{:#}", generate_input(&specs, "5").unwrap_err())
+            .contains("`6` isn't smaller than the field modulus 5"));
+    }
+}
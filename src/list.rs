@@ -13,7 +13,8 @@ use self::state::{Filter, UiState};
 
 mod state;
 
-pub fn list(app_state: &mut AppState) -> Result<()> {
+/// `only_skipped` starts the list with the filter SKIPPED enabled.
+pub fn list(app_state: &mut AppState, only_skipped: bool) -> Result<()> {
     let mut stdout = io::stdout().lock();
     stdout.execute(EnterAlternateScreen)?;
     enable_raw_mode()?;
@@ -22,6 +23,13 @@ pub fn list(app_state: &mut AppState) -> Result<()> {
     terminal.clear()?;
 
     let mut ui_state = UiState::new(app_state);
+    if only_skipped {
+        ui_state.filter = Filter::Skipped;
+        ui_state = ui_state.with_updated_rows();
+        ui_state
+            .message
+            .push_str("Enabled filter SKIPPED │ Press s to disable the filter");
+    }
 
     'outer: loop {
         terminal.draw(|frame| ui_state.draw(frame).unwrap())?;
@@ -40,6 +48,10 @@ pub fn list(app_state: &mut AppState) -> Result<()> {
         };
 
         ui_state.message.clear();
+        // Resetting an exercise with changes is confirmed by pressing `r` twice in a row.
+        if key.code != KeyCode::Char('r') {
+            ui_state.unconfirmed_reset = None;
+        }
 
         match key.code {
             KeyCode::Char('q') => break,
@@ -71,6 +83,18 @@ pub fn list(app_state: &mut AppState) -> Result<()> {
                 ui_state = ui_state.with_updated_rows();
                 ui_state.message.push_str(message);
             }
+            KeyCode::Char('s') => {
+                let message = if ui_state.filter == Filter::Skipped {
+                    ui_state.filter = Filter::None;
+                    "Disabled filter SKIPPED"
+                } else {
+                    ui_state.filter = Filter::Skipped;
+                    "Enabled filter SKIPPED │ Press s again to disable the filter"
+                };
+
+                ui_state = ui_state.with_updated_rows();
+                ui_state.message.push_str(message);
+            }
             KeyCode::Char('r') => {
                 ui_state = ui_state.with_reset_selected()?;
             }
@@ -8,19 +8,33 @@ use ratatui::{
 };
 use std::fmt::Write;
 
-use crate::{app_state::AppState, progress_bar::progress_bar_ratatui};
+use crate::{app_state::AppState, exercise::Exercise, progress_bar::progress_bar_ratatui};
 
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum Filter {
     Done,
     Pending,
+    Skipped,
     None,
 }
 
+impl Filter {
+    fn matches(self, exercise: &Exercise) -> bool {
+        match self {
+            Self::Done => exercise.done,
+            Self::Pending => !exercise.done,
+            Self::Skipped => !exercise.done && exercise.skipped,
+            Self::None => true,
+        }
+    }
+}
+
 pub struct UiState<'a> {
     pub table: Table<'static>,
     pub message: String,
     pub filter: Filter,
+    /// The exercise with changes which is reset if `r` is pressed again.
+    pub unconfirmed_reset: Option<usize>,
     app_state: &'a mut AppState,
     table_state: TableState,
     n_rows: usize,
@@ -37,17 +51,15 @@ impl<'a> UiState<'a> {
             .iter()
             .enumerate()
             .filter_map(|(ind, exercise)| {
-                let exercise_state = if exercise.done {
-                    if self.filter == Filter::Pending {
-                        return None;
-                    }
+                if !self.filter.matches(exercise) {
+                    return None;
+                }
 
+                let exercise_state = if exercise.done {
                     "DONE".green()
+                } else if exercise.skipped {
+                    "SKIPPED".magenta()
                 } else {
-                    if self.filter == Filter::Done {
-                        return None;
-                    }
-
                     "PENDING".yellow()
                 };
 
@@ -120,6 +132,7 @@ impl<'a> UiState<'a> {
             table,
             message: String::with_capacity(128),
             filter,
+            unconfirmed_reset: None,
             app_state,
             table_state,
             n_rows,
@@ -191,20 +204,27 @@ impl<'a> UiState<'a> {
 
         let message = if self.message.is_empty() {
             // Help footer.
-            let mut spans = Vec::with_capacity(4);
+            let mut spans = Vec::with_capacity(7);
             spans.push(Span::raw(
                 "↓/j ↑/k home/g end/G │ <c>ontinue at │ <r>eset │ filter ",
             ));
-            match self.filter {
-                Filter::Done => {
-                    spans.push("<d>one".underlined().magenta());
-                    spans.push(Span::raw("/<p>ending"));
+            for (ind, (filter, label)) in [
+                (Filter::Done, "<d>one"),
+                (Filter::Pending, "<p>ending"),
+                (Filter::Skipped, "<s>kipped"),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                if ind > 0 {
+                    spans.push(Span::raw("/"));
                 }
-                Filter::Pending => {
-                    spans.push(Span::raw("<d>one/"));
-                    spans.push("<p>ending".underlined().magenta());
+
+                if self.filter == filter {
+                    spans.push(label.underlined().magenta());
+                } else {
+                    spans.push(Span::raw(label));
                 }
-                Filter::None => spans.push(Span::raw("<d>one/<p>ending")),
             }
             spans.push(Span::raw(" │ <q>uit"));
             Line::from(spans)
@@ -234,14 +254,21 @@ impl<'a> UiState<'a> {
             .exercises()
             .iter()
             .enumerate()
-            .filter_map(|(ind, exercise)| match self.filter {
-                Filter::Done => exercise.done.then_some(ind),
-                Filter::Pending => (!exercise.done).then_some(ind),
-                Filter::None => Some(ind),
-            })
+            .filter_map(|(ind, exercise)| self.filter.matches(exercise).then_some(ind))
             .nth(selected)
             .context("Invalid selection index")?;
 
+        if self.unconfirmed_reset != Some(ind) && self.app_state.is_exercise_modified(ind)? {
+            self.unconfirmed_reset = Some(ind);
+            write!(
+                self.message,
+                "The exercise {} has changes which will be lost │ Press r again to reset it",
+                self.app_state.exercises()[ind].path,
+            )?;
+            return Ok(self);
+        }
+
+        self.unconfirmed_reset = None;
         let exercise_path = self.app_state.reset_exercise_by_ind(ind)?;
         write!(self.message, "The exercise {exercise_path} has been reset")?;
 
@@ -258,11 +285,7 @@ impl<'a> UiState<'a> {
             .exercises()
             .iter()
             .enumerate()
-            .filter_map(|(ind, exercise)| match self.filter {
-                Filter::Done => exercise.done.then_some(ind),
-                Filter::Pending => (!exercise.done).then_some(ind),
-                Filter::None => Some(ind),
-            })
+            .filter_map(|(ind, exercise)| self.filter.matches(exercise).then_some(ind))
             .nth(selected)
             .context("Invalid selection index")?;
 
@@ -0,0 +1,108 @@
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+use crate::{color::Stylize, exercise::EXERCISE_TYPES};
+
+// The tool is installed if it can be spawned. Its exit code doesn't matter.
+fn tool_installed(tool: &str) -> bool {
+    Command::new(tool)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok()
+}
+
+fn write_types(writer: &mut impl Write, is_installed: impl Fn(&str) -> bool) -> io::Result<()> {
+    for exercise_type in EXERCISE_TYPES {
+        write!(
+            writer,
+            "{:<10} .{:<8}",
+            exercise_type.name, exercise_type.ext
+        )?;
+
+        if !exercise_type.enabled {
+            writeln!(
+                writer,
+                "{}",
+                exercise_type.disabled_err().to_string().yellow()
+            )?;
+            continue;
+        }
+
+        if exercise_type.tools.is_empty() {
+            writer.write_all(b"no external tools required")?;
+        }
+
+        for (ind, &tool) in exercise_type.tools.iter().enumerate() {
+            if ind > 0 {
+                writer.write_all(b", ")?;
+            }
+
+            if is_installed(tool) {
+                write!(writer, "{tool} {}", "✓".green())?;
+            } else {
+                write!(writer, "{tool} {}", "✗ (not installed)".red())?;
+            }
+        }
+
+        writer.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
+
+/// Print all supported exercise types with their extension and required tools.
+pub fn list_types() -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    write_types(&mut stdout, tool_installed)?;
+    stdout.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_types_listed() {
+        let mut output = Vec::new();
+        write_types(&mut output, |tool| tool == "cargo").unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert_eq!(output.lines().count(), EXERCISE_TYPES.len());
+        for (line, exercise_type) in output.lines().zip(EXERCISE_TYPES) {
+            assert!(line.starts_with(exercise_type.name));
+            assert!(line.contains(&format!(".{}", exercise_type.ext)));
+        }
+
+        // The types dispatched in `RunnableExercise::run_exercise`.
+        for ext in ["rs", "circom", "nr", "md"] {
+            assert!(EXERCISE_TYPES
+                .iter()
+                .any(|exercise_type| exercise_type.ext == ext));
+        }
+
+        let circom_line = output
+            .lines()
+            .find(|line| line.starts_with("circom"))
+            .unwrap();
+        if cfg!(feature = "circom") {
+            assert!(circom_line.contains("not installed"));
+        } else {
+            assert!(circom_line.contains("Rebuild with the Cargo feature `circom`"));
+        }
+
+        let noir_line = output
+            .lines()
+            .find(|line| line.starts_with("noir"))
+            .unwrap();
+        if cfg!(feature = "noir") {
+            assert!(noir_line.contains("not installed"));
+        } else {
+            assert!(noir_line.contains("Rebuild with the Cargo feature `noir`"));
+        }
+    }
+}
@@ -1,26 +1,64 @@
 use anyhow::{bail, Context, Result};
 use app_state::StateFileStatus;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
 use std::{
-    io::{self, BufRead, IsTerminal, StdoutLock, Write},
-    path::Path,
+    env,
+    io::{self, BufRead, IsTerminal, Write},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
     process::exit,
 };
 
-use self::{app_state::AppState, dev::DevCommands, info_file::InfoFile, watch::WatchExit};
+#[cfg(feature = "circom")]
+use self::circom::structure::StructureFormat;
+use self::{
+    app_state::AppState,
+    dev::DevCommands,
+    grading::Grading,
+    info_file::InfoFile,
+    snapshot::SnapshotMode,
+    verify::ReportFormat,
+    watch::{WatchClear, WatchExit},
+};
 
+mod answer_syntax;
 mod app_state;
 mod cargo_toml;
+mod choices;
+#[cfg(feature = "circom")]
+mod circom;
+mod clippy_fix;
 mod cmd;
+mod color;
+mod comparator;
+mod completion;
+mod criteria;
 mod dev;
+mod doctor;
+mod edit;
+mod editor;
 mod embedded;
 mod exercise;
+mod field;
+mod fixtures;
+mod grading;
 mod info_file;
 mod init;
+mod input_spec;
 mod list;
+mod list_types;
+#[cfg(feature = "noir")]
+mod noir;
+mod package;
+mod plan;
+mod pool;
 mod progress_bar;
 mod run;
+mod snapshot;
+mod source_patterns;
 mod terminal_link;
+mod verify;
 mod watch;
 
 const CURRENT_FORMAT_VERSION: u8 = 1;
@@ -41,7 +79,7 @@ fn in_official_repo() -> bool {
     Path::new("dev/zklings-repo.txt").exists()
 }
 
-fn clear_terminal(stdout: &mut StdoutLock) -> io::Result<()> {
+fn clear_terminal(stdout: &mut impl Write) -> io::Result<()> {
     stdout.write_all(b"\x1b[H\x1b[2J\x1b[3J")
 }
 
@@ -60,6 +98,34 @@ struct Args {
     /// Only use this if Rustlings fails to detect exercise file changes.
     #[arg(long)]
     manual_run: bool,
+    /// Use the course in this directory instead of the current directory.
+    /// All paths like the `exercises/` directory and the state file are relative to it
+    #[arg(long, global = true)]
+    project: Option<PathBuf>,
+    /// Set to `off` to keep the output of previous runs in the watch mode instead of clearing the
+    /// terminal. Overrides `watch_clear` in `info.toml`
+    #[arg(long, value_enum)]
+    watch_clear: Option<WatchClear>,
+    /// Show the hint of an exercise after it failed without requesting it.
+    /// In an assessment, only hints which were already shown are shown again
+    #[arg(long, global = true)]
+    hint_on_failure: bool,
+    /// Write the output as plain text without colors and styles, e.g. for logs.
+    /// Also disabled if the environment variable `NO_COLOR` is set
+    #[arg(long, global = true)]
+    no_color: bool,
+    /// Show the output of the commands which run an exercise while they run instead of only
+    /// when they are done
+    #[arg(long, global = true)]
+    stream_output: bool,
+    /// The maximum number of bytes of the output of each command which are kept.
+    /// The rest is discarded, e.g. of an exercise which prints in an endless loop [default: 1 MiB]
+    #[arg(long, global = true, value_name = "BYTES")]
+    max_output: Option<NonZeroUsize>,
+    /// Always compile Circom circuits instead of reusing the compiled files of unchanged circuits
+    #[cfg(feature = "circom")]
+    #[arg(long, global = true)]
+    force_compile: bool,
 }
 
 #[derive(Subcommand)]
@@ -70,17 +136,122 @@ enum Subcommands {
     Run {
         /// The name of the exercise
         name: Option<String>,
+        /// Only run the tests of a Rust exercise whose names contain this filter
+        #[arg(long)]
+        test: Option<String>,
     },
     /// Reset a single exercise
     Reset {
         /// The name of the exercise
         name: String,
+        /// Reset the exercise without asking even if its changes are lost
+        #[arg(short, long)]
+        force: bool,
     },
     /// Show a hint. Shows the hint of the next pending exercise if the exercise name is not specified
     Hint {
         /// The name of the exercise
         name: Option<String>,
     },
+    /// Open the interactive exercise list
+    List {
+        /// Only show the exercises skipped in the watch mode
+        #[arg(long)]
+        skipped: bool,
+    },
+    /// Run all exercises non-interactively and report the results without changing their state
+    Verify {
+        /// The format of the report
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+        /// Grade markdown exercises with the answers in this TOML file instead of the exercise files.
+        /// It maps exercise names to an answer or a list of attempts
+        #[arg(long)]
+        answers: Option<PathBuf>,
+        /// Fail a markdown exercise if none of its first attempts in the answers file is correct
+        #[arg(long, requires = "answers")]
+        max_attempts: Option<NonZeroUsize>,
+        /// Write the normalized output of every exercise to its golden file in `snapshots/`
+        #[arg(long, conflicts_with = "check_snapshots")]
+        snapshot: bool,
+        /// Fail exercises whose normalized output differs from their golden file in `snapshots/`
+        #[arg(long)]
+        check_snapshots: bool,
+        /// Run the exercises on a pool of workers with their own target directories
+        #[arg(long)]
+        parallel: bool,
+        /// The number of workers. Defaults to the available parallelism
+        #[arg(short, long, requires = "parallel")]
+        jobs: Option<NonZeroUsize>,
+    },
+    /// Run only the exercises which failed in the last run of `verify` or `retry-failed`
+    RetryFailed {
+        /// The format of the report
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+        /// Grade markdown exercises with the answers in this TOML file instead of the exercise files
+        #[arg(long)]
+        answers: Option<PathBuf>,
+        /// Fail a markdown exercise if none of its first attempts in the answers file is correct
+        #[arg(long, requires = "answers")]
+        max_attempts: Option<NonZeroUsize>,
+    },
+    /// Run a single exercise non-interactively, print the result and exit with a code reflecting
+    /// it. Never prompts and doesn't change the progress. For CI and scripts
+    RunOnce {
+        /// The name of the exercise
+        name: String,
+        /// Grade a markdown exercise with the answers in this TOML file instead of the exercise file
+        #[arg(long)]
+        answers: Option<PathBuf>,
+        /// Fail a markdown exercise if none of its first attempts in the answers file is correct
+        #[arg(long, requires = "answers")]
+        max_attempts: Option<NonZeroUsize>,
+        /// Grade a markdown exercise with the answer piped to stdin instead of the exercise file
+        #[arg(long, conflicts_with = "answers")]
+        answer_stdin: bool,
+    },
+    /// Print the commands that running an exercise executes without executing them.
+    /// Uses the next pending exercise if the exercise name is not specified
+    Plan {
+        /// The name of the exercise
+        name: Option<String>,
+    },
+    /// Print the components and signals of a Circom circuit as an approximate tree or DOT graph.
+    /// Uses the next pending exercise if the exercise name is not specified
+    #[cfg(feature = "circom")]
+    Structure {
+        /// The name of the exercise
+        name: Option<String>,
+        /// The format of the diagram
+        #[arg(long, value_enum, default_value_t = StructureFormat::Tree)]
+        format: StructureFormat,
+    },
+    /// Bundle an exercise with its solution, referenced files and the versions of the required
+    /// tools into a tarball which reproduces it standalone, e.g. for bug reports
+    Package {
+        /// The name of the exercise
+        name: String,
+        /// The path of the tarball. Defaults to `NAME.tar.gz`
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Open the current exercise in the editor of the environment variable `EDITOR` (or `VISUAL`)
+    /// at its first TODO marker
+    Edit,
+    /// Answer requests of editor integrations as JSON lines over stdin and stdout
+    Editor,
+    /// List the supported exercise types and check if their required tools are installed
+    ListTypes,
+    /// Check the fixture files listed in `fixtures.toml` against their SHA-256 checksums
+    VerifyFixtures,
+    /// Check if the tools of the exercise types are installed and if the fixtures are intact
+    Doctor,
+    /// Print a shell completion script including the names of the exercises
+    Completion {
+        /// The shell to generate the completion script for
+        shell: Shell,
+    },
     /// Commands for developing (third-party) Rustlings exercises
     #[command(subcommand)]
     Dev(DevCommands),
@@ -88,6 +259,27 @@ enum Subcommands {
 
 fn main() -> Result<()> {
     let args = Args::parse();
+    color::init(args.no_color);
+    if args.stream_output {
+        cmd::stream_output();
+    }
+    if let Some(max_output) = args.max_output {
+        cmd::set_max_output_len(max_output.get());
+    }
+
+    if let Some(project) = &args.project {
+        env::set_current_dir(project).with_context(|| {
+            format!(
+                "Failed to change the current directory to the project {}",
+                project.display(),
+            )
+        })?;
+    }
+
+    #[cfg(feature = "circom")]
+    if args.force_compile {
+        circom::build_cache::force_compile();
+    }
 
     if !DEBUG_PROFILE && in_official_repo() {
         bail!("{OLD_METHOD_ERR}");
@@ -110,6 +302,14 @@ fn main() -> Result<()> {
             return init::init().context("Initialization failed");
         }
         Some(Subcommands::Dev(dev_command)) => return dev_command.run(),
+        Some(Subcommands::ListTypes) => {
+            return list_types::list_types().context("Failed to list the exercise types")
+        }
+        Some(Subcommands::VerifyFixtures) => return fixtures::verify_fixtures(),
+        Some(Subcommands::Doctor) => return doctor::doctor(),
+        Some(Subcommands::Completion { shell }) => {
+            return completion::completion(Args::command(), shell)
+        }
         _ => (),
     }
 
@@ -127,10 +327,23 @@ fn main() -> Result<()> {
     let (mut app_state, state_file_status) = AppState::new(
         info_file.exercises,
         info_file.final_message.unwrap_or_default(),
+        info_file.hint_budget,
     )?;
 
     // Show the welcome message if the state file doesn't exist yet.
-    if let Some(welcome_message) = info_file.welcome_message {
+    // Editors can't answer the prompt and would receive the message instead of a response.
+    // Non-interactive commands must never wait for input.
+    let non_interactive = matches!(
+        args.command,
+        Some(
+            Subcommands::Editor
+                | Subcommands::RunOnce { .. }
+                | Subcommands::Package { .. }
+                | Subcommands::Verify { .. }
+                | Subcommands::RetryFailed { .. }
+        ),
+    );
+    if let Some(welcome_message) = info_file.welcome_message.filter(|_| !non_interactive) {
         match state_file_status {
             StateFileStatus::NotRead => {
                 let mut stdout = io::stdout().lock();
@@ -167,35 +380,152 @@ fn main() -> Result<()> {
                 )
             };
 
+            let watch_clear = args
+                .watch_clear
+                .or(info_file.watch_clear)
+                .unwrap_or_default();
+
             loop {
-                match watch::watch(&mut app_state, notify_exercise_names)? {
+                match watch::watch(
+                    &mut app_state,
+                    notify_exercise_names,
+                    watch_clear,
+                    args.hint_on_failure,
+                )? {
                     WatchExit::Shutdown => break,
                     // It is much easier to exit the watch mode, launch the list mode and then restart
                     // the watch mode instead of trying to pause the watch threads and correct the
                     // watch state.
-                    WatchExit::List => list::list(&mut app_state)?,
+                    WatchExit::List => list::list(&mut app_state, false)?,
                 }
             }
         }
-        Some(Subcommands::Run { name }) => {
+        Some(Subcommands::Run { name, test }) => {
             if let Some(name) = name {
                 app_state.set_current_exercise_by_name(&name)?;
             }
-            run::run(&mut app_state)?;
+            run::run(&mut app_state, args.hint_on_failure, test.as_deref())?;
         }
-        Some(Subcommands::Reset { name }) => {
+        Some(Subcommands::Reset { name, force }) => {
             app_state.set_current_exercise_by_name(&name)?;
+
+            let ind = app_state.current_exercise_ind();
+            if !force && app_state.is_exercise_modified(ind)? {
+                let path = app_state.current_exercise().path;
+                if !io::stdin().is_terminal() {
+                    bail!("The exercise {path} has changes which would be lost. Run `zklings reset --force {name}` to reset it anyway");
+                }
+
+                print!(
+                    "The exercise {path} has changes which will be lost. Reset it anyway? [y/N] "
+                );
+                io::stdout().flush()?;
+                let mut answer = String::new();
+                io::stdin().lock().read_line(&mut answer)?;
+                if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+                    println!("The exercise {path} wasn't reset");
+                    return Ok(());
+                }
+            }
+
             let exercise_path = app_state.reset_current_exercise()?;
             println!("The exercise {exercise_path} has been reset");
         }
+        Some(Subcommands::Verify {
+            format,
+            answers,
+            max_attempts,
+            snapshot,
+            check_snapshots,
+            parallel,
+            jobs,
+        }) => {
+            let grading = answers
+                .map(|answers| Grading::new(&answers, max_attempts))
+                .transpose()?;
+            let snapshots = if snapshot {
+                Some(SnapshotMode::Write)
+            } else if check_snapshots {
+                Some(SnapshotMode::Check)
+            } else {
+                None
+            };
+            verify::verify(
+                &app_state,
+                format,
+                grading.as_ref(),
+                snapshots,
+                pool::n_workers(parallel, jobs),
+            )?;
+        }
+        Some(Subcommands::RetryFailed {
+            format,
+            answers,
+            max_attempts,
+        }) => {
+            let grading = answers
+                .map(|answers| Grading::new(&answers, max_attempts))
+                .transpose()?;
+            verify::retry_failed(&app_state, format, grading.as_ref())?;
+        }
+        Some(Subcommands::RunOnce {
+            name,
+            answers,
+            max_attempts,
+            answer_stdin,
+        }) => {
+            let grading = if answer_stdin {
+                Some(Grading::read_answer(&name, io::stdin().lock())?)
+            } else {
+                answers
+                    .map(|answers| Grading::new(&answers, max_attempts))
+                    .transpose()?
+            };
+            verify::run_once(&app_state, &name, grading.as_ref())?;
+        }
+        Some(Subcommands::Package { name, output }) => package::package(&name, output)?,
+        Some(Subcommands::Edit) => edit::edit(app_state.current_exercise())?,
+        Some(Subcommands::Editor) => editor::serve(&mut app_state)?,
+        Some(Subcommands::Plan { name }) => {
+            let exercise = match name {
+                Some(name) => &app_state.exercises()[app_state.exercise_ind_by_name(&name)?],
+                None => app_state.current_exercise(),
+            };
+            plan::plan(exercise, app_state.target_dir())?;
+        }
+        #[cfg(feature = "circom")]
+        Some(Subcommands::Structure { name, format }) => {
+            let exercise = match name {
+                Some(name) => &app_state.exercises()[app_state.exercise_ind_by_name(&name)?],
+                None => app_state.current_exercise(),
+            };
+            print!("{}", circom::circuit_structure(exercise, format)?);
+        }
+        Some(Subcommands::List { skipped }) => {
+            if !io::stdout().is_terminal() {
+                bail!("Unsupported or missing terminal/TTY");
+            }
+
+            list::list(&mut app_state, skipped)?;
+        }
         Some(Subcommands::Hint { name }) => {
             if let Some(name) = name {
                 app_state.set_current_exercise_by_name(&name)?;
             }
-            println!("{}", app_state.current_exercise().hint);
+            match app_state.reveal_hint(app_state.current_exercise_ind())? {
+                Some(hint) => println!("{hint}"),
+                None => println!("{}", app_state.hint_budget_exhausted_msg()),
+            }
         }
         // Handled in an earlier match.
-        Some(Subcommands::Init | Subcommands::Dev(_)) => (),
+        Some(
+            Subcommands::Init
+            | Subcommands::Dev(_)
+            | Subcommands::ListTypes
+            | Subcommands::VerifyFixtures
+            | Subcommands::Doctor
+            | Subcommands::Completion { .. },
+        ) => (),
     }
 
     Ok(())
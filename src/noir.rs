@@ -0,0 +1,166 @@
+// Noir exercises are single `.nr` files. `nargo` only builds packages. Therefore, a package with
+// the exercise file as `src/main.nr` is generated in the target directory before running `nargo`.
+
+use anyhow::{Context, Result};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    cmd::{NargoCmd, PlannedCmd},
+    color::Stylize,
+    criteria::Measurements,
+};
+
+// The inputs of `main`. `NAME.Prover.toml` is preferred over `Prover.toml` to allow multiple
+// exercises in one directory.
+fn prover_toml(source_path: &Path, name: &str) -> Option<PathBuf> {
+    [format!("{name}.Prover.toml"), String::from("Prover.toml")]
+        .into_iter()
+        .map(|file_name| source_path.with_file_name(file_name))
+        .find(|path| path.is_file())
+}
+
+fn package_dir(package_name: &str, target_dir: &Path) -> PathBuf {
+    target_dir.join("noir").join(package_name)
+}
+
+// Generate the package `package_name` which runs the Noir file at `source_path`.
+fn write_package(
+    package_name: &str,
+    name: &str,
+    source_path: &str,
+    target_dir: &Path,
+) -> Result<PathBuf> {
+    let package_dir = package_dir(package_name, target_dir);
+    let src_dir = package_dir.join("src");
+    fs::create_dir_all(&src_dir)
+        .with_context(|| format!("Failed to create the directory {}", src_dir.display()))?;
+
+    let nargo_toml = format!(
+        "[package]\nname = \"{package_name}\"\ntype = \"bin\"\nauthors = [\"\"]\n\n[dependencies]\n"
+    );
+    fs::write(package_dir.join("Nargo.toml"), nargo_toml)
+        .context("Failed to write the `Nargo.toml` file of the Noir package")?;
+
+    fs::copy(source_path, src_dir.join("main.nr"))
+        .with_context(|| format!("Failed to copy the Noir file {source_path}"))?;
+
+    // `nargo execute` requires the file even if `main` has no parameters.
+    let prover_toml_path = package_dir.join("Prover.toml");
+    match prover_toml(Path::new(source_path), name) {
+        Some(path) => {
+            fs::copy(&path, &prover_toml_path)
+                .with_context(|| format!("Failed to copy the file {}", path.display()))?;
+        }
+        None => fs::write(&prover_toml_path, "")
+            .context("Failed to write the `Prover.toml` file of the Noir package")?,
+    }
+
+    Ok(package_dir)
+}
+
+/// The commands of `run_noir` in this order, without executing them.
+pub fn plan_noir(package_name: &str, target_dir: &Path) -> Vec<PlannedCmd> {
+    let package_dir = package_dir(package_name, target_dir);
+    // Only used when running the commands.
+    let mut output = Vec::new();
+
+    [
+        ("compile", "nargo compile …"),
+        ("execute", "nargo execute …"),
+    ]
+    .into_iter()
+    .map(|(subcommand, description)| PlannedCmd {
+        description: description.to_string(),
+        cmd: NargoCmd {
+            subcommand,
+            description,
+            output: &mut output,
+            package_dir: &package_dir,
+        }
+        .command(),
+    })
+    .collect()
+}
+
+/// Compile the Noir file at `source_path` of the exercise `name` and execute it with the inputs
+/// of its `Prover.toml`. The output is written to the `output` buffer after clearing it.
+pub fn run_noir(
+    package_name: &str,
+    name: &str,
+    source_path: &str,
+    output: &mut Vec<u8>,
+    target_dir: &Path,
+    measurements: &mut Measurements,
+) -> Result<bool> {
+    output.clear();
+
+    let package_dir = write_package(package_name, name, source_path, target_dir)?;
+
+    writeln!(output, "{}", "Compiling Noir circuit...".underlined())?;
+    let compiled = NargoCmd {
+        subcommand: "compile",
+        description: "nargo compile …",
+        output,
+        package_dir: &package_dir,
+    }
+    .run()?;
+    measurements.stages.compiled = Some(compiled);
+    if !compiled {
+        return Ok(false);
+    }
+
+    writeln!(output, "{}", "Executing Noir circuit...".underlined())?;
+    let executed = NargoCmd {
+        subcommand: "execute",
+        description: "nargo execute …",
+        output,
+        package_dir: &package_dir,
+    }
+    .run()?;
+    measurements.stages.ran = Some(executed);
+
+    Ok(executed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn package_with_specific_prover_toml() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        let exercises_dir = dir.join("exercises");
+        fs::create_dir_all(&exercises_dir).unwrap();
+        let source_path = exercises_dir.join("noir1.nr");
+        fs::write(&source_path, "fn main(x: Field) { assert(x == 1); }\n").unwrap();
+        fs::write(exercises_dir.join("Prover.toml"), "x = \"2\"\n").unwrap();
+
+        let target_dir = dir.join("target");
+        let source_path = source_path.to_str().unwrap();
+        let package_dir = write_package("noir1", "noir1", source_path, &target_dir).unwrap();
+        assert_eq!(package_dir, target_dir.join("noir/noir1"));
+        assert_eq!(
+            fs::read_to_string(package_dir.join("src/main.nr")).unwrap(),
+            "fn main(x: Field) { assert(x == 1); }\n",
+        );
+        assert!(fs::read_to_string(package_dir.join("Nargo.toml"))
+            .unwrap()
+            .contains("name = \"noir1\"\ntype = \"bin\""));
+        assert_eq!(
+            fs::read_to_string(package_dir.join("Prover.toml")).unwrap(),
+            "x = \"2\"\n",
+        );
+
+        fs::write(exercises_dir.join("noir1.Prover.toml"), "x = \"1\"\n").unwrap();
+        write_package("noir1", "noir1", source_path, &target_dir).unwrap();
+        assert_eq!(
+            fs::read_to_string(package_dir.join("Prover.toml")).unwrap(),
+            "x = \"1\"\n",
+        );
+    }
+}
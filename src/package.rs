@@ -0,0 +1,271 @@
+use anyhow::{bail, Context, Result};
+use std::{
+    env,
+    fmt::Write as _,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process::{self, Command, Stdio},
+};
+
+use crate::{
+    cargo_toml::updated_cargo_toml,
+    embedded::EMBEDDED_FILES,
+    exercise::EXERCISE_TYPES,
+    fixtures::sha256_file,
+    info_file::{ExerciseInfo, InfoFile},
+};
+
+const VERSIONS_FILE_NAME: &str = "VERSIONS.txt";
+const CHECKSUMS_FILE_NAME: &str = "SHA256SUMS";
+// Files next to a circuit which are read when running it.
+// Either `NAME.FILE` or `FILE` like in `circom::circuit_file`.
+const CIRCUIT_FILES: [&str; 4] = ["input.json", "proof.json", "public.json", "expected.json"];
+
+// Files of the project which the exercise needs, relative to the project root.
+fn referenced_files(exercise_info: &ExerciseInfo) -> Result<Vec<String>> {
+    let mut required = vec![exercise_info.path()];
+    required.extend(exercise_info.input_files.iter().cloned());
+    required.extend(exercise_info.ptau.clone());
+
+    let mut optional = vec![exercise_info.sol_path()];
+    if let Some(dir) = &exercise_info.dir {
+        optional.push(format!("exercises/{dir}/README.md"));
+    }
+    if let Some(rust_verifier) = &exercise_info.rust_verifier {
+        for path in [exercise_info.path(), exercise_info.sol_path()] {
+            let path = Path::new(&path).with_file_name(format!("{rust_verifier}.rs"));
+            optional.push(path.to_string_lossy().into_owned());
+        }
+    }
+    if exercise_info.ext == "circom" {
+        let path = exercise_info.path();
+        let circuit_dir = Path::new(&path).parent().unwrap_or(Path::new(""));
+        for file_name in CIRCUIT_FILES {
+            for file_name in [
+                format!("{}.{file_name}", exercise_info.name),
+                file_name.into(),
+            ] {
+                optional.push(circuit_dir.join(file_name).to_string_lossy().into_owned());
+            }
+        }
+    }
+
+    for path in &required {
+        if !Path::new(path).is_file() {
+            bail!(
+                "The file `{path}` of the exercise `{}` doesn't exist",
+                exercise_info.name,
+            );
+        }
+    }
+
+    let mut files = required;
+    for path in optional {
+        if Path::new(&path).is_file() && !files.contains(&path) {
+            files.push(path);
+        }
+    }
+
+    Ok(files)
+}
+
+// The section of the exercise in `info.toml` without the header `[[exercises]]`.
+fn info_toml_entry<'a>(info_toml: &'a str, name: &str) -> Option<&'a str> {
+    let name_line = format!("name = \"{name}\"");
+
+    let mut entry = info_toml
+        .split("\n[[exercises]]\n")
+        .skip(1)
+        .find(|entry| entry.lines().any(|line| line.trim() == name_line))?
+        .trim_end();
+
+    // Comments at the end belong to the next exercise.
+    while let Some((rest, last_line)) = entry.rsplit_once('\n') {
+        if !last_line.trim_start().starts_with('#') {
+            break;
+        }
+        entry = rest.trim_end();
+    }
+
+    Some(entry)
+}
+
+fn package_info_toml(info_file: &InfoFile, name: &str) -> Result<String> {
+    let info_toml = match fs::read_to_string("info.toml") {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => EMBEDDED_FILES.info_file.to_string(),
+        Err(e) => return Err(e).context("Failed to read the `info.toml` file"),
+    };
+    let entry = info_toml_entry(&info_toml, name)
+        .with_context(|| format!("Failed to find the exercise `{name}` in `info.toml`"))?;
+
+    Ok(format!(
+        "format_version = {}\n\n[[exercises]]\n{entry}\n",
+        info_file.format_version,
+    ))
+}
+
+// The first line of `TOOL --version`.
+fn tool_version(tool: &str) -> String {
+    let output = Command::new(tool)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output();
+
+    match output {
+        Ok(output) => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+        Err(_) => String::from("not installed"),
+    }
+}
+
+fn versions(ext: &str) -> String {
+    let mut versions = format!("zklings {}\n", env!("CARGO_PKG_VERSION"));
+
+    let tools = EXERCISE_TYPES
+        .iter()
+        .filter(|exercise_type| exercise_type.ext == ext)
+        .flat_map(|exercise_type| exercise_type.tools);
+    for tool in tools {
+        // Writing to a string can't fail.
+        let _ = writeln!(versions, "{tool}: {}", tool_version(tool));
+    }
+
+    versions
+}
+
+fn write_staged(dir: &Path, path: &str, content: &[u8]) -> Result<()> {
+    let file_path = dir.join(path);
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create the directory {}", parent.display()))?;
+    }
+
+    fs::write(&file_path, content)
+        .with_context(|| format!("Failed to write the file {}", file_path.display()))
+}
+
+// Copy the files of the exercise into the directory `dir` with the generated files and return
+// the paths of all files relative to it.
+fn stage(info_file: &InfoFile, exercise_info: &ExerciseInfo, dir: &Path) -> Result<Vec<String>> {
+    let mut files = referenced_files(exercise_info)?;
+    for path in &files {
+        let content = fs::read(path).with_context(|| format!("Failed to read the file {path}"))?;
+        write_staged(dir, path, &content)?;
+    }
+
+    let info_toml = package_info_toml(info_file, &exercise_info.name)?;
+    write_staged(dir, "info.toml", info_toml.as_bytes())?;
+    files.push(String::from("info.toml"));
+
+    // Only the bin of the exercise (and its solution) is kept.
+    // The dependencies are needed for building it.
+    match fs::read_to_string("Cargo.toml") {
+        Ok(cargo_toml) => {
+            let cargo_toml =
+                updated_cargo_toml(std::slice::from_ref(exercise_info), &cargo_toml, b"")
+                    .context("Failed to generate the `Cargo.toml` file of the package")?;
+            write_staged(dir, "Cargo.toml", &cargo_toml)?;
+            files.push(String::from("Cargo.toml"));
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => (),
+        Err(e) => return Err(e).context("Failed to read the `Cargo.toml` file"),
+    }
+
+    write_staged(
+        dir,
+        VERSIONS_FILE_NAME,
+        versions(&exercise_info.ext).as_bytes(),
+    )?;
+    files.push(String::from(VERSIONS_FILE_NAME));
+
+    // In the format of `sha256sum` to be checked with `sha256sum -c SHA256SUMS`.
+    let mut checksums = String::with_capacity(files.len() * 128);
+    for path in &files {
+        let checksum = sha256_file(&dir.join(path))
+            .with_context(|| format!("Failed to compute the checksum of {path}"))?;
+        let _ = writeln!(checksums, "{checksum}  {path}");
+    }
+    write_staged(dir, CHECKSUMS_FILE_NAME, checksums.as_bytes())?;
+    files.push(String::from(CHECKSUMS_FILE_NAME));
+
+    Ok(files)
+}
+
+/// Bundle the exercise `name` with its solution, `info.toml` entry, referenced files, the
+/// versions of the required tools and a checksum manifest into a tarball which reproduces the
+/// exercise standalone.
+pub fn package(name: &str, output: Option<PathBuf>) -> Result<()> {
+    let info_file = InfoFile::parse()?;
+    let Some(exercise_info) = info_file.exercises.iter().find(|info| info.name == name) else {
+        bail!("No exercise found for '{name}'!");
+    };
+
+    let output = output.unwrap_or_else(|| PathBuf::from(format!("{name}.tar.gz")));
+    let staging_dir = env::temp_dir().join(format!("zklings-package-{}", process::id()));
+    let package_dir = staging_dir.join(name);
+    let files = stage(&info_file, exercise_info, &package_dir);
+
+    let tar_result = files.and_then(|files| {
+        let status = Command::new("tar")
+            .arg("-czf")
+            .arg(&output)
+            .arg("-C")
+            .arg(&staging_dir)
+            .arg(name)
+            .stdin(Stdio::null())
+            .status()
+            .context("Failed to run `tar`")?;
+        if !status.success() {
+            bail!("`tar` failed to create {}", output.display());
+        }
+
+        Ok(files)
+    });
+    // Not cleaning up the staging directory is harmless.
+    let _ = fs::remove_dir_all(&staging_dir);
+    let files = tar_result?;
+
+    println!("Packaged the exercise {name} into {}:", output.display());
+    for path in files {
+        println!("  {name}/{path}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_info_toml_entry() {
+        let info_toml = "format_version = 1
+
+[[exercises]]
+name = \"intro1\"
+hint = \"\"\"\"\"\"
+
+# Comment of the second exercise.
+[[exercises]]
+name = \"intro2\"
+dir = \"intro\"
+hint = \"\"\"name = \"intro1\"\"\"\"
+";
+
+        assert_eq!(
+            info_toml_entry(info_toml, "intro1"),
+            Some("name = \"intro1\"\nhint = \"\"\"\"\"\""),
+        );
+        assert!(info_toml_entry(info_toml, "intro2")
+            .unwrap()
+            .contains("dir = \"intro\""));
+        assert_eq!(info_toml_entry(info_toml, "intro3"), None);
+    }
+}
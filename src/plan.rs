@@ -0,0 +1,58 @@
+use anyhow::{Context, Result};
+use std::{
+    env,
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::{
+    cmd::PlannedCmd,
+    color::Stylize,
+    exercise::{Exercise, RunnableExercise},
+};
+
+fn write_plan(
+    writer: &mut impl Write,
+    exercise: &Exercise,
+    plan: &[PlannedCmd],
+    current_dir: &Path,
+) -> io::Result<()> {
+    writeln!(
+        writer,
+        "Running {exercise} executes the following commands (nothing was executed):\n",
+    )?;
+
+    if plan.is_empty() {
+        writer.write_all(b"No commands. The answer is compared with the solution file.\n")?;
+    }
+
+    for (ind, planned_cmd) in plan.iter().enumerate() {
+        let working_dir = match planned_cmd.cmd.get_current_dir() {
+            Some(dir) => current_dir.join(dir),
+            None => current_dir.to_path_buf(),
+        };
+
+        writeln!(
+            writer,
+            "{}. {}",
+            ind + 1,
+            planned_cmd.description.as_str().bold()
+        )?;
+        writeln!(writer, "   $ {planned_cmd}")?;
+        writeln!(writer, "   in {}", working_dir.display())?;
+    }
+
+    Ok(())
+}
+
+/// Print the commands that running the exercise executes without executing them.
+pub fn plan(exercise: &Exercise, target_dir: &Path) -> Result<()> {
+    let plan = exercise.plan(target_dir)?;
+    let current_dir = env::current_dir().context("Failed to get the current directory")?;
+
+    let mut stdout = io::stdout().lock();
+    write_plan(&mut stdout, exercise, &plan, &current_dir)?;
+    stdout.flush()?;
+
+    Ok(())
+}
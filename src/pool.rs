@@ -0,0 +1,148 @@
+// A pool of workers with their own target directories to run exercises or solutions in parallel.
+// Workers sharing one target directory would wait for Cargo's lock on it.
+
+use anyhow::{Context, Result};
+use std::{
+    fs, io,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+// The directories of the target directory which contain the compiled dependencies.
+const CACHE_DIRS: [&str; 3] = ["deps", "build", ".fingerprint"];
+
+/// Run the job for every item on a pool of one worker per directory.
+/// A worker only passes its own directory to the job. The results are in the order of the items.
+pub fn run_pool<T, R, F>(items: &[T], worker_dirs: &[PathBuf], job: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T, &Path) -> R + Sync,
+{
+    let next_ind = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(items.len()));
+
+    thread::scope(|s| {
+        for worker_dir in worker_dirs {
+            s.spawn(|| loop {
+                let ind = next_ind.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(ind) else {
+                    break;
+                };
+
+                let result = job(item, worker_dir);
+                results.lock().unwrap().push((ind, result));
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_unstable_by_key(|(ind, _)| *ind);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+fn copy_dir(src: &Path, dst: &Path) -> io::Result<()> {
+    fs::create_dir_all(dst)?;
+
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let dst = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &dst)?;
+        } else {
+            fs::copy(entry.path(), dst)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Seed a new worker target directory with the dependencies compiled in the shared one.
+// Cargo then only compiles the exercises again instead of all dependencies.
+fn seed_worker_dir(target_dir: &Path, worker_dir: &Path) -> Result<()> {
+    if worker_dir.exists() {
+        return Ok(());
+    }
+
+    let profile_dir = target_dir.join("debug");
+    for dir_name in CACHE_DIRS {
+        let src = profile_dir.join(dir_name);
+        if !src.is_dir() {
+            continue;
+        }
+
+        let dst = worker_dir.join("debug").join(dir_name);
+        copy_dir(&src, &dst).with_context(|| {
+            format!(
+                "Failed to copy the dependency cache {} to {}",
+                src.display(),
+                dst.display(),
+            )
+        })?;
+    }
+
+    Ok(())
+}
+
+/// The number of workers: `jobs` or the available parallelism with `parallel`, otherwise one.
+pub fn n_workers(parallel: bool, jobs: Option<NonZeroUsize>) -> usize {
+    if !parallel {
+        return 1;
+    }
+
+    jobs.or_else(|| thread::available_parallelism().ok())
+        .map_or(1, NonZeroUsize::get)
+}
+
+/// The target directories of `n_workers` workers in the shared `target_dir`.
+/// New directories are seeded with the dependencies compiled in the shared one and kept for the
+/// next run.
+pub fn worker_dirs(target_dir: &Path, n_workers: usize) -> Result<Vec<PathBuf>> {
+    let workers_dir = target_dir.join("zklings-workers");
+    let worker_dirs = (0..n_workers)
+        .map(|worker_ind| workers_dir.join(worker_ind.to_string()))
+        .collect::<Vec<_>>();
+    for worker_dir in &worker_dirs {
+        seed_worker_dir(target_dir, worker_dir)?;
+    }
+
+    Ok(worker_dirs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Barrier;
+
+    use super::*;
+
+    #[test]
+    fn concurrent_workers_keep_their_artifacts() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        let worker_dirs = [dir.join("0"), dir.join("1")];
+        for worker_dir in &worker_dirs {
+            fs::create_dir_all(worker_dir).unwrap();
+        }
+
+        // Both jobs have to run at the same time to pass the barrier.
+        let barrier = Barrier::new(2);
+        let results = run_pool(&["sol1", "sol2"], &worker_dirs, |name, worker_dir| {
+            let artifact = worker_dir.join("artifact");
+            fs::write(&artifact, name).unwrap();
+            barrier.wait();
+            (
+                worker_dir.to_path_buf(),
+                fs::read_to_string(&artifact).unwrap(),
+            )
+        });
+
+        assert_ne!(results[0].0, results[1].0);
+        assert_eq!(results[0].1, "sol1");
+        assert_eq!(results[1].1, "sol2");
+    }
+}
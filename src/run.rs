@@ -1,23 +1,53 @@
 use anyhow::{bail, Result};
-use crossterm::style::{style, Stylize};
-use std::io::{self, Write};
+use crossterm::style::style;
+use std::io::{self, IsTerminal, Write};
 
 use crate::{
     app_state::{AppState, ExercisesProgress},
-    exercise::{RunnableExercise, OUTPUT_CAPACITY},
+    clippy_fix::offer_clippy_fix,
+    color::Stylize,
+    exercise::{write_hint, RunnableExercise, OUTPUT_CAPACITY},
     terminal_link::TerminalFileLink,
 };
 
-pub fn run(app_state: &mut AppState) -> Result<()> {
+/// With `hint_on_failure`, the hint is shown if the exercise isn't solved.
+/// With `test_filter`, only the tests of a Rust exercise whose names contain it are run.
+/// A filtered run doesn't change the progress because the other tests didn't run.
+pub fn run(
+    app_state: &mut AppState,
+    hint_on_failure: bool,
+    test_filter: Option<&str>,
+) -> Result<()> {
     let exercise = app_state.current_exercise();
     let mut output = Vec::with_capacity(OUTPUT_CAPACITY);
-    let success = exercise.run_exercise(&mut output, app_state.target_dir())?;
+    let outcome =
+        exercise.run_exercise_structured(&mut output, app_state.target_dir(), test_filter)?;
 
     let mut stdout = io::stdout().lock();
     stdout.write_all(&output)?;
 
-    if !success {
-        app_state.set_pending(app_state.current_exercise_ind())?;
+    if !outcome.is_solved() {
+        if outcome.clippy_failed && io::stdin().is_terminal() {
+            drop(stdout);
+            offer_clippy_fix(app_state.current_exercise(), app_state.target_dir())?;
+        }
+
+        let ind = app_state.current_exercise_ind();
+        if test_filter.is_none() {
+            app_state.set_pending(ind)?;
+        }
+        if hint_on_failure {
+            if let Some(hint) = app_state.hint_on_failure(ind)? {
+                write_hint(&mut io::stdout().lock(), hint)?;
+            }
+        }
+
+        if outcome.passed {
+            bail!(
+                "Ran {} successfully but it isn't fully solved yet",
+                app_state.current_exercise().terminal_link(),
+            );
+        }
 
         bail!(
             "Ran {} with errors",
@@ -32,6 +62,14 @@ pub fn run(app_state: &mut AppState) -> Result<()> {
         exercise.path.green(),
     )?;
 
+    if let Some(test_filter) = test_filter {
+        writeln!(
+            stdout,
+            "Only the filtered tests ran (`{test_filter}`). Run all tests to mark the exercise as done",
+        )?;
+        return Ok(());
+    }
+
     if !exercise.is_circom() {
         if let Some(solution_path) = app_state.current_solution_path()? {
             println!(
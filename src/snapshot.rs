@@ -0,0 +1,139 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::{fs, io::ErrorKind};
+
+use crate::verify::strip_ansi;
+
+/// The directory of the golden files with the expected output of the exercises.
+const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// A replacement of volatile output like timestamps, absolute paths or proof bytes which is
+/// applied before the output is compared with its snapshot or written to it.
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+pub struct NormalizeRule {
+    /// Regular expression searched for in the output without colors.
+    pub regex: String,
+    /// The replacement of every match. Can refer to capture groups like `$1`.
+    pub replacement: String,
+}
+
+/// What `verify` does with the output of the exercises.
+#[derive(Clone, Copy)]
+pub enum SnapshotMode {
+    /// `--snapshot`: Write the normalized output to the snapshots.
+    Write,
+    /// `--check-snapshots`: Fail exercises whose normalized output differs from their snapshot.
+    Check,
+}
+
+/// Remove colors from the output and apply the normalization rules in order.
+pub fn normalize(output: &[u8], rules: &[NormalizeRule]) -> Result<String> {
+    let mut normalized = strip_ansi(&String::from_utf8_lossy(output));
+
+    for rule in rules {
+        let regex = Regex::new(&rule.regex)
+            .with_context(|| format!("Invalid regex `{}` of a normalization rule", rule.regex))?;
+        normalized = regex
+            .replace_all(&normalized, rule.replacement.as_str())
+            .into_owned();
+    }
+
+    Ok(normalized)
+}
+
+fn snapshot_path(exercise_name: &str) -> String {
+    format!("{SNAPSHOTS_DIR}/{exercise_name}.txt")
+}
+
+// The number of the first line which differs.
+fn first_difference(snapshot: &str, normalized: &str) -> Option<usize> {
+    let mut snapshot_lines = snapshot.lines();
+    let mut normalized_lines = normalized.lines();
+
+    for line_number in 1.. {
+        match (snapshot_lines.next(), normalized_lines.next()) {
+            (None, None) => return None,
+            (a, b) if a != b => return Some(line_number),
+            _ => (),
+        }
+    }
+
+    None
+}
+
+/// Write the normalized output or compare it with the snapshot of the exercise.
+/// Returns why the output doesn't match the snapshot.
+pub fn process_snapshot(
+    mode: SnapshotMode,
+    exercise_name: &str,
+    normalized: &str,
+) -> Result<Option<String>> {
+    let path = snapshot_path(exercise_name);
+
+    match mode {
+        SnapshotMode::Write => {
+            fs::create_dir_all(SNAPSHOTS_DIR)
+                .with_context(|| format!("Failed to create the directory `{SNAPSHOTS_DIR}`"))?;
+            fs::write(&path, normalized)
+                .with_context(|| format!("Failed to write the snapshot {path}"))?;
+
+            Ok(None)
+        }
+        SnapshotMode::Check => {
+            let snapshot = match fs::read_to_string(&path) {
+                Ok(snapshot) => snapshot,
+                Err(e) if e.kind() == ErrorKind::NotFound => {
+                    return Ok(Some(format!(
+                        "The snapshot {path} is missing. Write it with `--snapshot`",
+                    )));
+                }
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to read the snapshot {path}"))
+                }
+            };
+
+            Ok(first_difference(&snapshot, normalized).map(|line_number| {
+                format!("The output differs from the snapshot {path} in line {line_number}")
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalized_path() {
+        let rules = [NormalizeRule {
+            regex: String::from(r"/\S*/(target/debug/)"),
+            replacement: String::from("<PROJECT>/$1"),
+        }];
+        let output = b"\x1b[1mRunning\x1b[0m `/home/ferris/zklings/target/debug/intro1`\nHello\n";
+
+        let normalized = normalize(output, &rules).unwrap();
+        assert_eq!(
+            normalized,
+            "Running `<PROJECT>/target/debug/intro1`\nHello\n",
+        );
+        assert_eq!(
+            first_difference(
+                "Running `<PROJECT>/target/debug/intro1`\nHello\n",
+                &normalized
+            ),
+            None,
+        );
+        assert_eq!(
+            first_difference("Running `<PROJECT>/target/debug/intro1`\n", &normalized),
+            Some(2),
+        );
+        assert_eq!(
+            first_difference(
+                &String::from_utf8_lossy(output),
+                &normalize(output, &[]).unwrap(),
+            ),
+            Some(1),
+        );
+    }
+}
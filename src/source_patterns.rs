@@ -0,0 +1,280 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::{fs, io::Write};
+
+use crate::color::Stylize;
+
+/// A pattern that an exercise's source file is required to contain or forbidden from containing.
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+pub struct SourcePattern {
+    /// Regular expression searched for in the source file without its comments and the content of
+    /// its string literals.
+    pub regex: String,
+    /// The API shown to the user, e.g. `.map` or `.unwrap()`.
+    pub api: String,
+}
+
+impl SourcePattern {
+    fn is_match(&self, source: &str) -> Result<bool> {
+        let regex = Regex::new(&self.regex).with_context(|| {
+            format!("Invalid regex `{}` for the API `{}`", self.regex, self.api)
+        })?;
+
+        Ok(regex.is_match(source))
+    }
+}
+
+#[inline]
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// Remove the comments and the content of string and character literals which must neither
+// satisfy required patterns nor violate forbidden ones. Nested block comments and raw strings of
+// Rust are supported. Newlines in block comments are kept.
+fn strip_comments_and_strings(source: &str) -> String {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut stripped = String::with_capacity(source.len());
+    let mut ind = 0;
+
+    while ind < chars.len() {
+        let c = chars[ind];
+        let next = chars.get(ind + 1).copied();
+
+        match (c, next) {
+            ('/', Some('/')) => {
+                while ind < chars.len() && chars[ind] != '\n' {
+                    ind += 1;
+                }
+            }
+            ('/', Some('*')) => {
+                let mut depth = 0_usize;
+                while ind < chars.len() {
+                    match (chars[ind], chars.get(ind + 1).copied()) {
+                        ('/', Some('*')) => {
+                            depth += 1;
+                            ind += 2;
+                        }
+                        ('*', Some('/')) => {
+                            depth -= 1;
+                            ind += 2;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        ('\n', _) => {
+                            stripped.push('\n');
+                            ind += 1;
+                        }
+                        _ => ind += 1,
+                    }
+                }
+                stripped.push(' ');
+            }
+            ('"', _) => {
+                ind += 1;
+                while ind < chars.len() && chars[ind] != '"' {
+                    // Skip the escaped character.
+                    ind += if chars[ind] == '\\' { 2 } else { 1 };
+                }
+                ind += 1;
+                stripped.push_str("\"\"");
+            }
+            // A raw string like `r#"…"#`, also as byte string `br"…"`.
+            ('r', Some('"' | '#'))
+                if !stripped
+                    .strip_suffix('b')
+                    .unwrap_or(&stripped)
+                    .ends_with(is_ident_char) =>
+            {
+                let n_hashes = chars[ind + 1..].iter().take_while(|&&c| c == '#').count();
+                if chars.get(ind + 1 + n_hashes) != Some(&'"') {
+                    // A raw identifier like `r#match`.
+                    stripped.push(c);
+                    ind += 1;
+                    continue;
+                }
+
+                ind += n_hashes + 2;
+                while ind < chars.len() {
+                    if chars[ind] == '"'
+                        && chars[ind + 1..]
+                            .iter()
+                            .take(n_hashes)
+                            .filter(|&&c| c == '#')
+                            .count()
+                            == n_hashes
+                    {
+                        ind += n_hashes + 1;
+                        break;
+                    }
+                    ind += 1;
+                }
+                stripped.push_str("r\"\"");
+            }
+            // A character literal like `'"'` or `'\''` in contrast to a lifetime like `'a`.
+            ('\'', Some('\\')) => {
+                ind += 3;
+                while ind < chars.len() && chars[ind] != '\'' {
+                    ind += 1;
+                }
+                ind += 1;
+                stripped.push_str("''");
+            }
+            ('\'', Some(_)) if chars.get(ind + 2) == Some(&'\'') => {
+                ind += 3;
+                stripped.push_str("''");
+            }
+            _ => {
+                stripped.push(c);
+                ind += 1;
+            }
+        }
+    }
+
+    stripped
+}
+
+// Check the source against the patterns and write a message for every violation to `output`.
+fn check_source(
+    source: &str,
+    required: &[SourcePattern],
+    forbidden: &[SourcePattern],
+    output: &mut Vec<u8>,
+) -> Result<bool> {
+    let source = &strip_comments_and_strings(source);
+    let mut success = true;
+
+    for pattern in required {
+        if !pattern.is_match(source)? {
+            success = false;
+            writeln!(
+                output,
+                "{}",
+                format!("This exercise requires you to use `{}`", pattern.api).red(),
+            )?;
+        }
+    }
+
+    for pattern in forbidden {
+        if pattern.is_match(source)? {
+            success = false;
+            writeln!(
+                output,
+                "{}",
+                format!("Avoid `{}` in this exercise", pattern.api).red(),
+            )?;
+        }
+    }
+
+    Ok(success)
+}
+
+/// Check the source file at `path` before building it.
+/// Returns `false` and writes the violations to `output` if a pattern isn't satisfied.
+pub fn check_source_file(
+    path: &str,
+    required: &[SourcePattern],
+    forbidden: &[SourcePattern],
+    output: &mut Vec<u8>,
+) -> Result<bool> {
+    if required.is_empty() && forbidden.is_empty() {
+        return Ok(true);
+    }
+
+    let source =
+        fs::read_to_string(path).with_context(|| format!("Failed to read the file {path}"))?;
+
+    check_source(&source, required, forbidden, output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(regex: &str, api: &str) -> SourcePattern {
+        SourcePattern {
+            regex: regex.to_owned(),
+            api: api.to_owned(),
+        }
+    }
+
+    const SOURCE: &str = "fn main() {
+    let v: Vec<u32> = \"1 2\".split(' ').map(|n| n.parse().unwrap()).collect();
+}";
+
+    #[test]
+    fn required_pattern() {
+        let mut output = Vec::new();
+        let map = [pattern(r"\.map\(", ".map")];
+        assert!(check_source(SOURCE, &map, &[], &mut output).unwrap());
+        assert!(output.is_empty());
+
+        let fold = [pattern(r"\.fold\(", ".fold")];
+        assert!(!check_source(SOURCE, &fold, &[], &mut output).unwrap());
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("This exercise requires you to use `.fold`"));
+    }
+
+    #[test]
+    fn forbidden_pattern() {
+        let mut output = Vec::new();
+        let expect = [pattern(r"\.expect\(", ".expect()")];
+        assert!(check_source(SOURCE, &[], &expect, &mut output).unwrap());
+        assert!(output.is_empty());
+
+        let unwrap = [pattern(r"\.unwrap\(\)", ".unwrap()")];
+        assert!(!check_source(SOURCE, &[], &unwrap, &mut output).unwrap());
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Avoid `.unwrap()` in this exercise"));
+    }
+
+    #[test]
+    fn forbidden_pattern_in_comment() {
+        let source = "fn main() {
+    // Don't call `.unwrap()` here.
+    /* Neither `.unwrap()` /* nested */ nor .unwrap() */
+    let message = \"Never .unwrap()\";
+    let quote = '\"';
+    let raw = r#\"\" .unwrap() \"#;
+    println!(\"{message}{quote}{raw}\");
+}";
+        let unwrap = [pattern(r"\.unwrap\(\)", ".unwrap()")];
+        let mut output = Vec::new();
+        assert!(check_source(source, &[], &unwrap, &mut output).unwrap());
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn required_pattern_in_comment() {
+        let source = "fn main() {
+    // TODO: Use .map(|n| n * 2)
+    let message = \"It's done with .map(|n| n * 2)\";
+    println!(\"{message}\");
+}";
+        let map = [pattern(r"\.map\(", ".map")];
+        let mut output = Vec::new();
+        assert!(!check_source(source, &map, &[], &mut output).unwrap());
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("This exercise requires you to use `.map`"));
+    }
+
+    #[test]
+    fn strip_keeps_code() {
+        assert_eq!(
+            strip_comments_and_strings("fn f<'a>(s: &'a str) -> char { let _ = r#x; '\\'' } // x"),
+            "fn f<'a>(s: &'a str) -> char { let _ = r#x; '' } ",
+        );
+        assert_eq!(
+            strip_comments_and_strings("a /* 1\n2 */ b\"c\\\"d\" e"),
+            "a \n  b\"\" e",
+        );
+    }
+
+    #[test]
+    fn invalid_regex() {
+        let invalid = [pattern(r"\.map(", ".map")];
+        assert!(check_source(SOURCE, &invalid, &[], &mut Vec::new()).is_err());
+    }
+}
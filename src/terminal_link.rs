@@ -3,10 +3,17 @@ use std::{
     fs,
 };
 
+use crate::color;
+
 pub struct TerminalFileLink<'a>(pub &'a str);
 
 impl<'a> Display for TerminalFileLink<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // The hyperlink is an escape sequence.
+        if !color::enabled() {
+            return write!(f, "{}", self.0);
+        }
+
         let path = fs::canonicalize(self.0);
 
         if let Some(path) = path.as_deref().ok().and_then(|path| path.to_str()) {
@@ -0,0 +1,662 @@
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{self, ErrorKind, StdoutLock, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    app_state::AppState,
+    color::Stylize,
+    criteria::{Measurements, Outcome, SolvedCriteria},
+    exercise::{Exercise, RunnableExercise, EXERCISE_TYPES, OUTPUT_CAPACITY},
+    grading::Grading,
+    pool,
+    snapshot::{self, SnapshotMode},
+};
+
+/// The names of the exercises which failed in the last run of `verify` or `retry-failed`.
+const FAILED_FILE_NAME: &str = ".zklings-failed.txt";
+
+/// The format of the report of `verify`.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ReportFormat {
+    /// One line per exercise while running them
+    Text,
+    /// JUnit XML for CI dashboards, written after running all exercises
+    Junit,
+    /// A JSON array with the result of every exercise, written after running all exercises
+    Json,
+}
+
+struct ExerciseResult<'a> {
+    exercise: &'a Exercise,
+    success: bool,
+    duration: Duration,
+    // The captured output. Also contains the error if running the exercise failed.
+    output: Vec<u8>,
+    // Why a graded exercise failed, e.g. because its attempts were exhausted.
+    failure_reason: Option<String>,
+    // Graded exercises and exercises which couldn't be run have no measurements.
+    outcome: Outcome,
+}
+
+fn run_exercise<'a>(
+    exercise: &'a Exercise,
+    target_dir: &Path,
+    grading: Option<&Grading>,
+) -> ExerciseResult<'a> {
+    let mut output = Vec::with_capacity(OUTPUT_CAPACITY);
+    let mut failure_reason = None;
+    let mut outcome = None;
+
+    let start = Instant::now();
+    let result = match grading {
+        // Markdown exercises are graded with the submitted answers instead of the exercise files.
+        Some(grading) if exercise.is_md() => {
+            grading
+                .grade(exercise, &mut output)
+                .map(|reason| match reason {
+                    Some(reason) => {
+                        output.extend_from_slice(format!("{reason}\n").as_bytes());
+                        failure_reason = Some(reason);
+                        false
+                    }
+                    None => true,
+                })
+        }
+        _ => exercise
+            .run_exercise_structured(&mut output, target_dir, None)
+            .map(|structured| {
+                let solved = structured.is_solved();
+                outcome = Some(structured);
+                solved
+            }),
+    };
+    let success = match result {
+        Ok(success) => success,
+        Err(e) => {
+            // The error belongs to the report of this exercise instead of aborting all others.
+            output.extend_from_slice(format!("\n{e:?}\n").as_bytes());
+            false
+        }
+    };
+
+    ExerciseResult {
+        exercise,
+        success,
+        duration: start.elapsed(),
+        output,
+        failure_reason,
+        outcome: outcome.unwrap_or_else(|| {
+            Outcome::new(success, SolvedCriteria::default(), Measurements::default())
+        }),
+    }
+}
+
+// Remove ANSI escape sequences like colors from the captured output.
+pub fn strip_ansi(output: &str) -> String {
+    let mut stripped = String::with_capacity(output.len());
+    let mut chars = output.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+
+        // Control Sequence Introducer: ESC [ parameters final byte (@ to ~)
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+
+    stripped
+}
+
+// Escape text for XML 1.0 attributes and elements.
+// Characters which aren't allowed in XML 1.0 at all are dropped.
+fn escape_xml(text: &str, escaped: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' || c == '\u{FFFE}' || c == '\u{FFFF}' => (),
+            c => escaped.push(c),
+        }
+    }
+}
+
+fn junit_report(results: &[ExerciseResult]) -> String {
+    let n_failures = results.iter().filter(|result| !result.success).count();
+    let total_time = results
+        .iter()
+        .map(|result| result.duration)
+        .sum::<Duration>()
+        .as_secs_f64();
+
+    let mut xml = String::with_capacity(OUTPUT_CAPACITY);
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    // Writing to a `String` can't fail.
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"zklings\" tests=\"{}\" failures=\"{n_failures}\" time=\"{total_time:.3}\">",
+        results.len(),
+    );
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"exercises\" tests=\"{}\" failures=\"{n_failures}\" errors=\"0\" time=\"{total_time:.3}\">",
+        results.len(),
+    );
+
+    for result in results {
+        xml.push_str("    <testcase name=\"");
+        escape_xml(result.exercise.name, &mut xml);
+        xml.push_str("\" classname=\"");
+        escape_xml(result.exercise.path, &mut xml);
+        let _ = write!(xml, "\" time=\"{:.3}\"", result.duration.as_secs_f64());
+
+        if result.success {
+            xml.push_str("/>\n");
+            continue;
+        }
+
+        xml.push_str(">\n      <failure message=\"");
+        escape_xml(
+            result
+                .failure_reason
+                .as_deref()
+                .unwrap_or("The exercise failed"),
+            &mut xml,
+        );
+        xml.push_str("\">");
+        escape_xml(
+            &strip_ansi(&String::from_utf8_lossy(&result.output)),
+            &mut xml,
+        );
+        xml.push_str("</failure>\n    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// The result of an exercise in the JSON report.
+#[derive(Serialize)]
+struct JsonResult<'a> {
+    name: &'a str,
+    /// The exercise type, e.g. `rust`.
+    kind: &'a str,
+    path: &'a str,
+    /// The stages, measurements and diagnostics.
+    #[serde(flatten)]
+    outcome: &'a Outcome,
+    success: bool,
+    /// In seconds.
+    duration: f64,
+    failure_reason: Option<&'a str>,
+    /// The output without colors.
+    output: String,
+}
+
+fn json_report(results: &[ExerciseResult]) -> Result<String> {
+    let results = results
+        .iter()
+        .map(|result| JsonResult {
+            name: result.exercise.name,
+            kind: EXERCISE_TYPES
+                .iter()
+                .find(|exercise_type| exercise_type.ext == result.exercise.ext)
+                .map_or(result.exercise.ext, |exercise_type| exercise_type.name),
+            path: result.exercise.path,
+            outcome: &result.outcome,
+            success: result.success,
+            duration: result.duration.as_secs_f64(),
+            failure_reason: result.failure_reason.as_deref(),
+            output: strip_ansi(&String::from_utf8_lossy(&result.output)),
+        })
+        .collect::<Vec<_>>();
+
+    let mut json =
+        serde_json::to_string_pretty(&results).context("Failed to serialize the JSON report")?;
+    json.push('\n');
+
+    Ok(json)
+}
+
+/// Run a single exercise non-interactively without changing its state and print its output and
+/// result. With `grading`, a markdown exercise is graded with the submitted answers.
+/// Fails if the exercise failed.
+pub fn run_once(app_state: &AppState, name: &str, grading: Option<&Grading>) -> Result<()> {
+    let exercise = &app_state.exercises()[app_state.exercise_ind_by_name(name)?];
+    let result = run_exercise(exercise, app_state.target_dir(), grading);
+
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(&result.output)?;
+    if result.success {
+        writeln!(stdout, "{} {exercise}", "ok".green())?;
+    } else {
+        writeln!(stdout, "{} {exercise}", "FAILED".red())?;
+    }
+    stdout.flush()?;
+
+    if !result.success {
+        bail!("The exercise {exercise} failed");
+    }
+
+    Ok(())
+}
+
+// Write the normalized output of the exercise to its snapshot or fail the exercise if it differs.
+fn process_snapshot(result: &mut ExerciseResult, mode: SnapshotMode) -> Result<()> {
+    let normalized = snapshot::normalize(&result.output, &result.exercise.normalize)?;
+
+    if let Some(reason) = snapshot::process_snapshot(mode, result.exercise.name, &normalized)? {
+        result
+            .output
+            .extend_from_slice(format!("{}\n", reason.as_str().red()).as_bytes());
+        result.success = false;
+        result.failure_reason.get_or_insert(reason);
+    }
+
+    Ok(())
+}
+
+// One name per line.
+fn failed_file_content(results: &[ExerciseResult]) -> String {
+    let mut content = String::with_capacity(results.len() * 32);
+    for result in results.iter().filter(|result| !result.success) {
+        content.push_str(result.exercise.name);
+        content.push('\n');
+    }
+
+    content
+}
+
+fn parse_failed_file(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+}
+
+fn write_text_result(stdout: &mut StdoutLock, result: &ExerciseResult) -> io::Result<()> {
+    if result.success {
+        writeln!(stdout, "{}", "ok".green())
+    } else {
+        writeln!(stdout, "{}", "FAILED".red())?;
+        stdout.write_all(&result.output)
+    }
+}
+
+// The links to the failed exercises to jump straight to them.
+fn write_failed_summary(writer: &mut impl Write, results: &[ExerciseResult]) -> io::Result<()> {
+    let mut failed = results.iter().filter(|result| !result.success).peekable();
+    if failed.peek().is_none() {
+        return Ok(());
+    }
+
+    writeln!(writer, "\n{}", "Failed exercises:".bold().red())?;
+    for result in failed {
+        writeln!(writer, "  {}", result.exercise.terminal_link())?;
+    }
+
+    Ok(())
+}
+
+// With more than one worker, the results are only printed in order after all exercises ran.
+fn verify_exercises<'a>(
+    app_state: &AppState,
+    exercises: impl ExactSizeIterator<Item = &'a Exercise>,
+    format: ReportFormat,
+    grading: Option<&Grading>,
+    snapshots: Option<SnapshotMode>,
+    n_workers: usize,
+) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    let text = matches!(format, ReportFormat::Text);
+    let mut results = Vec::with_capacity(exercises.len());
+
+    if n_workers == 1 {
+        for exercise in exercises {
+            if text {
+                write!(stdout, "Running {exercise} ... ")?;
+                stdout.flush()?;
+            }
+
+            let mut result = run_exercise(exercise, app_state.target_dir(), grading);
+            if let Some(mode) = snapshots {
+                process_snapshot(&mut result, mode)?;
+            }
+
+            if text {
+                write_text_result(&mut stdout, &result)?;
+            }
+
+            results.push(result);
+        }
+    } else {
+        let exercises = exercises.collect::<Vec<_>>();
+        if text {
+            writeln!(
+                stdout,
+                "Running {} exercises with {n_workers} workers. This may take a while…\n",
+                exercises.len(),
+            )?;
+            stdout.flush()?;
+        }
+
+        // The first exercise compiles the dependencies in the shared target directory.
+        if let Some((first, rest)) = exercises.split_first() {
+            results.push(run_exercise(first, app_state.target_dir(), grading));
+
+            let worker_dirs = pool::worker_dirs(app_state.target_dir(), n_workers)?;
+            results.extend(pool::run_pool(
+                rest,
+                &worker_dirs,
+                |exercise, worker_dir| run_exercise(*exercise, worker_dir, grading),
+            ));
+        }
+
+        for result in &mut results {
+            if let Some(mode) = snapshots {
+                process_snapshot(result, mode)?;
+            }
+
+            if text {
+                write!(stdout, "Running {} ... ", result.exercise)?;
+                write_text_result(&mut stdout, result)?;
+            }
+        }
+    }
+
+    match format {
+        ReportFormat::Text => write_failed_summary(&mut stdout, &results)?,
+        ReportFormat::Junit => stdout.write_all(junit_report(&results).as_bytes())?,
+        ReportFormat::Json => stdout.write_all(json_report(&results)?.as_bytes())?,
+    }
+    stdout.flush()?;
+
+    fs::write(FAILED_FILE_NAME, failed_file_content(&results))
+        .with_context(|| format!("Failed to write the file {FAILED_FILE_NAME}"))?;
+
+    let n_failures = results.iter().filter(|result| !result.success).count();
+    if n_failures > 0 {
+        bail!("{n_failures} of {} exercises failed", results.len());
+    }
+
+    Ok(())
+}
+
+/// Run all exercises without changing their state and report the results in the given format.
+/// With `grading`, markdown exercises are graded with the submitted answers.
+/// With `snapshots`, the normalized output is written to or compared with the golden files.
+/// With more than one worker, the exercises are run in parallel, see `pool`.
+/// The failed exercises are stored for `retry_failed`.
+/// Fails if at least one exercise failed.
+pub fn verify(
+    app_state: &AppState,
+    format: ReportFormat,
+    grading: Option<&Grading>,
+    snapshots: Option<SnapshotMode>,
+    n_workers: usize,
+) -> Result<()> {
+    verify_exercises(
+        app_state,
+        app_state.exercises().iter(),
+        format,
+        grading,
+        snapshots,
+        n_workers,
+    )
+}
+
+/// Run only the exercises which failed in the last run of `verify` or `retry_failed` and store
+/// which of them still fail. Fails if at least one exercise still fails.
+pub fn retry_failed(
+    app_state: &AppState,
+    format: ReportFormat,
+    grading: Option<&Grading>,
+) -> Result<()> {
+    let content = match fs::read_to_string(FAILED_FILE_NAME) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            bail!("There are no results of a previous run. Run `zklings verify` first");
+        }
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read the file {FAILED_FILE_NAME}"))
+        }
+    };
+
+    // Exercises which were removed since the last run are ignored.
+    let failed_names = parse_failed_file(&content).collect::<hashbrown::HashSet<_>>();
+    let failed = app_state
+        .exercises()
+        .iter()
+        .filter(|exercise| failed_names.contains(exercise.name))
+        .collect::<Vec<_>>();
+    if failed.is_empty() {
+        println!("No exercise failed in the last run");
+        return Ok(());
+    }
+
+    verify_exercises(app_state, failed.into_iter(), format, grading, None, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::criteria::Stages;
+
+    fn exercise(name: &'static str, path: &'static str) -> Exercise {
+        Exercise::new(name, "rs", path)
+    }
+
+    fn outcome(passed: bool) -> Outcome {
+        Outcome::new(passed, SolvedCriteria::default(), Measurements::default())
+    }
+
+    #[test]
+    fn junit_pass_and_fail() {
+        let passing = exercise("intro1", "exercises/intro1.rs");
+        let failing = exercise("poseidon<2>", "exercises/poseidon<2>.rs");
+        let results = [
+            ExerciseResult {
+                exercise: &passing,
+                success: true,
+                duration: Duration::from_millis(1500),
+                output: b"ok".to_vec(),
+                failure_reason: None,
+                outcome: outcome(true),
+            },
+            ExerciseResult {
+                exercise: &failing,
+                success: false,
+                duration: Duration::from_millis(20),
+                output: "\x1b[31merror\x1b[0m: expected `&` & found \"<\"\x07".into(),
+                failure_reason: None,
+                outcome: outcome(false),
+            },
+        ];
+
+        let xml = junit_report(&results);
+        let document = roxmltree::Document::parse(&xml).unwrap();
+
+        let testsuites = document.root_element();
+        assert_eq!(testsuites.tag_name().name(), "testsuites");
+        assert_eq!(testsuites.attribute("tests"), Some("2"));
+        assert_eq!(testsuites.attribute("failures"), Some("1"));
+
+        let testcases = testsuites
+            .descendants()
+            .filter(|node| node.has_tag_name("testcase"))
+            .collect::<Vec<_>>();
+        assert_eq!(testcases.len(), 2);
+
+        assert_eq!(testcases[0].attribute("name"), Some("intro1"));
+        assert_eq!(testcases[0].attribute("time"), Some("1.500"));
+        assert!(testcases[0].first_element_child().is_none());
+
+        assert_eq!(testcases[1].attribute("name"), Some("poseidon<2>"));
+        assert_eq!(
+            testcases[1].attribute("classname"),
+            Some("exercises/poseidon<2>.rs"),
+        );
+        let failure = testcases[1].first_element_child().unwrap();
+        assert_eq!(failure.tag_name().name(), "failure");
+        assert_eq!(failure.text(), Some("error: expected `&` & found \"<\""));
+    }
+
+    #[test]
+    fn junit_failure_reason() {
+        let exhausted = exercise("01_basic_modulo", "exercises/math/01_basic_modulo.md");
+        let results = [ExerciseResult {
+            exercise: &exhausted,
+            success: false,
+            duration: Duration::from_millis(1),
+            output: b"No correct answer within the maximum of 2 attempts\n".to_vec(),
+            failure_reason: Some(String::from(
+                "No correct answer within the maximum of 2 attempts",
+            )),
+            outcome: outcome(false),
+        }];
+
+        let xml = junit_report(&results);
+        let document = roxmltree::Document::parse(&xml).unwrap();
+        let failure = document
+            .descendants()
+            .find(|node| node.has_tag_name("failure"))
+            .unwrap();
+        assert_eq!(
+            failure.attribute("message"),
+            Some("No correct answer within the maximum of 2 attempts"),
+        );
+    }
+
+    #[test]
+    fn failed_summary() {
+        let passing = exercise("intro1", "exercises/intro1.rs");
+        let failing = exercise("intro2", "exercises/intro2.rs");
+        let result = |exercise, success| ExerciseResult {
+            exercise,
+            success,
+            duration: Duration::ZERO,
+            output: Vec::new(),
+            failure_reason: None,
+            outcome: outcome(success),
+        };
+
+        let mut summary = Vec::new();
+        write_failed_summary(&mut summary, &[result(&passing, true)]).unwrap();
+        assert!(summary.is_empty());
+
+        write_failed_summary(
+            &mut summary,
+            &[result(&passing, true), result(&failing, false)],
+        )
+        .unwrap();
+        let summary = strip_ansi(&String::from_utf8(summary).unwrap());
+        assert!(summary.contains("Failed exercises:"));
+        assert!(summary.contains("exercises/intro2.rs"));
+        assert!(!summary.contains("exercises/intro1.rs"));
+    }
+
+    #[test]
+    fn json_stages() {
+        let clippy_failure = exercise("intro2", "exercises/intro2.rs");
+        let results = [ExerciseResult {
+            exercise: &clippy_failure,
+            success: false,
+            duration: Duration::from_millis(250),
+            output: b"\x1b[33mwarning\x1b[0m: denied".to_vec(),
+            failure_reason: None,
+            outcome: Outcome::new(
+                false,
+                SolvedCriteria::default(),
+                Measurements {
+                    stages: Stages {
+                        compiled: Some(true),
+                        clippy_passed: Some(false),
+                        tests_passed: None,
+                        ran: None,
+                    },
+                    ..Default::default()
+                },
+            ),
+        }];
+
+        let json = json_report(&results).unwrap();
+        let report = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+        assert_eq!(
+            report,
+            serde_json::json!([{
+                "name": "intro2",
+                "kind": "rust",
+                "path": "exercises/intro2.rs",
+                "passed": false,
+                "diagnostics": [],
+                "constraints": null,
+                "verify_time": null,
+                "warnings": null,
+                "compiled": true,
+                "clippy_passed": false,
+                "tests_passed": null,
+                "ran": null,
+                "success": false,
+                "duration": 0.25,
+                "failure_reason": null,
+                "output": "warning: denied",
+            }]),
+        );
+    }
+
+    #[test]
+    fn json_measurements_and_diagnostics() {
+        let over_budget = Exercise::new("multiplier", "circom", "exercises/multiplier.circom");
+        let criteria = SolvedCriteria {
+            max_constraints: Some(1),
+            ..Default::default()
+        };
+        let results = [ExerciseResult {
+            exercise: &over_budget,
+            success: false,
+            duration: Duration::ZERO,
+            output: Vec::new(),
+            failure_reason: None,
+            outcome: Outcome::new(
+                true,
+                criteria,
+                Measurements {
+                    constraints: Some(3),
+                    verify_time: Some(Duration::from_millis(500)),
+                    ..Default::default()
+                },
+            ),
+        }];
+
+        let json = json_report(&results).unwrap();
+        let report = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+        let result = &report[0];
+        assert_eq!(result["kind"], "circom");
+        assert_eq!(result["passed"], true);
+        assert_eq!(result["success"], false);
+        assert_eq!(result["constraints"], 3);
+        assert_eq!(result["verify_time"], 0.5);
+        assert_eq!(
+            result["diagnostics"],
+            serde_json::json!(["The circuit has 3 constraints but the budget is 1"]),
+        );
+    }
+}
@@ -1,17 +1,22 @@
 use anyhow::{Error, Result};
+use clap::ValueEnum;
 use notify_debouncer_mini::{
     new_debouncer,
     notify::{self, RecursiveMode},
 };
+use serde::Deserialize;
 use std::{
     io::{self, Write},
-    path::Path,
+    path::{Path, PathBuf},
     sync::mpsc::channel,
     thread,
     time::Duration,
 };
 
-use crate::app_state::{AppState, ExercisesProgress};
+use crate::{
+    app_state::{AppState, ExercisesProgress},
+    exercise::RunnableExercise,
+};
 
 use self::{
     notify_event::NotifyEventHandler,
@@ -31,6 +36,17 @@ enum WatchEvent {
     TerminalEventErr(io::Error),
 }
 
+/// Whether the watch mode clears the terminal before rendering.
+#[derive(ValueEnum, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchClear {
+    /// Clear the terminal to only show the output of the last run.
+    #[default]
+    On,
+    /// Keep the scrollback and separate the output of each run from the previous one.
+    Off,
+}
+
 /// Returned by the watch mode to indicate what to do afterwards.
 #[must_use]
 pub enum WatchExit {
@@ -44,6 +60,8 @@ pub enum WatchExit {
 pub fn watch(
     app_state: &mut AppState,
     notify_exercise_names: Option<&'static [&'static [u8]]>,
+    clear: WatchClear,
+    hint_on_failure: bool,
 ) -> Result<WatchExit> {
     let (tx, rx) = channel();
 
@@ -51,11 +69,27 @@ pub fn watch(
     // Prevent dropping the guard until the end of the function.
     // Otherwise, the file watcher exits.
     let _debouncer_guard = if let Some(exercise_names) = notify_exercise_names {
+        let input_files = app_state
+            .exercises()
+            .iter()
+            .enumerate()
+            .flat_map(|(exercise_ind, exercise)| {
+                exercise
+                    .input_files
+                    .iter()
+                    .map(PathBuf::from)
+                    // The Rust verifier of a Circom exercise is part of the exercise.
+                    .chain(exercise.rust_verifier_path())
+                    .map(move |input_file| (input_file, exercise_ind))
+            })
+            .collect();
+
         let mut debouncer = new_debouncer(
             Duration::from_millis(200),
             NotifyEventHandler {
                 tx: tx.clone(),
                 exercise_names,
+                input_files,
             },
         )
         .inspect_err(|_| eprintln!("{NOTIFY_ERR}"))?;
@@ -70,7 +104,7 @@ pub fn watch(
         None
     };
 
-    let mut watch_state = WatchState::new(app_state, manual_run);
+    let mut watch_state = WatchState::new(app_state, manual_run, clear, hint_on_failure);
 
     watch_state.run_current_exercise()?;
 
@@ -83,6 +117,11 @@ pub fn watch(
                 ExercisesProgress::CurrentPending => watch_state.render()?,
                 ExercisesProgress::NewPending => watch_state.run_current_exercise()?,
             },
+            WatchEvent::Input(InputEvent::Skip) => match watch_state.skip_exercise()? {
+                ExercisesProgress::AllDone => break,
+                ExercisesProgress::CurrentPending => watch_state.render()?,
+                ExercisesProgress::NewPending => watch_state.run_current_exercise()?,
+            },
             WatchEvent::Input(InputEvent::Hint) => {
                 watch_state.show_hint()?;
             }
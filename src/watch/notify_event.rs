@@ -1,5 +1,5 @@
 use notify_debouncer_mini::{DebounceEventResult, DebouncedEventKind};
-use std::sync::mpsc::Sender;
+use std::{path::PathBuf, sync::mpsc::Sender};
 
 use super::WatchEvent;
 
@@ -7,6 +7,8 @@ pub struct NotifyEventHandler {
     pub tx: Sender<WatchEvent>,
     /// Used to report which exercise was modified.
     pub exercise_names: &'static [&'static [u8]],
+    /// The input files of exercises with the index of their exercise.
+    pub input_files: Vec<(PathBuf, usize)>,
 }
 
 impl notify_debouncer_mini::DebounceEventHandler for NotifyEventHandler {
@@ -20,6 +22,14 @@ impl notify_debouncer_mini::DebounceEventHandler for NotifyEventHandler {
                             return None;
                         }
 
+                        if let Some((_, exercise_ind)) = self
+                            .input_files
+                            .iter()
+                            .find(|(input_file, _)| input_event.path.ends_with(input_file))
+                        {
+                            return Some(*exercise_ind);
+                        }
+
                         let file_name = input_event.path.file_name()?.to_str()?.as_bytes();
 
                         if file_name.len() < 4 {
@@ -1,18 +1,18 @@
 use anyhow::Result;
-use crossterm::{
-    style::{style, Stylize},
-    terminal,
-};
+use crossterm::{style::style, terminal};
 use std::io::{self, StdoutLock, Write};
 
 use crate::{
     app_state::{AppState, ExercisesProgress},
     clear_terminal,
-    exercise::{RunnableExercise, OUTPUT_CAPACITY},
+    color::Stylize,
+    exercise::{write_hint, RunnableExercise, OUTPUT_CAPACITY},
     progress_bar::progress_bar,
     terminal_link::TerminalFileLink,
 };
 
+use super::WatchClear;
+
 #[derive(PartialEq, Eq)]
 enum DoneStatus {
     DoneWithSolution(String),
@@ -25,12 +25,59 @@ pub struct WatchState<'a> {
     app_state: &'a mut AppState,
     output: Vec<u8>,
     show_hint: bool,
+    // The hint budget of the assessment was exhausted when requesting the hint.
+    hint_refused: bool,
     done_status: DoneStatus,
     manual_run: bool,
+    clear: WatchClear,
+    // Show the hint after a failed run without requesting it.
+    hint_on_failure: bool,
+    // The number of runs of the current exercise shown in the separators without clearing.
+    n_runs: usize,
+    rendered: bool,
+}
+
+// Clear the terminal before rendering or separate the render from the previous one which is kept
+// without clearing.
+fn start_render<W: Write>(
+    writer: &mut W,
+    clear: WatchClear,
+    first_render: bool,
+    n_runs: usize,
+    line_width: u16,
+) -> io::Result<()> {
+    match clear {
+        WatchClear::On => {
+            // Prevent having the first line shifted if clearing wasn't successful.
+            writer.write_all(b"\n")?;
+
+            clear_terminal(writer)
+        }
+        // Nothing to separate from.
+        WatchClear::Off if first_render => Ok(()),
+        WatchClear::Off => write_separator(writer, n_runs, line_width),
+    }
+}
+
+fn write_separator<W: Write>(writer: &mut W, n_runs: usize, line_width: u16) -> io::Result<()> {
+    let label = format!(" Run {n_runs} ");
+    let width = usize::from(line_width).saturating_sub(label.len()).max(4);
+    let left = width / 2;
+
+    writeln!(
+        writer,
+        "\n{}",
+        format!("{}{label}{}", "─".repeat(left), "─".repeat(width - left)).dim(),
+    )
 }
 
 impl<'a> WatchState<'a> {
-    pub fn new(app_state: &'a mut AppState, manual_run: bool) -> Self {
+    pub fn new(
+        app_state: &'a mut AppState,
+        manual_run: bool,
+        clear: WatchClear,
+        hint_on_failure: bool,
+    ) -> Self {
         let writer = io::stdout().lock();
 
         Self {
@@ -38,8 +85,13 @@ impl<'a> WatchState<'a> {
             app_state,
             output: Vec::with_capacity(OUTPUT_CAPACITY),
             show_hint: false,
+            hint_refused: false,
             done_status: DoneStatus::Pending,
             manual_run,
+            clear,
+            hint_on_failure,
+            n_runs: 0,
+            rendered: false,
         }
     }
 
@@ -50,11 +102,14 @@ impl<'a> WatchState<'a> {
 
     pub fn run_current_exercise(&mut self) -> Result<()> {
         self.show_hint = false;
+        self.hint_refused = false;
+        self.n_runs += 1;
 
-        let success = self
-            .app_state
-            .current_exercise()
-            .run_exercise(&mut self.output, self.app_state.target_dir())?;
+        let success = self.app_state.current_exercise().run_exercise(
+            &mut self.output,
+            self.app_state.target_dir(),
+            None,
+        )?;
         if success {
             self.done_status =
                 if let Some(solution_path) = self.app_state.current_solution_path()? {
@@ -63,8 +118,11 @@ impl<'a> WatchState<'a> {
                     DoneStatus::DoneWithoutSolution
                 };
         } else {
-            self.app_state
-                .set_pending(self.app_state.current_exercise_ind())?;
+            let ind = self.app_state.current_exercise_ind();
+            self.app_state.set_pending(ind)?;
+            if self.hint_on_failure {
+                self.show_hint = self.app_state.hint_on_failure(ind)?.is_some();
+            }
 
             self.done_status = DoneStatus::Pending;
         }
@@ -80,6 +138,9 @@ impl<'a> WatchState<'a> {
             return Ok(());
         }
 
+        if self.app_state.current_exercise_ind() != exercise_ind {
+            self.n_runs = 0;
+        }
         self.app_state.set_current_exercise_ind(exercise_ind)?;
         self.run_current_exercise()
     }
@@ -93,6 +154,16 @@ impl<'a> WatchState<'a> {
         self.app_state.done_current_exercise(&mut self.writer)
     }
 
+    /// Move on to the next pending exercise without marking the current one as done.
+    /// A done exercise isn't skipped but moved on from like with `next_exercise`.
+    pub fn skip_exercise(&mut self) -> Result<ExercisesProgress> {
+        if self.done_status != DoneStatus::Pending {
+            return self.app_state.done_current_exercise(&mut self.writer);
+        }
+
+        self.app_state.skip_current_exercise()
+    }
+
     fn show_prompt(&mut self) -> io::Result<()> {
         self.writer.write_all(b"\n")?;
 
@@ -100,7 +171,9 @@ impl<'a> WatchState<'a> {
             write!(self.writer, "{}:run / ", 'r'.bold())?;
         }
 
-        if self.done_status != DoneStatus::Pending {
+        if self.done_status == DoneStatus::Pending {
+            write!(self.writer, "{}:skip / ", 's'.bold())?;
+        } else {
             write!(self.writer, "{}:{} / ", 'n'.bold(), "next".underlined())?;
         }
 
@@ -114,20 +187,27 @@ impl<'a> WatchState<'a> {
     }
 
     pub fn render(&mut self) -> Result<()> {
-        // Prevent having the first line shifted if clearing wasn't successful.
-        self.writer.write_all(b"\n")?;
+        let line_width = terminal::size()?.0;
 
-        clear_terminal(&mut self.writer)?;
+        start_render(
+            &mut self.writer,
+            self.clear,
+            !self.rendered,
+            self.n_runs,
+            line_width,
+        )?;
+        self.rendered = true;
 
         self.writer.write_all(&self.output)?;
         self.writer.write_all(b"\n")?;
 
         if self.show_hint {
+            write_hint(&mut self.writer, &self.app_state.current_exercise().hint)?;
+        } else if self.hint_refused {
             writeln!(
                 self.writer,
-                "{}\n{}\n",
-                "Hint".bold().cyan().underlined(),
-                self.app_state.current_exercise().hint,
+                "{}\n",
+                self.app_state.hint_budget_exhausted_msg().yellow(),
             )?;
         }
 
@@ -150,7 +230,6 @@ When you are done experimenting, enter `n` to move on to the next exercise 👽"
             )?;
         }
 
-        let line_width = terminal::size()?.0;
         let progress_bar = progress_bar(
             self.app_state.n_done(),
             self.app_state.exercises().len() as u16,
@@ -169,7 +248,37 @@ When you are done experimenting, enter `n` to move on to the next exercise 👽"
     }
 
     pub fn show_hint(&mut self) -> Result<()> {
-        self.show_hint = true;
+        let ind = self.app_state.current_exercise_ind();
+        self.show_hint = self.app_state.reveal_hint(ind)?.is_some();
+        self.hint_refused = !self.show_hint;
         self.render()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render_start(clear: WatchClear, first_render: bool, n_runs: usize, width: u16) -> String {
+        let mut output = Vec::new();
+        start_render(&mut output, clear, first_render, n_runs, width).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn separator_without_clearing() {
+        assert_eq!(render_start(WatchClear::Off, true, 1, 20), "");
+
+        let separator = render_start(WatchClear::Off, false, 3, 20);
+        assert!(separator.starts_with('\n'));
+        assert!(separator.contains("────── Run 3 ───────"));
+        assert!(!separator.contains("\x1b[2J"));
+
+        // Narrow terminals still get a visible separator.
+        assert!(render_start(WatchClear::Off, false, 12, 5).contains("── Run 12 ──"));
+
+        let cleared = render_start(WatchClear::On, false, 3, 20);
+        assert!(cleared.contains("\x1b[2J"));
+        assert!(!cleared.contains("Run 3"));
+    }
+}
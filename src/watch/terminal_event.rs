@@ -6,6 +6,7 @@ use super::WatchEvent;
 pub enum InputEvent {
     Run,
     Next,
+    Skip,
     Hint,
     List,
     Quit,
@@ -50,6 +51,7 @@ pub fn terminal_event_handler(tx: Sender<WatchEvent>, manual_run: bool) {
                     KeyCode::Char(c) => {
                         let input_event = match c {
                             'n' => InputEvent::Next,
+                            's' => InputEvent::Skip,
                             'h' => InputEvent::Hint,
                             'l' => break InputEvent::List,
                             'q' => break InputEvent::Quit,
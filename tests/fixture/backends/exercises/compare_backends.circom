@@ -0,0 +1,12 @@
+pragma circom 2.1.6;
+
+// Prove the same circuit with Groth16 and PLONK and compare them.
+template Multiplier() {
+    signal input a;
+    signal input b;
+    signal output c;
+
+    c <== a * b;
+}
+
+component main = Multiplier();
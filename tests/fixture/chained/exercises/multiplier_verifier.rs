@@ -0,0 +1,35 @@
+// Runs after the proof of `multiplier.circom` was verified with `snarkjs`.
+// The paths of the generated files are passed in environment variables.
+
+use std::{env, fs, process::exit};
+
+fn read(var: &str) -> String {
+    let path = env::var(var).unwrap_or_else(|_| {
+        eprintln!("The environment variable {var} isn't set");
+        exit(1);
+    });
+
+    fs::read_to_string(&path).unwrap_or_else(|e| {
+        eprintln!("Failed to read {path}: {e}");
+        exit(1);
+    })
+}
+
+fn main() {
+    let vkey = read("ZKLINGS_VKEY");
+    let proof = read("ZKLINGS_PROOF");
+    let public = read("ZKLINGS_PUBLIC");
+
+    if !vkey.contains("\"groth16\"") || !proof.contains("\"groth16\"") {
+        eprintln!("Expected a Groth16 verification key and proof");
+        exit(1);
+    }
+
+    // The public output `c` of `3 * 11`.
+    if !public.contains("\"33\"") {
+        eprintln!("Expected the public signal 33, got {public}");
+        exit(1);
+    }
+
+    println!("Verified the proof of 3 * 11 = 33");
+}
@@ -0,0 +1,5 @@
+fn main() {
+    let numbers = std::fs::read_to_string("exercises/data/numbers.txt").unwrap();
+    let sum: u64 = numbers.lines().map(|line| line.parse::<u64>().unwrap()).sum();
+    println!("{sum}");
+}
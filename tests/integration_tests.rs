@@ -1,4 +1,5 @@
 use assert_cmd::prelude::*;
+use predicates::prelude::PredicateBooleanExt;
 use std::process::Command;
 
 #[test]
@@ -132,3 +133,381 @@ fn run_single_test_success_with_output() {
         .code(0)
         .stdout(predicates::str::contains("THIS TEST TOO SHALL PASS"));
 }
+
+#[cfg(feature = "circom")]
+#[test]
+fn plan_chained_exercise() {
+    Command::cargo_bin("zklings")
+        .unwrap()
+        .args(["plan", "multiplier"])
+        .current_dir("tests/fixture/chained/")
+        .assert()
+        .code(0)
+        .stdout(
+            predicates::str::is_match(
+                "(?s)circom multiplier.circom.*snarkjs groth16 verify.*cargo build.*--bin multiplier_verifier.*ZKLINGS_PROOF=",
+            )
+            .unwrap(),
+        );
+}
+
+#[cfg(feature = "circom")]
+#[test]
+fn plan_external_proof_exercise() {
+    Command::cargo_bin("zklings")
+        .unwrap()
+        .args(["plan", "multiplier"])
+        .current_dir("tests/fixture/external_proof/")
+        .assert()
+        .code(0)
+        .stdout(
+            predicates::str::is_match(
+                "(?s)circom multiplier.circom.*snarkjs groth16 setup.*snarkjs zkey export verificationkey.*snarkjs groth16 verify.*public.json.*proof.json",
+            )
+            .unwrap(),
+        );
+}
+
+#[test]
+fn run_in_project_dir() {
+    Command::cargo_bin("zklings")
+        .unwrap()
+        .args(["--project", "fixture/success", "run", "compSuccess"])
+        .current_dir("tests/")
+        .assert()
+        .success();
+
+    Command::cargo_bin("zklings")
+        .unwrap()
+        .args(["run", "compFailure", "--project", "tests/fixture/failure"])
+        .assert()
+        .code(1);
+
+    // The answers file is relative to the directory in which zklings was started.
+    let dir = tempfile::tempdir().unwrap();
+    let dir = dir.path();
+    std::fs::write(dir.join("answers.toml"), "compSuccess = \"42\"\n").unwrap();
+    let project = std::fs::canonicalize("tests/fixture/success").unwrap();
+    Command::cargo_bin("zklings")
+        .unwrap()
+        .arg("--project")
+        .arg(&project)
+        .args(["run-once", "compSuccess", "--answers", "answers.toml"])
+        .current_dir(dir)
+        .assert()
+        .success();
+}
+
+#[cfg(feature = "circom")]
+#[test]
+fn plan_backends_exercise() {
+    Command::cargo_bin("zklings")
+        .unwrap()
+        .args(["plan", "compare_backends"])
+        .current_dir("tests/fixture/backends/")
+        .assert()
+        .code(0)
+        .stdout(
+            predicates::str::is_match(
+                "(?s)circom compare_backends.circom.*snarkjs wtns calculate.*snarkjs groth16 setup.*snarkjs groth16 prove.*snarkjs groth16 verify.*snarkjs plonk setup.*snarkjs plonk prove.*snarkjs plonk verify",
+            )
+            .unwrap(),
+        );
+}
+
+#[test]
+fn package_contains_referenced_files() {
+    let output_dir = tempfile::tempdir().unwrap();
+    let output = output_dir.path().join("package.tar.gz");
+
+    Command::cargo_bin("zklings")
+        .unwrap()
+        .args(["package", "sum_numbers", "--output"])
+        .arg(&output)
+        .current_dir("tests/fixture/package/")
+        .assert()
+        .success();
+
+    let list = Command::new("tar")
+        .arg("-tzf")
+        .arg(&output)
+        .output()
+        .unwrap();
+    assert!(list.status.success());
+    let list = String::from_utf8(list.stdout).unwrap();
+    let files = list.lines().collect::<Vec<_>>();
+
+    for file in [
+        "sum_numbers/exercises/data/sum_numbers.rs",
+        "sum_numbers/solutions/data/sum_numbers.rs",
+        "sum_numbers/exercises/data/README.md",
+        "sum_numbers/exercises/data/numbers.txt",
+        "sum_numbers/info.toml",
+        "sum_numbers/Cargo.toml",
+        "sum_numbers/VERSIONS.txt",
+        "sum_numbers/SHA256SUMS",
+    ] {
+        assert!(files.contains(&file), "{file} missing in {files:?}");
+    }
+    assert!(!list.contains("unrelated"));
+}
+
+#[test]
+fn run_once_never_reads_stdin() {
+    // The stdin pipe stays open. Reading from it would block until the timeout.
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_zklings"))
+        .args(["run-once", "sum_numbers"])
+        .current_dir("tests/fixture/package/")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    let _stdin = child.stdin.take();
+
+    let start = std::time::Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        if start.elapsed() > std::time::Duration::from_secs(120) {
+            child.kill().unwrap();
+            panic!("`run-once` blocked");
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    };
+    assert!(status.success());
+
+    let mut stdout = String::new();
+    std::io::Read::read_to_string(&mut child.stdout.take().unwrap(), &mut stdout).unwrap();
+    assert!(stdout.contains("6"));
+    assert!(!stdout.contains("Welcome"));
+
+    Command::cargo_bin("zklings")
+        .unwrap()
+        .args(["run-once", "compFailure"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn filtered_run_keeps_exercise_pending() {
+    // The state file is written in the project. Therefore, a copy of the fixture is used.
+    let project_dir = tempfile::tempdir().unwrap();
+    let project = project_dir.path();
+    std::fs::create_dir_all(project.join("exercises")).unwrap();
+    for file in [
+        "Cargo.toml",
+        "info.toml",
+        "exercises/compSuccess.rs",
+        "exercises/testSuccess.rs",
+    ] {
+        std::fs::copy(
+            std::path::Path::new("tests/fixture/success").join(file),
+            project.join(file),
+        )
+        .unwrap();
+    }
+
+    let assert = Command::cargo_bin("zklings")
+        .unwrap()
+        .args(["run", "testSuccess", "--test", "passing"])
+        .current_dir(project)
+        .assert();
+    let state = std::fs::read_to_string(project.join(".zklings-state.txt")).unwrap_or_default();
+
+    assert
+        .success()
+        .stdout(predicates::str::contains("Only the filtered tests ran"));
+    // The done exercises follow the current exercise after an empty line.
+    let done = state.split("\n\n").nth(2).unwrap_or_default();
+    assert!(!done.lines().any(|name| name == "testSuccess"), "{state}");
+}
+
+#[test]
+fn junit_report_without_welcome_message() {
+    // A fresh project without a state file would show the welcome message.
+    let project_dir = tempfile::tempdir().unwrap();
+    let project = project_dir.path();
+    std::fs::create_dir_all(project.join("exercises")).unwrap();
+    for file in [
+        "Cargo.toml",
+        "exercises/compSuccess.rs",
+        "exercises/testSuccess.rs",
+    ] {
+        std::fs::copy(
+            std::path::Path::new("tests/fixture/success").join(file),
+            project.join(file),
+        )
+        .unwrap();
+    }
+    let info_file = std::fs::read_to_string("tests/fixture/success/info.toml").unwrap();
+    std::fs::write(
+        project.join("info.toml"),
+        format!("welcome_message = \"Welcome!\"\n{info_file}"),
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("zklings")
+        .unwrap()
+        .args(["verify", "--format", "junit"])
+        .current_dir(project)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let document = roxmltree::Document::parse(&stdout).unwrap();
+    assert_eq!(document.root_element().tag_name().name(), "testsuites");
+}
+
+#[test]
+fn retry_failed_reruns_remaining_failure() {
+    // Fixing an exercise changes the project. Therefore, a copy of the fixture is used.
+    let project_dir = tempfile::tempdir().unwrap();
+    let project = project_dir.path();
+    std::fs::create_dir_all(project.join("exercises")).unwrap();
+    for file in [
+        "Cargo.toml",
+        "info.toml",
+        "exercises/first.rs",
+        "exercises/second.rs",
+    ] {
+        std::fs::copy(
+            std::path::Path::new("tests/fixture/retry").join(file),
+            project.join(file),
+        )
+        .unwrap();
+    }
+
+    Command::cargo_bin("zklings")
+        .unwrap()
+        .arg("verify")
+        .current_dir(project)
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("Running exercises/first.rs")
+                .and(predicates::str::contains("Running exercises/second.rs")),
+        );
+
+    std::fs::write(project.join("exercises/first.rs"), "fn main() {}\n").unwrap();
+    Command::cargo_bin("zklings")
+        .unwrap()
+        .arg("retry-failed")
+        .current_dir(project)
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("Running exercises/first.rs")
+                .and(predicates::str::contains("Running exercises/second.rs")),
+        );
+
+    let assert = Command::cargo_bin("zklings")
+        .unwrap()
+        .arg("retry-failed")
+        .current_dir(project)
+        .assert();
+    assert.code(1).stdout(
+        predicates::str::contains("Running exercises/second.rs")
+            .and(predicates::str::contains("Running exercises/first.rs").not()),
+    );
+}
+
+#[test]
+fn doctor_checks_fixtures() {
+    Command::cargo_bin("zklings")
+        .unwrap()
+        .arg("doctor")
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "There is no `fixtures.toml` file",
+        ));
+}
+
+// Copy the fixture `tests/fixture/parallel` whose exercises are built by a pool of workers.
+fn parallel_project() -> tempfile::TempDir {
+    let project = tempfile::tempdir().unwrap();
+    for dir in ["exercises", "solutions"] {
+        std::fs::create_dir(project.path().join(dir)).unwrap();
+    }
+    for file in [
+        "Cargo.toml",
+        "info.toml",
+        "exercises/first.rs",
+        "exercises/second.rs",
+        "exercises/third.rs",
+        "solutions/first.rs",
+        "solutions/second.rs",
+        "solutions/third.rs",
+    ] {
+        std::fs::copy(
+            std::path::Path::new("tests/fixture/parallel").join(file),
+            project.path().join(file),
+        )
+        .unwrap();
+    }
+
+    project
+}
+
+// The first binary is built in the shared target directory, the others only by the workers.
+fn assert_built_by_workers(target_dir: &std::path::Path, bin_names: [&str; 3]) {
+    let workers_dir = target_dir.join("zklings-workers");
+    let built_by_worker = |bin_name: &str| {
+        ["0", "1"].into_iter().any(|worker| {
+            workers_dir
+                .join(worker)
+                .join("debug")
+                .join(bin_name)
+                .is_file()
+        })
+    };
+
+    assert!(target_dir.join("debug").join(bin_names[0]).is_file());
+    for bin_name in &bin_names[1..] {
+        assert!(built_by_worker(bin_name), "{bin_name}");
+        assert!(
+            !target_dir.join("debug").join(bin_name).exists(),
+            "{bin_name}"
+        );
+    }
+}
+
+#[test]
+fn parallel_verify_builds_in_worker_dirs() {
+    let project = parallel_project();
+
+    Command::cargo_bin("zklings")
+        .unwrap()
+        .args(["verify", "--parallel", "--jobs", "2"])
+        .current_dir(project.path())
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("Running exercises/second.rs")
+                .and(predicates::str::contains("Running exercises/third.rs")),
+        );
+
+    assert_built_by_workers(&project.path().join("target"), ["first", "second", "third"]);
+}
+
+#[test]
+fn parallel_solutions_build_in_worker_dirs() {
+    let project = parallel_project();
+
+    Command::cargo_bin("zklings")
+        .unwrap()
+        .args(["dev", "verify-solutions", "--parallel", "--jobs", "2"])
+        .current_dir(project.path())
+        .assert()
+        .success();
+
+    assert_built_by_workers(
+        &project.path().join("target"),
+        ["first_sol", "second_sol", "third_sol"],
+    );
+}